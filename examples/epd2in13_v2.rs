@@ -80,7 +80,9 @@ fn main() -> Result<(), std::io::Error> {
     display.set_rotation(DisplayRotation::Rotate270);
     draw_text(&mut display, "Rotate 270!", 5, 50);
 
-    epd2in13.update_frame(&mut spi, display.buffer(), &mut delay)?;
+    epd2in13
+        .update_frame(&mut spi, display.buffer(), &mut delay)
+        .unwrap();
     epd2in13
         .display_frame(&mut spi, &mut delay)
         .expect("display frame new graphics");
@@ -156,7 +158,9 @@ fn main() -> Result<(), std::io::Error> {
     }
 
     println!("Finished tests - going to sleep");
-    epd2in13.sleep(&mut spi, &mut delay)
+    epd2in13.sleep(&mut spi, &mut delay).unwrap();
+
+    Ok(())
 }
 
 fn draw_text(display: &mut Display2in13, text: &str, x: i32, y: i32) {
@@ -137,7 +137,14 @@ fn main() -> Result<(), std::io::Error> {
 
     // we used three colors, so we need to update both bw-buffer and chromatic-buffer
 
-    epd2in13.update_color_frame(&mut spi, display.bw_buffer(), display.chromatic_buffer())?;
+    epd2in13
+        .update_color_frame(
+            &mut spi,
+            display.bw_buffer(),
+            display.chromatic_buffer(),
+            &mut delay,
+        )
+        .unwrap();
     epd2in13
         .display_frame(&mut spi, &mut delay)
         .expect("display frame new graphics");
@@ -147,11 +154,20 @@ fn main() -> Result<(), std::io::Error> {
 
     // clear both bw buffer and chromatic buffer
     display.clear_buffer(TriColor::White);
-    epd2in13.update_color_frame(&mut spi, display.bw_buffer(), display.chromatic_buffer())?;
-    epd2in13.display_frame(&mut spi, &mut delay)?;
+    epd2in13
+        .update_color_frame(
+            &mut spi,
+            display.bw_buffer(),
+            display.chromatic_buffer(),
+            &mut delay,
+        )
+        .unwrap();
+    epd2in13.display_frame(&mut spi, &mut delay).unwrap();
 
     println!("Finished tests - going to sleep");
-    epd2in13.sleep(&mut spi, &mut delay)
+    epd2in13.sleep(&mut spi, &mut delay).unwrap();
+
+    Ok(())
 }
 
 fn draw_text(display: &mut Display2in13bc, text: &str, x: i32, y: i32) {
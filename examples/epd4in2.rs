@@ -79,7 +79,9 @@ fn main() -> Result<(), std::io::Error> {
     display.set_rotation(DisplayRotation::Rotate270);
     draw_text(&mut display, "Rotate 270!", 5, 50);
 
-    epd4in2.update_frame(&mut spi, display.buffer(), &mut delay)?;
+    epd4in2
+        .update_frame(&mut spi, display.buffer(), &mut delay)
+        .unwrap();
     epd4in2
         .display_frame(&mut spi, &mut delay)
         .expect("display frame new graphics");
@@ -127,7 +129,9 @@ fn main() -> Result<(), std::io::Error> {
 
     // a moving `Hello World!`
     let limit = 10;
-    epd4in2.set_lut(&mut spi, Some(RefreshLut::Quick)).unwrap();
+    epd4in2
+        .set_lut(&mut spi, Some(RefreshLut::Quick), &mut delay)
+        .unwrap();
     epd4in2.clear_frame(&mut spi, &mut delay).unwrap();
     for i in 0..limit {
         //println!("Moving Hello World. Loop {} from {}", (i + 1), limit);
@@ -145,7 +149,9 @@ fn main() -> Result<(), std::io::Error> {
     }
 
     println!("Finished tests - going to sleep");
-    epd4in2.sleep(&mut spi, &mut delay)
+    epd4in2.sleep(&mut spi, &mut delay).unwrap();
+
+    Ok(())
 }
 
 fn draw_text(display: &mut Display4in2, text: &str, x: i32, y: i32) {
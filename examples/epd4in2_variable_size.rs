@@ -1,5 +1,4 @@
 #![deny(warnings)]
-#![deny(warnings)]
 
 use embedded_graphics::{
     mono_font::MonoTextStyleBuilder,
@@ -67,9 +66,10 @@ fn main() -> Result<(), std::io::Error> {
 
     println!("Test all the rotations");
 
-    let (x, y, width, height) = (50, 50, 250, 250);
+    // x and width need to be multiples of 8
+    let (x, y, width, height) = (48, 48, 248, 248);
 
-    let mut buffer = [epd4in2::DEFAULT_BACKGROUND_COLOR.get_byte_value(); 62500]; //250*250
+    let mut buffer = [epd4in2::DEFAULT_BACKGROUND_COLOR.get_byte_value(); 248 / 8 * 248];
     let mut display = VarDisplay::new(width, height, &mut buffer);
     display.set_rotation(DisplayRotation::Rotate0);
     draw_text(&mut display, "Rotate 0!", 5, 50);
@@ -84,7 +84,7 @@ fn main() -> Result<(), std::io::Error> {
     draw_text(&mut display, "Rotate 270!", 5, 50);
 
     epd4in2
-        .update_partial_frame(&mut spi, display.buffer(), x, y, width, height)
+        .update_partial_frame(&mut spi, display.buffer(), x, y, width, height, &mut delay)
         .unwrap();
     epd4in2
         .display_frame(&mut spi, &mut delay)
@@ -140,7 +140,7 @@ fn main() -> Result<(), std::io::Error> {
         draw_text(&mut display, "  Hello World! ", 5 + i * 12, 50);
 
         epd4in2
-            .update_partial_frame(&mut spi, display.buffer(), x, y, width, height)
+            .update_partial_frame(&mut spi, display.buffer(), x, y, width, height, &mut delay)
             .unwrap();
         epd4in2
             .display_frame(&mut spi, &mut delay)
@@ -150,7 +150,9 @@ fn main() -> Result<(), std::io::Error> {
     }
 
     println!("Finished tests - going to sleep");
-    epd4in2.sleep(&mut spi, &mut delay)
+    epd4in2.sleep(&mut spi, &mut delay).unwrap();
+
+    Ok(())
 }
 
 fn draw_text(display: &mut VarDisplay, text: &str, x: i32, y: i32) {
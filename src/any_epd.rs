@@ -0,0 +1,643 @@
+//! One driver for several panels, chosen at runtime
+//!
+//! Firmware which is sold with different panels can't pick the driver module at compile
+//! time. [AnyEpd] holds one of the supported drivers and forwards the [WaveshareDisplay],
+//! [PartialUpdate] and [LutSelection] calls to it. The panel is selected with a [PanelKind],
+//! which can be parsed from a config byte or string, and [PanelKind::capabilities] tells
+//! how large the frame buffer has to be.
+//!
+//! [AnyEpd] implements [QuickRefresh], [StreamingFrame], [ExternalTemperature] and [Deinit],
+//! so it works with [Ghosting](crate::ghosting::Ghosting) and `paged::PagedDisplay`.
+//! [WaveshareDisplay], [PartialUpdate], [LutSelection] and the traits built on them can't be
+//! implemented: the size constants of [PanelInfo] are fixed at compile time and
+//! [new](WaveshareDisplay::new) doesn't take a [PanelKind]. Their methods are inherent
+//! methods of [AnyEpd] instead.
+//!
+//! Example:
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
+//!use core::convert::TryFrom;
+//!use epd_waveshare::{any_epd::*, buffer_len, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!# let config_byte = 1;
+//!
+//!let kind = PanelKind::try_from(config_byte).unwrap_or(PanelKind::Gdeq0213b74);
+//!let mut epd = AnyEpd::new(kind, &mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// Large enough for the 122x250 panels
+//!let mut frame = [0xFF; buffer_len(122, 250)];
+//!let frame = &mut frame[..epd.capabilities().buffer_len()];
+//!frame[0] = 0x00;
+//!epd.update_and_display_frame(&mut spi, frame, &mut delay)?;
+//!epd.sleep(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+//!
+//! Functions only some of the panels have, like the grayscale mode of the GDEW0213T5D, are
+//! reached by matching on the variants of [AnyEpd].
+
+use core::convert::TryFrom;
+use core::fmt;
+use core::str::FromStr;
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::color::Color;
+use crate::epd2in13_GDEQ0213B74 as gdeq0213b74;
+use crate::epd2in13_GDEW0213T5D::Epd2in13_T5D;
+use crate::epd2in13_v2 as waveshare2in13v2;
+use crate::traits::{
+    Deinit, DriverError, ExternalTemperature, LutSelection, PanelInfo, PartialUpdate, QuickRefresh,
+    RefreshLut, StreamingFrame, WaveshareDisplay,
+};
+
+/// The panels [AnyEpd] can drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelKind {
+    /// Good Display GDEW0213T5D, see [epd2in13_GDEW0213T5D](crate::epd2in13_GDEW0213T5D)
+    Gdew0213t5d,
+    /// Good Display GDEQ0213B74 or GDEY0213B74, see
+    /// [epd2in13_GDEQ0213B74](crate::epd2in13_GDEQ0213B74)
+    Gdeq0213b74,
+    /// Waveshare 2.13" V2, see [epd2in13_v2](crate::epd2in13_v2)
+    Waveshare2in13V2,
+}
+
+/// Size and features of a [PanelKind]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Width in pixels, see [PanelInfo::WIDTH]
+    pub width: u32,
+    /// Height in pixels, see [PanelInfo::HEIGHT]
+    pub height: u32,
+    /// Bits per pixel of a frame, see [PanelInfo::COLOR_DEPTH]
+    pub color_depth: u8,
+    /// Whether 4 grayscale frames can be shown, see [GrayscaleDisplay](crate::traits::GrayscaleDisplay)
+    pub grayscale: bool,
+    /// Whether windows can be updated, see [PartialUpdate]
+    pub partial_update: bool,
+    /// Whether old and new frame can be sent for quick refreshes, see
+    /// [QuickRefresh](crate::traits::QuickRefresh)
+    pub quick_refresh: bool,
+}
+
+/// A config byte or string which doesn't name a [PanelKind]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownPanel;
+
+impl fmt::Display for UnknownPanel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown panel")
+    }
+}
+
+impl Capabilities {
+    /// Length of a full frame buffer, see [buffer_len](crate::buffer_len)
+    pub const fn buffer_len(&self) -> usize {
+        crate::buffer_len(self.width as usize, self.height as usize)
+    }
+}
+
+type T5dInfo = Epd2in13_T5D<(), (), (), (), (), ()>;
+type B74Info = gdeq0213b74::Epd2in13<(), (), (), (), (), ()>;
+type V2Info = waveshare2in13v2::Epd2in13<(), (), (), (), (), ()>;
+
+impl PanelKind {
+    /// All panels, in the order of their config bytes
+    pub const ALL: [PanelKind; 3] = [
+        PanelKind::Gdew0213t5d,
+        PanelKind::Gdeq0213b74,
+        PanelKind::Waveshare2in13V2,
+    ];
+
+    /// Size and features of the panel
+    pub const fn capabilities(self) -> Capabilities {
+        match self {
+            PanelKind::Gdew0213t5d => Capabilities {
+                width: T5dInfo::WIDTH,
+                height: T5dInfo::HEIGHT,
+                color_depth: T5dInfo::COLOR_DEPTH,
+                grayscale: true,
+                partial_update: true,
+                quick_refresh: true,
+            },
+            PanelKind::Gdeq0213b74 => Capabilities {
+                width: B74Info::WIDTH,
+                height: B74Info::HEIGHT,
+                color_depth: B74Info::COLOR_DEPTH,
+                grayscale: false,
+                partial_update: true,
+                quick_refresh: true,
+            },
+            PanelKind::Waveshare2in13V2 => Capabilities {
+                width: V2Info::WIDTH,
+                height: V2Info::HEIGHT,
+                color_depth: V2Info::COLOR_DEPTH,
+                grayscale: false,
+                partial_update: true,
+                quick_refresh: true,
+            },
+        }
+    }
+}
+
+/// Config bytes 0, 1 and 2, in the order of the variants
+impl TryFrom<u8> for PanelKind {
+    type Error = UnknownPanel;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        PanelKind::ALL
+            .get(usize::from(value))
+            .copied()
+            .ok_or(UnknownPanel)
+    }
+}
+
+/// The part numbers, ignoring case: `GDEW0213T5D`, `GDEQ0213B74` or `GDEY0213B74` and
+/// `2in13_v2`
+impl FromStr for PanelKind {
+    type Err = UnknownPanel;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let names: [(&str, PanelKind); 4] = [
+            ("GDEW0213T5D", PanelKind::Gdew0213t5d),
+            ("GDEQ0213B74", PanelKind::Gdeq0213b74),
+            ("GDEY0213B74", PanelKind::Gdeq0213b74),
+            ("2in13_v2", PanelKind::Waveshare2in13V2),
+        ];
+        names
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s.trim()))
+            .map(|&(_, kind)| kind)
+            .ok_or(UnknownPanel)
+    }
+}
+
+/// One of the drivers of [PanelKind], chosen at runtime
+pub enum AnyEpd<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// Driver of the GDEW0213T5D
+    Gdew0213t5d(Epd2in13_T5D<SPI, CS, BUSY, DC, RST, DELAY>),
+    /// Driver of the GDEQ0213B74
+    Gdeq0213b74(gdeq0213b74::Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>),
+    /// Driver of the Waveshare 2.13" V2
+    Waveshare2in13V2(waveshare2in13v2::Epd2in13<SPI, CS, BUSY, DC, RST, DELAY>),
+}
+
+macro_rules! dispatch {
+    ($self:expr, $epd:ident => $call:expr) => {
+        match $self {
+            AnyEpd::Gdew0213t5d($epd) => $call,
+            AnyEpd::Gdeq0213b74($epd) => $call,
+            AnyEpd::Waveshare2in13V2($epd) => $call,
+        }
+    };
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> AnyEpd<SPI, CS, BUSY, DC, RST, DELAY> {
+    /// The panel of the driver
+    pub fn kind(&self) -> PanelKind {
+        match self {
+            AnyEpd::Gdew0213t5d(_) => PanelKind::Gdew0213t5d,
+            AnyEpd::Gdeq0213b74(_) => PanelKind::Gdeq0213b74,
+            AnyEpd::Waveshare2in13V2(_) => PanelKind::Waveshare2in13V2,
+        }
+    }
+
+    /// Size and features of the panel, see [PanelKind::capabilities]
+    pub fn capabilities(&self) -> Capabilities {
+        self.kind().capabilities()
+    }
+
+    /// Width of the panel, see [WaveshareDisplay::width]
+    pub fn width(&self) -> u32 {
+        self.capabilities().width
+    }
+
+    /// Height of the panel, see [WaveshareDisplay::height]
+    pub fn height(&self) -> u32 {
+        self.capabilities().height
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Creates and initialises the driver of `kind`, see [WaveshareDisplay::new]
+    pub fn new(
+        kind: PanelKind,
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DriverError<SPI, CS, BUSY, DC, RST>> {
+        Ok(match kind {
+            PanelKind::Gdew0213t5d => {
+                AnyEpd::Gdew0213t5d(Epd2in13_T5D::new(spi, cs, busy, dc, rst, delay)?)
+            }
+            PanelKind::Gdeq0213b74 => {
+                AnyEpd::Gdeq0213b74(gdeq0213b74::Epd2in13::new(spi, cs, busy, dc, rst, delay)?)
+            }
+            PanelKind::Waveshare2in13V2 => AnyEpd::Waveshare2in13V2(
+                waveshare2in13v2::Epd2in13::new(spi, cs, busy, dc, rst, delay)?,
+            ),
+        })
+    }
+
+    /// See [WaveshareDisplay::sleep]
+    pub fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.sleep(spi, delay))
+    }
+
+    /// See [WaveshareDisplay::wake_up]
+    pub fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.wake_up(spi, delay))
+    }
+
+    /// See [WaveshareDisplay::set_background_color]
+    pub fn set_background_color(&mut self, color: Color) {
+        dispatch!(self, epd => epd.set_background_color(color))
+    }
+
+    /// See [WaveshareDisplay::background_color]
+    pub fn background_color(&self) -> &Color {
+        dispatch!(self, epd => epd.background_color())
+    }
+
+    /// See [WaveshareDisplay::update_frame]
+    pub fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.update_frame(spi, buffer, delay))
+    }
+
+    /// See [PartialUpdate::update_partial_frame]
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.update_partial_frame(spi, buffer, x, y, width, height, delay))
+    }
+
+    /// See [WaveshareDisplay::display_frame]
+    pub fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.display_frame(spi, delay))
+    }
+
+    /// See [WaveshareDisplay::update_and_display_frame]
+    pub fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.update_and_display_frame(spi, buffer, delay))
+    }
+
+    /// See [WaveshareDisplay::clear_frame]
+    pub fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.clear_frame(spi, delay))
+    }
+
+    /// See [LutSelection::set_lut]
+    pub fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh_rate: Option<RefreshLut>,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.set_lut(spi, refresh_rate, delay))
+    }
+
+    /// See [WaveshareDisplay::is_busy]
+    pub fn is_busy(&self) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.is_busy())
+    }
+
+    /// See [WaveshareDisplay::set_busy_timeout]
+    pub fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        dispatch!(self, epd => epd.set_busy_timeout(timeout_ms))
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.update_old_frame(spi, buffer, delay))
+    }
+
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.update_new_frame(spi, buffer, delay))
+    }
+
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.display_new_frame(spi, delay))
+    }
+
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.update_and_display_new_frame(spi, buffer, delay))
+    }
+
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.update_partial_old_frame(spi, buffer, x, y, width, height, delay))
+    }
+
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.update_partial_new_frame(spi, buffer, x, y, width, height, delay))
+    }
+
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.clear_partial_frame(spi, x, y, width, height, delay))
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>
+    for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn begin_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.begin_frame(spi, delay))
+    }
+
+    fn write_frame_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.write_frame_data(spi, data))
+    }
+
+    fn end_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.end_frame(spi, delay))
+    }
+
+    fn begin_base_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.begin_base_frame(spi, delay))
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> ExternalTemperature<SPI, CS, BUSY, DC, RST, DELAY>
+    for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn set_temperature(
+        &mut self,
+        spi: &mut SPI,
+        celsius: i8,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        dispatch!(self, epd => epd.set_temperature(spi, celsius, delay))
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Deinit<CS, BUSY, DC, RST>
+    for AnyEpd<SPI, CS, BUSY, DC, RST, DELAY>
+{
+    fn deinit(self) -> (CS, BUSY, DC, RST) {
+        dispatch!(self, epd => epd.deinit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::SimulatedPanel;
+
+    #[test]
+    fn parse_panel_kind() {
+        assert_eq!(PanelKind::try_from(0), Ok(PanelKind::Gdew0213t5d));
+        assert_eq!(PanelKind::try_from(2), Ok(PanelKind::Waveshare2in13V2));
+        assert_eq!(PanelKind::try_from(3), Err(UnknownPanel));
+        assert_eq!("gdey0213b74".parse(), Ok(PanelKind::Gdeq0213b74));
+        assert_eq!(" 2IN13_V2".parse(), Ok(PanelKind::Waveshare2in13V2));
+        assert_eq!("2in13".parse::<PanelKind>(), Err(UnknownPanel));
+    }
+
+    #[test]
+    fn capabilities() {
+        let t5d = PanelKind::Gdew0213t5d.capabilities();
+        assert_eq!((t5d.width, t5d.height, t5d.color_depth), (104, 212, 1));
+        assert!(t5d.grayscale);
+        assert_eq!(t5d.buffer_len(), 13 * 212);
+        let b74 = PanelKind::Gdeq0213b74.capabilities();
+        assert_eq!((b74.width, b74.height), (122, 250));
+        assert!(!b74.grayscale && b74.partial_update);
+        assert_eq!(PanelKind::Waveshare2in13V2.capabilities().width, 122);
+    }
+
+    #[test]
+    fn runtime_panels() {
+        for &kind in PanelKind::ALL.iter() {
+            let capabilities = kind.capabilities();
+            let (width, height) = (capabilities.width, capabilities.height);
+            let panel = match kind {
+                PanelKind::Gdew0213t5d => SimulatedPanel::uc8151(width, height),
+                _ => SimulatedPanel::ssd1680(width, height),
+            };
+            let (mut spi, mut delay) = (panel.spi(), panel.delay());
+            let mut epd = AnyEpd::new(
+                kind,
+                &mut spi,
+                panel.cs(),
+                panel.busy(),
+                panel.dc(),
+                panel.rst(),
+                &mut delay,
+            )
+            .unwrap();
+            assert_eq!(epd.kind(), kind);
+            assert_eq!((epd.width(), epd.height()), (width, height));
+
+            let mut buffer = std::vec![0xFF; capabilities.buffer_len()];
+            buffer[0] = 0x00;
+            epd.update_and_display_frame(&mut spi, &buffer, &mut delay)
+                .unwrap();
+            assert!(panel.image().is_black_window(0, 0, 8, 1));
+
+            epd.clear_frame(&mut spi, &mut delay).unwrap();
+            epd.update_partial_frame(&mut spi, &[0x00; 2 * 3], 16, 100, 16, 3, &mut delay)
+                .unwrap();
+            epd.display_frame(&mut spi, &mut delay).unwrap();
+            assert!(panel.image().is_black_window(16, 100, 16, 3));
+
+            epd.sleep(&mut spi, &mut delay).unwrap();
+            // the 2.13" V2 keeps DeepSleepMode::Normal of the Waveshare code by default
+            assert_eq!(panel.is_asleep(), kind != PanelKind::Waveshare2in13V2);
+            assert_eq!(panel.violations(), [], "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn runtime_panels_through_traits() {
+        use crate::color::TriColor;
+        use crate::simulator::{Delay, Pin, Spi};
+
+        fn quick_refresh<EPD>(epd: &mut EPD, spi: &mut Spi, delay: &mut Delay)
+        where
+            EPD: QuickRefresh<Spi, Pin, Pin, Pin, Pin, Delay>,
+        {
+            epd.update_partial_old_frame(spi, &[0xFF; 2 * 4], 32, 60, 16, 4, delay)
+                .unwrap();
+            epd.update_partial_new_frame(spi, &[0x00; 2 * 4], 32, 60, 16, 4, delay)
+                .unwrap();
+            epd.display_new_frame(spi, delay).unwrap();
+        }
+
+        for &kind in PanelKind::ALL.iter() {
+            let capabilities = kind.capabilities();
+            let (width, height) = (capabilities.width, capabilities.height);
+            let panel = match kind {
+                PanelKind::Gdew0213t5d => SimulatedPanel::uc8151(width, height),
+                _ => SimulatedPanel::ssd1680(width, height),
+            };
+            let (mut spi, mut delay) = (panel.spi(), panel.delay());
+            let mut epd = AnyEpd::new(
+                kind,
+                &mut spi,
+                panel.cs(),
+                panel.busy(),
+                panel.dc(),
+                panel.rst(),
+                &mut delay,
+            )
+            .unwrap();
+
+            let len = capabilities.buffer_len();
+            epd.set_temperature(&mut spi, 20, &mut delay).unwrap();
+            epd.update_frame_from_bytes(&mut spi, || std::iter::repeat(0xFF).take(len), &mut delay)
+                .unwrap();
+            epd.display_frame(&mut spi, &mut delay).unwrap();
+            assert!(panel.image().pixels().iter().all(|&p| p == TriColor::White));
+            assert_eq!(panel.temperature(), Some(20));
+
+            epd.set_lut(&mut spi, Some(RefreshLut::Quick), &mut delay)
+                .unwrap();
+            quick_refresh(&mut epd, &mut spi, &mut delay);
+            assert!(panel.image().is_black_window(32, 60, 16, 4), "{:?}", kind);
+            assert_eq!(panel.violations(), [], "{:?}", kind);
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Async driver API on top of [embedded-hal-async](https://docs.rs/embedded-hal-async)
+//!
+//! The async traits mirror [WaveshareDisplay](crate::prelude::WaveshareDisplay),
+//! [PartialUpdate](crate::prelude::PartialUpdate), [LutSelection](crate::prelude::LutSelection)
+//! and [QuickRefresh](crate::prelude::QuickRefresh), but await the busy pin through
+//! [Wait] instead of polling it and use async SPI writes.
+//! They are implemented by the same driver structs, with embedded-hal 1.0 pins and an
+//! async [SpiBus] and [DelayNs], and send exactly the same command sequences as the blocking
+//! drivers.
+//!
+//! Supported drivers:
+//!
+//! - [Epd2in13_T5D](crate::epd2in13_GDEW0213T5D::Epd2in13_T5D) (also [AsyncPartialUpdate],
+//!   [AsyncLutSelection] and [AsyncQuickRefresh])
+//! - [Ssd1680](crate::ssd1680::Ssd1680) and its presets
+//!   [epd2in13_v2](crate::epd2in13_v2::Epd2in13) and
+//!   [epd2in13_GDEQ0213B74](crate::epd2in13_GDEQ0213B74::Epd2in13) (also [AsyncPartialUpdate],
+//!   [AsyncLutSelection] and [AsyncQuickRefresh])
+//! - [Epd4in2](crate::epd4in2::Epd4in2) (also [AsyncPartialUpdate], [AsyncLutSelection] and
+//!   [AsyncQuickRefresh])
+//! - [Epd5in65f](crate::epd5in65f::Epd5in65f)
+//! - [Epd7in5 HD](crate::epd7in5_hd::Epd7in5)
+//!
+//! The other drivers are blocking only.
+//!
+//! Only available with the `async` feature.
+
+use crate::error::EpdError;
+use crate::traits::RefreshLut;
+use core::marker::Sized;
+use embedded_hal_1::digital::{self, InputPin, OutputPin};
+use embedded_hal_1::spi;
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiBus};
+
+/// The [EpdError] returned by the async drivers, async version of
+/// [DriverError](crate::traits::DriverError)
+pub type AsyncDriverError<SPI, CS, BUSY, DC, RST> = EpdError<
+    <SPI as spi::ErrorType>::Error,
+    <BUSY as digital::ErrorType>::Error,
+    <CS as digital::ErrorType>::Error,
+    <DC as digital::ErrorType>::Error,
+    <RST as digital::ErrorType>::Error,
+>;
+
+/// All the functions to interact with the EPDs, async version of
+/// [WaveshareDisplay](crate::prelude::WaveshareDisplay)
+#[allow(async_fn_in_trait)]
+pub trait AsyncWaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// The Color Type used by the Display
+    type DisplayColor;
+    /// Creates a new driver from a SPI peripheral, CS Pin, Busy InputPin, DC
+    ///
+    /// This already initialises the device.
+    async fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, AsyncDriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        Self: Sized;
+
+    /// Let the device enter deep-sleep mode to save power.
+    ///
+    /// The deep sleep mode returns to standby with a hardware reset.
+    async fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Wakes the device up from sleep
+    ///
+    /// Also reintialises the device if necessary.
+    async fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Sets the backgroundcolor for various commands like [clear_frame](AsyncWaveshareDisplay::clear_frame)
+    fn set_background_color(&mut self, color: Self::DisplayColor);
+
+    /// Get current background color
+    fn background_color(&self) -> &Self::DisplayColor;
+
+    /// Get the width of the display
+    fn width(&self) -> u32;
+
+    /// Get the height of the display
+    fn height(&self) -> u32;
+
+    /// Transmit a full frame to the SRAM of the EPD
+    async fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Displays the frame data from SRAM
+    async fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Provide a combined update&display and save some time (skipping a busy check in between)
+    async fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Clears the frame buffer on the EPD with the declared background color
+    async fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Checks if the display is busy transmitting data
+    ///
+    /// Takes `&mut self` because embedded-hal 1.0 pins are read through a mutable reference.
+    fn is_busy(&mut self) -> Result<bool, AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Sets the maximum time in ms to wait for the display to become idle, `None` waits forever
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>);
+}
+
+/// Async version of [PartialUpdate](crate::prelude::PartialUpdate)
+#[allow(async_fn_in_trait)]
+pub trait AsyncPartialUpdate<SPI, CS, BUSY, DC, RST, DELAY>:
+    AsyncWaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Transmits partial data to the SRAM of the EPD
+    ///
+    /// Same requirements as [update_partial_frame](crate::prelude::PartialUpdate::update_partial_frame).
+    #[allow(clippy::too_many_arguments)]
+    async fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Async version of [LutSelection](crate::prelude::LutSelection)
+#[allow(async_fn_in_trait)]
+pub trait AsyncLutSelection<SPI, CS, BUSY, DC, RST, DELAY>:
+    AsyncWaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Loads the LUT of the given refresh mode, see
+    /// [set_lut](crate::prelude::LutSelection::set_lut)
+    async fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh_mode: Option<RefreshLut>,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Async version of [QuickRefresh](crate::prelude::QuickRefresh)
+#[allow(async_fn_in_trait)]
+pub trait AsyncQuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    /// Updates the old frame.
+    async fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Updates the new frame.
+    async fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Displays the new frame
+    async fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Updates and displays the new frame.
+    async fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Updates the old frame for a portion of the display.
+    #[allow(clippy::too_many_arguments)]
+    async fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Updates the new frame for a portion of the display.
+    #[allow(clippy::too_many_arguments)]
+    async fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Clears the partial frame buffer on the EPD with the declared background color
+    #[allow(clippy::too_many_arguments)]
+    async fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>>;
+}
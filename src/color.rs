@@ -66,6 +66,21 @@ pub enum OctColor {
     HiZ = 0x07,
 }
 
+/// For the Displays supporting 4 grayscales (e.g. GDEW0213T5D)
+///
+/// The discriminant is the 2 bit value stored per pixel in the display buffer
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum QuadColor {
+    /// Black color
+    Black = 0x00,
+    /// Dark gray color
+    DarkGray = 0x01,
+    /// Light gray color
+    LightGray = 0x02,
+    /// White color
+    White = 0x03,
+}
+
 impl From<()> for OctColor {
     fn from(_: ()) -> OctColor {
         OctColor::White
@@ -182,6 +197,80 @@ impl OctColor {
         }
     }
 }
+impl QuadColor {
+    /// Gets the 2 bit representation of the color as stored in the display buffer
+    pub fn get_bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Gets a full byte (4 pixels) of this color
+    pub fn get_byte_value(self) -> u8 {
+        self.get_bits() * 0x55
+    }
+
+    /// Takes the lower 2 bits and converts them to a QuadColor
+    pub fn from_bits(bits: u8) -> QuadColor {
+        match bits & 0x03 {
+            0x00 => QuadColor::Black,
+            0x01 => QuadColor::DarkGray,
+            0x02 => QuadColor::LightGray,
+            _ => QuadColor::White,
+        }
+    }
+
+    /// Converts to a 8 bit luma value
+    pub fn luma(self) -> u8 {
+        self.get_bits() * 0x55
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl PixelColor for QuadColor {
+    type Raw = embedded_graphics_core::pixelcolor::raw::RawU2;
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::raw::RawU2> for QuadColor {
+    fn from(b: embedded_graphics_core::pixelcolor::raw::RawU2) -> Self {
+        use embedded_graphics_core::prelude::RawData;
+        QuadColor::from_bits(b.into_inner())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<BinaryColor> for QuadColor {
+    fn from(b: BinaryColor) -> QuadColor {
+        match b {
+            BinaryColor::On => QuadColor::Black,
+            BinaryColor::Off => QuadColor::White,
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Gray2> for QuadColor {
+    fn from(g: embedded_graphics_core::pixelcolor::Gray2) -> QuadColor {
+        use embedded_graphics_core::pixelcolor::GrayColor as _;
+        QuadColor::from_bits(g.luma())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<QuadColor> for embedded_graphics_core::pixelcolor::Gray2 {
+    fn from(g: QuadColor) -> Self {
+        Self::new(g.get_bits())
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl From<embedded_graphics_core::pixelcolor::Gray8> for QuadColor {
+    fn from(g: embedded_graphics_core::pixelcolor::Gray8) -> QuadColor {
+        use embedded_graphics_core::pixelcolor::GrayColor as _;
+        // round to the nearest of the 4 levels (0x00, 0x55, 0xAA, 0xFF)
+        QuadColor::from_bits(((u16::from(g.luma()) + 0x2A) / 0x55) as u8)
+    }
+}
+
 //TODO: Rename get_bit_value to bit() and get_byte_value to byte() ?
 
 impl Color {
@@ -266,7 +355,7 @@ mod tests {
     // test all values aside from 0 and 1 which all should panic
     #[test]
     fn from_u8_panic() {
-        for val in 2..=u8::max_value() {
+        for val in 2..=u8::MAX {
             extern crate std;
             let result = std::panic::catch_unwind(|| Color::from(val));
             assert!(result.is_err());
@@ -285,6 +374,26 @@ mod tests {
         assert_eq!(Color::from(1u8).get_bit_value(), 1u8);
     }
 
+    #[test]
+    fn gray_bits() {
+        for bits in 0..4 {
+            assert_eq!(QuadColor::from_bits(bits).get_bits(), bits);
+        }
+        assert_eq!(QuadColor::White.get_byte_value(), 0xff);
+        assert_eq!(QuadColor::LightGray.get_byte_value(), 0b1010_1010);
+        assert_eq!(QuadColor::Black.get_byte_value(), 0x00);
+    }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn gray_from_gray8() {
+        use embedded_graphics_core::pixelcolor::Gray8;
+        assert_eq!(QuadColor::from(Gray8::new(0x00)), QuadColor::Black);
+        assert_eq!(QuadColor::from(Gray8::new(0x50)), QuadColor::DarkGray);
+        assert_eq!(QuadColor::from(Gray8::new(0xB0)), QuadColor::LightGray);
+        assert_eq!(QuadColor::from(Gray8::new(0xF0)), QuadColor::White);
+    }
+
     #[test]
     fn test_oct() {
         let left = OctColor::Red;
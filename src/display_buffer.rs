@@ -0,0 +1,314 @@
+//! Const-generic full size buffer behind the display buffers of the drivers
+
+use crate::color::{OctColor, QuadColor, TriColor};
+use crate::graphics::{
+    ColorFormat, DirtyRegion, Display, DisplayRotation, OctDisplay, QuadDisplay, TriDisplay,
+};
+use core::marker::PhantomData;
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_graphics_core::prelude::*;
+
+/// Full size buffer of a `WIDTH` x `HEIGHT` display with pixels of the [ColorFormat] `C`
+///
+/// The drivers come with aliases of it (e.g. [Display4in2](crate::epd4in2::Display4in2)), a
+/// display without one, e.g. a new [Panel](crate::ssd1680::Panel), names it directly.
+///
+/// `BYTES` is the length of the buffer, as stable Rust can't compute it from the other
+/// parameters:
+///
+/// - [BinaryColor]: [buffer_len](crate::buffer_len)`(WIDTH, HEIGHT)`
+/// - [TriColor]: `2 * buffer_len(WIDTH, HEIGHT)`, the black/white plane followed by the
+///   chromatic one
+/// - [QuadColor]: [gray_buffer_len](crate::gray_buffer_len)`(WIDTH, HEIGHT)`
+/// - [OctColor]: `WIDTH / 2 * HEIGHT`, rounded up per row for odd widths
+///
+/// Other lengths fail to compile.
+///
+/// [OriginDimensions::size] is the unrotated size of the display, or the rotated one with
+/// `ROTATED_SIZE`.
+///
+/// ```
+/// use epd_waveshare::buffer_len;
+/// use epd_waveshare::color::TriColor;
+/// use epd_waveshare::graphics::{Display, DisplayBuffer, TriDisplay};
+///
+/// /// Buffer of a 128x296 black/white display
+/// type Display2in9 = DisplayBuffer<128, 296, { buffer_len(128, 296) }>;
+/// /// Buffer of a 128x296 black/white/red display
+/// type Display2in9Red = DisplayBuffer<128, 296, { 2 * buffer_len(128, 296) }, TriColor>;
+///
+/// let display = Display2in9::default();
+/// assert_eq!(display.buffer().len(), 16 * 296);
+/// let display = Display2in9Red::default();
+/// assert_eq!(display.buffer().len(), 2 * 16 * 296);
+/// ```
+pub struct DisplayBuffer<
+    const WIDTH: u32,
+    const HEIGHT: u32,
+    const BYTES: usize,
+    C = BinaryColor,
+    const ROTATED_SIZE: bool = false,
+> {
+    buffer: [u8; BYTES],
+    rotation: DisplayRotation,
+    dirty: DirtyRegion,
+    color: PhantomData<C>,
+}
+
+impl<
+        const WIDTH: u32,
+        const HEIGHT: u32,
+        const BYTES: usize,
+        C: ColorFormat,
+        const ROTATED_SIZE: bool,
+    > Default for DisplayBuffer<WIDTH, HEIGHT, BYTES, C, ROTATED_SIZE>
+{
+    fn default() -> Self {
+        const {
+            let row = (WIDTH as usize * C::BITS_PER_PIXEL).div_ceil(8);
+            assert!(
+                BYTES == C::PLANES * row * HEIGHT as usize,
+                "wrong buffer length"
+            );
+        }
+        DisplayBuffer {
+            buffer: [C::white_byte(); BYTES],
+            rotation: DisplayRotation::default(),
+            dirty: DirtyRegion::new(),
+            color: PhantomData,
+        }
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, C, const ROTATED_SIZE: bool>
+    OriginDimensions for DisplayBuffer<WIDTH, HEIGHT, BYTES, C, ROTATED_SIZE>
+{
+    fn size(&self) -> Size {
+        match self.rotation {
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 if ROTATED_SIZE => {
+                Size::new(HEIGHT, WIDTH)
+            }
+            _ => Size::new(WIDTH, HEIGHT),
+        }
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, const ROTATED_SIZE: bool> DrawTarget
+    for DisplayBuffer<WIDTH, HEIGHT, BYTES, BinaryColor, ROTATED_SIZE>
+{
+    type Color = BinaryColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.draw_helper(WIDTH, HEIGHT, pixel)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, const ROTATED_SIZE: bool> Display
+    for DisplayBuffer<WIDTH, HEIGHT, BYTES, BinaryColor, ROTATED_SIZE>
+{
+    fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn get_mut_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    fn buffer_size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+
+    fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    fn dirty_region(&self) -> DirtyRegion {
+        self.dirty
+    }
+
+    fn set_dirty_region(&mut self, region: DirtyRegion) {
+        self.dirty = region;
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, const ROTATED_SIZE: bool> DrawTarget
+    for DisplayBuffer<WIDTH, HEIGHT, BYTES, TriColor, ROTATED_SIZE>
+{
+    type Color = TriColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.draw_helper_tri(WIDTH, HEIGHT, pixel)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, const ROTATED_SIZE: bool> TriDisplay
+    for DisplayBuffer<WIDTH, HEIGHT, BYTES, TriColor, ROTATED_SIZE>
+{
+    fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn get_mut_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    fn buffer_size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+
+    fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    fn chromatic_offset(&self) -> usize {
+        BYTES / 2
+    }
+
+    fn bw_buffer(&self) -> &[u8] {
+        &self.buffer[..BYTES / 2]
+    }
+
+    fn chromatic_buffer(&self) -> &[u8] {
+        &self.buffer[BYTES / 2..]
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, const ROTATED_SIZE: bool> DrawTarget
+    for DisplayBuffer<WIDTH, HEIGHT, BYTES, OctColor, ROTATED_SIZE>
+{
+    type Color = OctColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.draw_helper(WIDTH, HEIGHT, pixel)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, const ROTATED_SIZE: bool> OctDisplay
+    for DisplayBuffer<WIDTH, HEIGHT, BYTES, OctColor, ROTATED_SIZE>
+{
+    fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn get_mut_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    fn buffer_size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+
+    fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, const ROTATED_SIZE: bool> DrawTarget
+    for DisplayBuffer<WIDTH, HEIGHT, BYTES, QuadColor, ROTATED_SIZE>
+{
+    type Color = QuadColor;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for pixel in pixels {
+            self.draw_helper(WIDTH, HEIGHT, pixel)?;
+        }
+        Ok(())
+    }
+}
+
+impl<const WIDTH: u32, const HEIGHT: u32, const BYTES: usize, const ROTATED_SIZE: bool> QuadDisplay
+    for DisplayBuffer<WIDTH, HEIGHT, BYTES, QuadColor, ROTATED_SIZE>
+{
+    fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn get_mut_buffer(&mut self) -> &mut [u8] {
+        &mut self.buffer
+    }
+
+    fn buffer_size(&self) -> Size {
+        Size::new(WIDTH, HEIGHT)
+    }
+
+    fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_len;
+
+    #[test]
+    fn display_buffer_formats() {
+        use crate::color::{OctColor, TriColor};
+
+        let mut display = DisplayBuffer::<16, 2, { buffer_len(16, 2) }>::default();
+        let _ = Pixel(Point::new(9, 1), BinaryColor::On).draw(&mut display);
+        assert_eq!(display.buffer(), [0xff, 0xff, 0xff, 0xbf]);
+        assert!(!display.dirty_region().is_empty());
+        display.set_rotation(DisplayRotation::Rotate90);
+        assert_eq!(display.size(), Size::new(16, 2));
+
+        let mut display =
+            DisplayBuffer::<16, 2, { buffer_len(16, 2) }, BinaryColor, true>::default();
+        display.set_rotation(DisplayRotation::Rotate90);
+        assert_eq!(display.size(), Size::new(2, 16));
+        display.set_rotation(DisplayRotation::Rotate180);
+        assert_eq!(display.size(), Size::new(16, 2));
+
+        let mut display = DisplayBuffer::<16, 2, { 2 * buffer_len(16, 2) }, TriColor>::default();
+        let _ = Pixel(Point::new(9, 1), TriColor::Chromatic).draw(&mut display);
+        assert_eq!(display.bw_buffer(), [0xff; 4]);
+        assert_eq!(display.chromatic_buffer(), [0xff, 0xff, 0xff, 0xbf]);
+
+        let mut display = DisplayBuffer::<4, 1, 2, OctColor>::default();
+        let _ = Pixel(Point::new(2, 0), OctColor::Black).draw(&mut display);
+        let white = OctColor::colors_byte(OctColor::White, OctColor::White);
+        let black = OctColor::colors_byte(OctColor::Black, OctColor::White);
+        assert_eq!(display.buffer(), [white, black]);
+    }
+}
@@ -0,0 +1,518 @@
+//! Dithering of true color and grayscale images to the few colors of the displays
+//!
+//! [Dithered] wraps a [Display](crate::graphics::Display), [TriDisplay](crate::graphics::TriDisplay),
+//! [OctDisplay](crate::graphics::OctDisplay) or any other draw target with a [Palette] and accepts
+//! [Rgb888], [Gray8](embedded_graphics_core::pixelcolor::Gray8) or any other color convertible
+//! to [Rgb888]. Every pixel is mapped to the nearest color of the palette and the remaining error is
+//! spread to the neighbouring pixels with one of the [Dithering] algorithms.
+//!
+//! Error diffusion needs the pixels in the order of the rows, as images and filled areas are drawn
+//! (`fill_contiguous`). It only keeps the errors of the next one or two rows in a buffer provided by
+//! the caller, see [Dithering::buffer_len]. Pixels drawn one by one (`draw_iter`) get the nearest
+//! color or, with [Dithering::Bayer], the ordered dithering which doesn't depend on the order.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!use embedded_graphics::{image::{Image, ImageRaw}, pixelcolor::Rgb888, prelude::*};
+//!use epd_waveshare::{dither::{Dithered, Dithering, Palette}, epd5in65f::*, prelude::*};
+//!
+//!# let data = [0u8; 3 * 64 * 64];
+//!let image: ImageRaw<Rgb888> = ImageRaw::new(&data, 64);
+//!
+//!let mut display = Display5in65f::default();
+//!let mut errors = [0; Dithering::FloydSteinberg.buffer_len(64, OctColor::CHANNELS)];
+//!let mut dithered = Dithered::new(&mut display, Dithering::FloydSteinberg, &mut errors);
+//!let _ = Image::new(&image, Point::zero()).draw(&mut dithered);
+//!```
+
+use crate::color::{OctColor, QuadColor, TriColor};
+use core::marker::PhantomData;
+use embedded_graphics_core::{
+    pixelcolor::{BinaryColor, Gray8, GrayColor, Rgb888},
+    prelude::*,
+    primitives::Rectangle,
+};
+
+/// Unused columns left and right of the error rows, so the errors can be spread without
+/// checking for the image borders
+const MARGIN_LEFT: usize = 1;
+const MARGIN_RIGHT: usize = 2;
+
+/// 4x4 Bayer threshold matrix
+const BAYER: [[i16; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+/// Dithering algorithms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dithering {
+    /// No dithering, every pixel gets the nearest color of the palette
+    None,
+    /// Floyd–Steinberg error diffusion to the current and the next row
+    FloydSteinberg,
+    /// Atkinson error diffusion to the current and the next two rows
+    ///
+    /// Only spreads 3/4 of the error, which keeps more contrast but loses details in very
+    /// light and dark areas.
+    Atkinson,
+    /// Ordered dithering with a 4x4 Bayer matrix, doesn't need an error buffer
+    Bayer,
+}
+
+impl Dithering {
+    /// Number of rows kept in the error buffer
+    const fn rows(self) -> usize {
+        match self {
+            Dithering::FloydSteinberg => 2,
+            Dithering::Atkinson => 3,
+            Dithering::None | Dithering::Bayer => 0,
+        }
+    }
+
+    /// Length of the error buffer needed to diffuse the errors of images up to `width` pixels wide
+    ///
+    /// `channels` is [Palette::CHANNELS] of the display color. Pixels right of `width` get the
+    /// nearest color without error diffusion.
+    pub const fn buffer_len(self, width: usize, channels: usize) -> usize {
+        self.rows() * (MARGIN_LEFT + width + MARGIN_RIGHT) * channels
+    }
+}
+
+/// Display colors images can be dithered to
+pub trait Palette: PixelColor + 'static {
+    /// The colors of the display and how they look like
+    const PALETTE: &'static [(Self, Rgb888)];
+
+    /// Color channels the errors are diffused in, 1 (luma) for grayscale displays and
+    /// 3 (red, green and blue) for color displays
+    const CHANNELS: usize;
+}
+
+impl Palette for BinaryColor {
+    const PALETTE: &'static [(Self, Rgb888)] = &[
+        (BinaryColor::On, Rgb888::BLACK),
+        (BinaryColor::Off, Rgb888::WHITE),
+    ];
+    const CHANNELS: usize = 1;
+}
+
+impl Palette for QuadColor {
+    const PALETTE: &'static [(Self, Rgb888)] = &[
+        (QuadColor::Black, Rgb888::new(0x00, 0x00, 0x00)),
+        (QuadColor::DarkGray, Rgb888::new(0x55, 0x55, 0x55)),
+        (QuadColor::LightGray, Rgb888::new(0xaa, 0xaa, 0xaa)),
+        (QuadColor::White, Rgb888::new(0xff, 0xff, 0xff)),
+    ];
+    const CHANNELS: usize = 1;
+}
+
+/// The chromatic color is red, use [Dithered::with_palette] for yellow displays
+impl Palette for TriColor {
+    const PALETTE: &'static [(Self, Rgb888)] = &[
+        (TriColor::Black, Rgb888::BLACK),
+        (TriColor::White, Rgb888::WHITE),
+        (TriColor::Chromatic, Rgb888::RED),
+    ];
+    const CHANNELS: usize = 3;
+}
+
+/// All colors besides [OctColor::HiZ], with the colors of [OctColor::rgb]
+impl Palette for OctColor {
+    const PALETTE: &'static [(Self, Rgb888)] = &[
+        (OctColor::Black, Rgb888::new(0x00, 0x00, 0x00)),
+        (OctColor::White, Rgb888::new(0xff, 0xff, 0xff)),
+        (OctColor::Green, Rgb888::new(0x00, 0xff, 0x00)),
+        (OctColor::Blue, Rgb888::new(0x00, 0x00, 0xff)),
+        (OctColor::Red, Rgb888::new(0xff, 0x00, 0x00)),
+        (OctColor::Yellow, Rgb888::new(0xff, 0xff, 0x00)),
+        (OctColor::Orange, Rgb888::new(0xff, 0x80, 0x00)),
+    ];
+    const CHANNELS: usize = 3;
+}
+
+/// Draw target dithering colors of type `C` to the [Palette] of the wrapped display,
+/// see the [module documentation](self)
+pub struct Dithered<'a, D, C = Rgb888>
+where
+    D: DrawTarget,
+{
+    display: &'a mut D,
+    dithering: Dithering,
+    palette: &'a [(D::Color, Rgb888)],
+    errors: &'a mut [i16],
+    color: PhantomData<C>,
+}
+
+impl<'a, D, C> Dithered<'a, D, C>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    /// Wraps `display`, `errors` needs [Dithering::buffer_len] entries for error diffusion
+    pub fn new(display: &'a mut D, dithering: Dithering, errors: &'a mut [i16]) -> Self {
+        Dithered {
+            display,
+            dithering,
+            palette: D::Color::PALETTE,
+            errors,
+            color: PhantomData,
+        }
+    }
+
+    /// Replaces the default [Palette::PALETTE], e.g. to match the measured colors of a display
+    pub fn with_palette(mut self, palette: &'a [(D::Color, Rgb888)]) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    /// Returns the wrapped display
+    pub fn into_inner(self) -> &'a mut D {
+        self.display
+    }
+}
+
+/// Channel values of `color` as dithered in
+fn channels<P: Palette>(color: Rgb888) -> [i16; 3] {
+    if P::CHANNELS == 1 {
+        [i16::from(Gray8::from(color).luma()), 0, 0]
+    } else {
+        [
+            i16::from(color.r()),
+            i16::from(color.g()),
+            i16::from(color.b()),
+        ]
+    }
+}
+
+/// Palette entry closest to `value` and its channel values
+fn nearest<P: Palette>(palette: &[(P, Rgb888)], value: [i16; 3]) -> (P, [i16; 3]) {
+    let distance = |target: &[i16; 3]| -> i32 {
+        value
+            .iter()
+            .zip(target.iter())
+            .map(|(&v, &t)| (i32::from(v) - i32::from(t)).pow(2))
+            .sum()
+    };
+    palette
+        .iter()
+        .map(|&(color, rgb)| (color, channels::<P>(rgb)))
+        .min_by_key(|(_, target)| distance(target))
+        .expect("empty palette")
+}
+
+/// Error rows of the running error diffusion
+struct Diffusion<'e> {
+    errors: &'e mut [i16],
+    dithering: Dithering,
+    channels: usize,
+    /// Columns of a row including the margins
+    columns: usize,
+    row: usize,
+}
+
+impl<'e> Diffusion<'e> {
+    fn new(errors: &'e mut [i16], dithering: Dithering, channels: usize) -> Self {
+        let rows = dithering.rows();
+        let columns = if rows == 0 {
+            0
+        } else {
+            errors.len() / (rows * channels)
+        };
+        errors.iter_mut().for_each(|e| *e = 0);
+        Diffusion {
+            errors,
+            dithering,
+            channels,
+            columns,
+            row: 0,
+        }
+    }
+
+    /// Whether the errors of `column` are kept
+    fn diffuses(&self, column: usize) -> bool {
+        MARGIN_LEFT + column + MARGIN_RIGHT < self.columns
+    }
+
+    /// Index of the error of a channel, `column` counts from the start of the left margin
+    fn index(&self, column: usize, row: usize, channel: usize) -> usize {
+        let slot = row % self.dithering.rows();
+        (slot * self.columns + column) * self.channels + channel
+    }
+
+    /// Clears the rows above `row`, they are reused for the following rows
+    fn start_row(&mut self, row: usize) {
+        while self.row < row {
+            let start = self.index(0, self.row, 0);
+            let len = self.columns * self.channels;
+            self.errors[start..start + len]
+                .iter_mut()
+                .for_each(|e| *e = 0);
+            self.row += 1;
+        }
+    }
+
+    /// Adds the diffused error of the pixel at `column`, `row`
+    fn apply(&self, column: usize, row: usize, value: &mut [i16; 3]) {
+        for (channel, v) in value.iter_mut().enumerate().take(self.channels) {
+            *v = v.saturating_add(self.errors[self.index(MARGIN_LEFT + column, row, channel)]);
+        }
+    }
+
+    /// Spreads the quantization `error` of the pixel at `column`, `row` to its neighbours
+    fn spread(&mut self, column: usize, row: usize, error: [i16; 3]) {
+        // (column offset, row offset, weight), the weights are divided by `divisor`
+        let (neighbours, divisor): (&[(isize, usize, i16)], i16) = match self.dithering {
+            Dithering::FloydSteinberg => (&[(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)], 16),
+            Dithering::Atkinson => (
+                &[
+                    (1, 0, 1),
+                    (2, 0, 1),
+                    (-1, 1, 1),
+                    (0, 1, 1),
+                    (1, 1, 1),
+                    (0, 2, 1),
+                ],
+                8,
+            ),
+            Dithering::None | Dithering::Bayer => return,
+        };
+        for &(dx, dy, weight) in neighbours {
+            // the margins make room for the neighbours left and right of the image
+            let neighbour = ((MARGIN_LEFT + column) as isize + dx) as usize;
+            for (channel, &e) in error.iter().enumerate().take(self.channels) {
+                let index = self.index(neighbour, row + dy, channel);
+                self.errors[index] = self.errors[index].saturating_add(e * weight / divisor);
+            }
+        }
+    }
+}
+
+/// Threshold offset of ordered dithering at `point`, between about -1/2 and 1/2 of the
+/// full channel range
+fn bayer_offset(point: Point) -> i16 {
+    let threshold = BAYER[point.y.rem_euclid(4) as usize][point.x.rem_euclid(4) as usize];
+    (2 * threshold + 1) * 255 / 32 - 128
+}
+
+impl<'a, D, C> Dithered<'a, D, C>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+{
+    /// Color of a pixel which isn't part of a row by row error diffusion
+    fn quantize(
+        dithering: Dithering,
+        palette: &[(D::Color, Rgb888)],
+        pixel: Pixel<C>,
+    ) -> Pixel<D::Color>
+    where
+        C: PixelColor + Into<Rgb888>,
+    {
+        let Pixel(point, color) = pixel;
+        let mut value = channels::<D::Color>(color.into());
+        if dithering == Dithering::Bayer {
+            let offset = bayer_offset(point);
+            value.iter_mut().for_each(|v| *v += offset);
+        }
+        Pixel(point, nearest(palette, value).0)
+    }
+}
+
+impl<'a, D, C> DrawTarget for Dithered<'a, D, C>
+where
+    D: DrawTarget,
+    D::Color: Palette,
+    C: PixelColor + Into<Rgb888>,
+{
+    type Color = C;
+    type Error = D::Error;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (dithering, palette) = (self.dithering, self.palette);
+        self.display.draw_iter(
+            pixels
+                .into_iter()
+                .map(|pixel| Self::quantize(dithering, palette, pixel)),
+        )
+    }
+
+    fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Self::Color>,
+    {
+        let (dithering, palette) = (self.dithering, self.palette);
+        if dithering.rows() == 0 || area.size.width == 0 {
+            return self.draw_iter(area.points().zip(colors).map(|(p, c)| Pixel(p, c)));
+        }
+
+        let mut diffusion = Diffusion::new(self.errors, dithering, D::Color::CHANNELS);
+        let width = area.size.width as usize;
+        let pixels = area
+            .points()
+            .zip(colors)
+            .enumerate()
+            .map(move |(i, (point, color))| {
+                let (column, row) = (i % width, i / width);
+                let mut value = channels::<D::Color>(color.into());
+                if !diffusion.diffuses(column) {
+                    return Pixel(point, nearest(palette, value).0);
+                }
+                diffusion.start_row(row);
+                diffusion.apply(column, row, &mut value);
+                let (color, target) = nearest(palette, value);
+                let mut error = [0; 3];
+                for (e, (v, t)) in error.iter_mut().zip(value.iter().zip(target.iter())) {
+                    *e = v - t;
+                }
+                diffusion.spread(column, row, error);
+                Pixel(point, color)
+            });
+        self.display.draw_iter(pixels)
+    }
+}
+
+impl<'a, D, C> Dimensions for Dithered<'a, D, C>
+where
+    D: DrawTarget,
+{
+    fn bounding_box(&self) -> Rectangle {
+        self.display.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::graphics::{Display, TriDisplay, VarDisplay};
+    use crate::{buffer_len, epd2in13bc::Display2in13bc, epd5in65f::Display5in65f};
+    use embedded_graphics::{prelude::*, primitives::PrimitiveStyle};
+
+    const WIDTH: u32 = 32;
+    const HEIGHT: u32 = 16;
+
+    /// Number of black pixels after filling the display with `gray`
+    fn black_pixels(dithering: Dithering, gray: u8) -> u32 {
+        let mut buffer =
+            [Color::White.get_byte_value(); buffer_len(WIDTH as usize, HEIGHT as usize)];
+        let mut display = VarDisplay::new(WIDTH, HEIGHT, &mut buffer);
+        let mut errors = [0; Dithering::Atkinson.buffer_len(WIDTH as usize, 1)];
+        let mut dithered = Dithered::new(&mut display, dithering, &mut errors);
+        let _ = Rectangle::new(Point::zero(), Size::new(WIDTH, HEIGHT))
+            .into_styled(PrimitiveStyle::with_fill(Gray8::new(gray)))
+            .draw(&mut dithered);
+        display.buffer().iter().map(|b| b.count_zeros()).sum()
+    }
+
+    #[test]
+    fn gray_levels() {
+        let pixels = WIDTH * HEIGHT;
+        for dithering in [
+            Dithering::FloydSteinberg,
+            Dithering::Atkinson,
+            Dithering::Bayer,
+        ] {
+            assert_eq!(black_pixels(dithering, 0x00), pixels);
+            assert_eq!(black_pixels(dithering, 0xff), 0);
+            let half = black_pixels(dithering, 0x80);
+            assert!(
+                half.abs_diff(pixels / 2) <= pixels / 16,
+                "{:?}: {}",
+                dithering,
+                half
+            );
+            // Atkinson loses 1/4 of the error and renders light areas lighter
+            let quarter = black_pixels(dithering, 0xc0);
+            let expected = if dithering == Dithering::Atkinson {
+                pixels / 8
+            } else {
+                pixels / 4
+            };
+            assert!(
+                quarter.abs_diff(expected) <= pixels / 16,
+                "{:?}: {}",
+                dithering,
+                quarter
+            );
+        }
+        assert_eq!(black_pixels(Dithering::None, 0x70), WIDTH * HEIGHT);
+        assert_eq!(black_pixels(Dithering::None, 0x90), 0);
+    }
+
+    #[test]
+    fn bayer_in_any_order() {
+        let mut buffer =
+            [Color::White.get_byte_value(); buffer_len(WIDTH as usize, HEIGHT as usize)];
+        let mut display = VarDisplay::new(WIDTH, HEIGHT, &mut buffer);
+        let mut dithered = Dithered::new(&mut display, Dithering::Bayer, &mut []);
+        let gray = Gray8::new(0x80);
+        let _ = dithered.draw_iter([Pixel(Point::new(0, 0), gray), Pixel(Point::new(1, 0), gray)]);
+        let _ = dithered.draw_iter([Pixel(Point::new(1, 1), gray), Pixel(Point::new(0, 1), gray)]);
+        // threshold 0 and 8 in the first, 12 and 4 in the second row
+        assert_eq!(display.buffer()[0] >> 6, 0b01);
+        assert_eq!(display.buffer()[WIDTH as usize / 8] >> 6, 0b10);
+    }
+
+    #[test]
+    fn narrow_error_buffer() {
+        let mut buffer =
+            [Color::White.get_byte_value(); buffer_len(WIDTH as usize, HEIGHT as usize)];
+        let mut display = VarDisplay::new(WIDTH, HEIGHT, &mut buffer);
+        let mut errors = [0; Dithering::FloydSteinberg.buffer_len(8, 1)];
+        let mut dithered = Dithered::new(&mut display, Dithering::FloydSteinberg, &mut errors);
+        let _ = Rectangle::new(Point::zero(), Size::new(WIDTH, HEIGHT))
+            .into_styled(PrimitiveStyle::with_fill(Gray8::new(0x90)))
+            .draw(&mut dithered);
+        // dithered left of 8 pixels, nearest color (white) right of it
+        assert!(display
+            .buffer()
+            .chunks(WIDTH as usize / 8)
+            .all(|row| row[1..] == [0xff; 3]));
+        assert!(display
+            .buffer()
+            .iter()
+            .step_by(WIDTH as usize / 8)
+            .any(|&b| b != 0xff));
+    }
+
+    #[test]
+    fn tri_and_oct_colors() {
+        let mut display = Display2in13bc::default();
+        let mut errors = [0; Dithering::FloydSteinberg.buffer_len(8, TriColor::CHANNELS)];
+        let mut dithered = Dithered::new(&mut display, Dithering::FloydSteinberg, &mut errors);
+        let _ = Rectangle::new(Point::zero(), Size::new(8, 2))
+            .into_styled(PrimitiveStyle::with_fill(Rgb888::new(0xf0, 0x10, 0x10)))
+            .draw(&mut dithered);
+        assert_eq!(display.chromatic_buffer()[0], 0x00);
+        assert_eq!(display.bw_buffer()[0], 0xff);
+
+        let mut display = Display5in65f::default();
+        let mut errors = [0; Dithering::Atkinson.buffer_len(8, OctColor::CHANNELS)];
+        let mut dithered = Dithered::new(&mut display, Dithering::Atkinson, &mut errors);
+        let _ = Rectangle::new(Point::zero(), Size::new(8, 4))
+            .into_styled(PrimitiveStyle::with_fill(Rgb888::new(0x10, 0xe0, 0x20)))
+            .draw(&mut dithered);
+        let green = OctColor::colors_byte(OctColor::Green, OctColor::Green);
+        assert_eq!(
+            crate::graphics::OctDisplay::buffer(&display)[..4],
+            [green; 4]
+        );
+    }
+
+    #[test]
+    fn custom_palette() {
+        const YELLOW: &[(TriColor, Rgb888)] = &[
+            (TriColor::Black, Rgb888::BLACK),
+            (TriColor::White, Rgb888::WHITE),
+            (TriColor::Chromatic, Rgb888::YELLOW),
+        ];
+        let mut display = Display2in13bc::default();
+        let mut dithered =
+            Dithered::new(&mut display, Dithering::None, &mut []).with_palette(YELLOW);
+        let _ = Pixel(Point::zero(), Rgb888::new(0xf0, 0xf0, 0x00)).draw(&mut dithered);
+        let _ = Pixel(Point::new(1, 0), Rgb888::new(0xf0, 0x00, 0x00)).draw(&mut dithered);
+        assert_eq!(display.chromatic_buffer()[0] >> 6, 0b01);
+    }
+}
@@ -4,7 +4,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyleBuilder},
 //!};
@@ -52,6 +52,7 @@ pub const HEIGHT: u32 = 200;
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
 //const DPI: u16 = 184;
 const IS_BUSY_LOW: bool = false;
+const SINGLE_BYTE_WRITE: bool = false;
 
 use embedded_hal::{
     blocking::{delay::*, spi::Write},
@@ -65,8 +66,13 @@ use crate::type_a::{
 
 use crate::color::Color;
 
-use crate::traits::{RefreshLut, WaveshareDisplay};
+use crate::traits::{
+    Deinit, DriverError, LutSelection, PanelInfo, PartialUpdate, RefreshLut, StreamingFrame,
+    WaveshareDisplay,
+};
 
+use crate::buffer_len;
+use crate::error::EpdError;
 use crate::interface::DisplayInterface;
 
 #[cfg(feature = "graphics")]
@@ -77,7 +83,7 @@ pub use crate::epd1in54::graphics::Display1in54;
 /// Epd1in54 driver
 pub struct Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> {
     /// SPI
-    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
     /// Color
     background_color: Color,
     /// Refresh LUT
@@ -93,8 +99,12 @@ where
     RST: OutputPin,
     DELAY: DelayMs<u8>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.interface.reset(delay, 10);
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.reset(delay, 10)?;
 
         // 3 Databytes:
         // A[7:0]
@@ -132,13 +142,28 @@ where
         self.interface
             .cmd_with_data(spi, Command::DataEntryModeSetting, &[0x03])?;
 
-        self.set_lut(spi, None)?;
+        self.set_lut(spi, None, delay)?;
 
-        self.wait_until_idle();
+        self.wait_until_idle(delay)?;
         Ok(())
     }
 }
 
+impl<SPI, CS, BUSY, DC, RST, DELAY> Deinit<CS, BUSY, DC, RST>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+{
+    fn deinit(self) -> (CS, BUSY, DC, RST) {
+        self.interface.deinit()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> PanelInfo for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+    const COLOR_DEPTH: u8 = 1;
+    const IS_BUSY_LOW: bool = IS_BUSY_LOW;
+}
+
 impl<SPI, CS, BUSY, DC, RST, E, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
 where
@@ -150,14 +175,6 @@ where
     DELAY: DelayMs<u8>,
 {
     type DisplayColor = Color;
-    fn width(&self) -> u32 {
-        WIDTH
-    }
-
-    fn height(&self) -> u32 {
-        HEIGHT
-    }
-
     fn new(
         spi: &mut SPI,
         cs: CS,
@@ -165,7 +182,7 @@ where
         dc: DC,
         rst: RST,
         delay: &mut DELAY,
-    ) -> Result<Self, SPI::Error> {
+    ) -> Result<Self, DriverError<SPI, CS, BUSY, DC, RST>> {
         let interface = DisplayInterface::new(cs, busy, dc, rst);
 
         let mut epd = Epd1in54 {
@@ -179,12 +196,20 @@ where
         Ok(epd)
     }
 
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.init(spi, delay)
     }
 
-    fn sleep(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
         // 0x00 for Normal mode (Power on Reset), 0x01 for Deep Sleep Mode
         //TODO: is 0x00 needed here or would 0x01 be even more efficient?
         self.interface
@@ -196,36 +221,23 @@ where
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
-        _delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.use_full_frame(spi)?;
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(buffer_len(WIDTH as usize, HEIGHT as usize), buffer.len())?;
+
+        self.wait_until_idle(delay)?;
+        self.use_full_frame(spi, delay)?;
         self.interface
             .cmd_with_data(spi, Command::WriteRam, buffer)?;
         Ok(())
     }
 
-    //TODO: update description: last 3 bits will be ignored for width and x_pos
-    fn update_partial_frame(
+    fn display_frame(
         &mut self,
         spi: &mut SPI,
-        buffer: &[u8],
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.set_ram_area(spi, x, y, x + width, y + height)?;
-        self.set_ram_counter(spi, x, y)?;
-
-        self.interface
-            .cmd_with_data(spi, Command::WriteRam, buffer)?;
-        Ok(())
-    }
-
-    fn display_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
         // enable clock signal, enable cp, display pattern -> 0xC4 (tested with the arduino version)
         //TODO: test control_1 or control_2 with default value 0xFF (from the datasheet)
         self.interface
@@ -243,15 +255,19 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.use_full_frame(spi)?;
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
+        self.use_full_frame(spi, delay)?;
 
         // clear the ram with the background color
         let color = self.background_color.get_byte_value();
@@ -270,22 +286,108 @@ where
         &self.background_color
     }
 
+    fn is_busy(&self) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.interface.set_busy_timeout(timeout_ms);
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> PartialUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    //TODO: update description: last 3 bits will be ignored for width and x_pos
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, WIDTH, HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+        self.wait_until_idle(delay)?;
+        self.set_ram_area(spi, x, y, x + width, y + height, delay)?;
+        self.set_ram_counter(spi, x, y, delay)?;
+
+        self.interface
+            .cmd_with_data(spi, Command::WriteRam, buffer)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, E, DELAY> LutSelection<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8, Error = E>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
     fn set_lut(
         &mut self,
         spi: &mut SPI,
         refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         if let Some(refresh_lut) = refresh_rate {
             self.refresh = refresh_lut;
         }
         match self.refresh {
-            RefreshLut::Full => self.set_lut_helper(spi, &LUT_FULL_UPDATE),
-            RefreshLut::Quick => self.set_lut_helper(spi, &LUT_PARTIAL_UPDATE),
+            RefreshLut::Full => self.set_lut_helper(spi, &LUT_FULL_UPDATE, delay),
+            RefreshLut::Quick => self.set_lut_helper(spi, &LUT_PARTIAL_UPDATE, delay),
         }
     }
+}
 
-    fn is_busy(&self) -> bool {
-        self.interface.is_busy(IS_BUSY_LOW)
+impl<SPI, CS, BUSY, DC, RST, DELAY> StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn begin_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
+        self.use_full_frame(spi, delay)?;
+        self.interface.cmd(spi, Command::WriteRam)
+    }
+
+    fn write_frame_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.data(spi, data)
+    }
+
+    fn end_frame(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
     }
 }
 
@@ -298,16 +400,23 @@ where
     RST: OutputPin,
     DELAY: DelayMs<u8>,
 {
-    fn wait_until_idle(&mut self) {
-        let _ = self.interface.wait_until_idle(IS_BUSY_LOW);
+    fn wait_until_idle(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW)
     }
 
-    pub(crate) fn use_full_frame(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+    pub(crate) fn use_full_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         // choose full frame/ram
-        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1)?;
+        self.set_ram_area(spi, 0, 0, WIDTH - 1, HEIGHT - 1, delay)?;
 
         // start from the beginning
-        self.set_ram_counter(spi, 0, 0)
+        self.set_ram_counter(spi, 0, 0, delay)
     }
 
     pub(crate) fn set_ram_area(
@@ -317,10 +426,12 @@ where
         start_y: u32,
         end_x: u32,
         end_y: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        assert!(start_x < end_x);
-        assert!(start_y < end_y);
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
+        if start_x >= end_x || start_y >= end_y {
+            return Err(EpdError::WindowAlignment);
+        }
 
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
@@ -349,8 +460,9 @@ where
         spi: &mut SPI,
         x: u32,
         y: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
         // x is positioned in bytes, so the last 3 bits which show the position inside a byte in the ram
         // aren't relevant
         self.interface
@@ -365,8 +477,13 @@ where
         Ok(())
     }
 
-    fn set_lut_helper(&mut self, spi: &mut SPI, buffer: &[u8]) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn set_lut_helper(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
         assert!(buffer.len() == 30);
 
         self.interface
@@ -2,7 +2,7 @@
 use crate::traits;
 
 #[allow(dead_code)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub(crate) enum Command {
     PanelSetting = 0x00,
 
@@ -38,3 +38,36 @@ impl traits::Command for Command {
         self as u8
     }
 }
+
+impl Command {
+    /// The command with this address, used to decode [traces](crate::trace)
+    #[cfg(any(test, feature = "std"))]
+    pub(crate) fn from_address(address: u8) -> Option<Self> {
+        Some(match address {
+            0x00 => Command::PanelSetting,
+            0x01 => Command::PowerSetting,
+            0x02 => Command::PowerOff,
+            0x04 => Command::PowerOn,
+            0x06 => Command::BoosterSoftStart,
+            0x10 => Command::DataStartTransmission1,
+            0x12 => Command::DisplayRefresh,
+            0x13 => Command::DataStartTransmission2,
+            0x20 => Command::LutForVcom,
+            0x21 => Command::LutWhiteToWhite,
+            0x22 => Command::LutBlackToWhite,
+            0x23 => Command::LutG0,
+            0x24 => Command::LutG1,
+            0x25 => Command::LutRedVcom,
+            0x26 => Command::LutRed0,
+            0x27 => Command::LutRed1,
+            0x30 => Command::PllControl,
+            0x40 => Command::TemperatureSensor,
+            0x41 => Command::TemperatureSensorSelection,
+            0x50 => Command::VcomAndDataIntervalSetting,
+            0x61 => Command::ResolutionSetting,
+            0x82 => Command::VcmDcSetting,
+            0xE3 => Command::PowerSaving,
+            _ => return None,
+        })
+    }
+}
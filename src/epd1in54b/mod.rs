@@ -5,8 +5,11 @@ use embedded_hal::{
     digital::v2::*,
 };
 
+use crate::buffer_len;
+use crate::error::EpdError;
 use crate::traits::{
-    InternalWiAdditions, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+    Deinit, DriverError, InternalWiAdditions, LutSelection, PanelInfo, RefreshLut,
+    StreamingColorFrame, StreamingFrame, WaveshareDisplay, WaveshareThreeColorDisplay,
 };
 use crate::{interface::DisplayInterface, prelude::TriColor};
 
@@ -21,9 +24,8 @@ pub const HEIGHT: u32 = 200;
 /// Default Background Color (white)
 pub const DEFAULT_BACKGROUND_COLOR: TriColor = TriColor::White;
 
-const NUM_DISPLAY_BITS: u32 = WIDTH * HEIGHT / 8;
-
 const IS_BUSY_LOW: bool = true;
+const SINGLE_BYTE_WRITE: bool = false;
 
 use crate::color::TriColor as Color;
 
@@ -37,8 +39,10 @@ pub use self::graphics::Display1in54b;
 
 /// Epd1in54b driver
 pub struct Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY> {
-    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
     color: Color,
+    /// Whether the streamed frame data is the chromatic plane, which is sent without expanding
+    streaming_chromatic: bool,
 }
 
 impl<SPI, CS, BUSY, DC, RST, DELAY> Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
@@ -57,6 +61,14 @@ where
     }
 }
 
+impl<SPI, CS, BUSY, DC, RST, DELAY> Deinit<CS, BUSY, DC, RST>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+{
+    fn deinit(self) -> (CS, BUSY, DC, RST) {
+        self.interface.deinit()
+    }
+}
+
 impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
     for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
 where
@@ -67,49 +79,53 @@ where
     RST: OutputPin,
     DELAY: DelayMs<u8>,
 {
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        defmt::info!("EPD1in54b reset");
-        self.interface.reset(delay, 10);
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        info!("EPD1in54b reset");
+        self.interface.reset(delay, 10)?;
 
-        defmt::info!("EPD1in54b set power");
+        info!("EPD1in54b set power");
         // set the power settings
         self.interface
             .cmd_with_data(spi, Command::PowerSetting, &[0x07, 0x00, 0x08, 0x00])?;
 
-        defmt::info!("EPD1in54b start booster");
+        info!("EPD1in54b start booster");
         // start the booster
         self.interface
             .cmd_with_data(spi, Command::BoosterSoftStart, &[0x07, 0x07, 0x07])?;
 
-        defmt::info!("EPD1in54b pwron");
+        info!("EPD1in54b pwron");
         // power on
         self.command(spi, Command::PowerOn)?;
         // delay.delay_ms(250);
         // delay.delay_ms(250);
         delay.delay_ms(5);
-        self.wait_until_idle();
+        self.wait_until_idle(delay)?;
 
-        defmt::info!("EPD1in54b setup panel");
+        info!("EPD1in54b setup panel");
         // set the panel settings
         self.cmd_with_data(spi, Command::PanelSetting, &[0xCF])?;
 
         self.cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x37])?;
 
-        defmt::info!("EPD1in54b PLL");
+        info!("EPD1in54b PLL");
         // PLL
         self.cmd_with_data(spi, Command::PllControl, &[0x39])?;
 
-        defmt::info!("EPD1in54b Set res");
+        info!("EPD1in54b Set res");
         // set resolution
         self.send_resolution(spi)?;
 
         self.cmd_with_data(spi, Command::VcmDcSetting, &[0x0E])?;
 
-        defmt::info!("EPD1in54b Set LUT");
-        self.set_lut(spi, None)?;
+        info!("EPD1in54b Set LUT");
+        self.set_lut(spi, None, delay)?;
 
-        defmt::info!("EPD1in54b wait till idle");
-        self.wait_until_idle();
+        info!("EPD1in54b wait till idle");
+        self.wait_until_idle(delay)?;
         // delay.delay_ms(250);
         // delay.delay_ms(250);
 
@@ -132,13 +148,21 @@ where
         spi: &mut SPI,
         black: &[u8],
         chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
-        self.update_achromatic_frame(spi, black)?;
-        self.update_chromatic_frame(spi, chromatic)
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.update_achromatic_frame(spi, black, delay)?;
+        self.update_chromatic_frame(spi, chromatic, delay)
     }
 
-    fn update_achromatic_frame(&mut self, spi: &mut SPI, black: &[u8]) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn update_achromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(buffer_len(WIDTH as usize, HEIGHT as usize), black.len())?;
+
+        self.wait_until_idle(delay)?;
         self.send_resolution(spi)?;
 
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
@@ -154,13 +178,23 @@ where
         &mut self,
         spi: &mut SPI,
         chromatic: &[u8],
-    ) -> Result<(), SPI::Error> {
+        _delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(buffer_len(WIDTH as usize, HEIGHT as usize), chromatic.len())?;
+
         self.interface.cmd(spi, Command::DataStartTransmission2)?;
         self.interface.data(spi, chromatic)?;
         Ok(())
     }
 }
 
+impl<SPI, CS, BUSY, DC, RST, DLY> PanelInfo for Epd1in54b<SPI, CS, BUSY, DC, RST, DLY> {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+    const COLOR_DEPTH: u8 = 2;
+    const IS_BUSY_LOW: bool = IS_BUSY_LOW;
+}
+
 impl<SPI, CS, BUSY, DC, RST, DLY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DLY>
     for Epd1in54b<SPI, CS, BUSY, DC, RST, DLY>
 where
@@ -179,19 +213,27 @@ where
         dc: DC,
         rst: RST,
         delay: &mut DLY,
-    ) -> Result<Self, SPI::Error> {
+    ) -> Result<Self, DriverError<SPI, CS, BUSY, DC, RST>> {
         let interface = DisplayInterface::new(cs, busy, dc, rst);
         let color = DEFAULT_BACKGROUND_COLOR;
 
-        let mut epd = Epd1in54b { interface, color };
+        let mut epd = Epd1in54b {
+            interface,
+            color,
+            streaming_chromatic: false,
+        };
 
         epd.init(spi, delay)?;
 
         Ok(epd)
     }
 
-    fn sleep(&mut self, spi: &mut SPI, _delay: &mut DLY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DLY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
         self.interface
             .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x17])?; //border floating
 
@@ -201,7 +243,7 @@ where
         self.interface
             .cmd_with_data(spi, Command::PowerSetting, &[0x02, 0x00, 0x00, 0x00])?; //VG&VS to 0V fast
 
-        self.wait_until_idle();
+        self.wait_until_idle(delay)?;
 
         //NOTE: The example code has a 1s delay here
 
@@ -210,7 +252,11 @@ where
         Ok(())
     }
 
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DLY) -> Result<(), SPI::Error> {
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DLY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.init(spi, delay)
     }
 
@@ -222,21 +268,15 @@ where
         &self.color
     }
 
-    fn width(&self) -> u32 {
-        WIDTH
-    }
-
-    fn height(&self) -> u32 {
-        HEIGHT
-    }
-
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
-        _delay: &mut DLY,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+        delay: &mut DLY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(buffer_len(WIDTH as usize, HEIGHT as usize), buffer.len())?;
+
+        self.wait_until_idle(delay)?;
         self.send_resolution(spi)?;
 
         self.interface.cmd(spi, Command::DataStartTransmission1)?;
@@ -260,21 +300,12 @@ where
         Ok(())
     }
 
-    #[allow(unused)]
-    fn update_partial_frame(
+    fn display_frame(
         &mut self,
         spi: &mut SPI,
-        buffer: &[u8],
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Result<(), SPI::Error> {
-        unimplemented!()
-    }
-
-    fn display_frame(&mut self, spi: &mut SPI, _delay: &mut DLY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+        delay: &mut DLY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
         self.command(spi, Command::DisplayRefresh)?;
         Ok(())
     }
@@ -284,14 +315,18 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DLY,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.update_frame(spi, buffer, delay)?;
         self.display_frame(spi, delay)?;
         Ok(())
     }
 
-    fn clear_frame(&mut self, spi: &mut SPI, _delay: &mut DLY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DLY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
         self.send_resolution(spi)?;
 
         let color = self.color.get_byte_value();
@@ -310,11 +345,31 @@ where
         Ok(())
     }
 
+    fn is_busy(&self) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.interface.set_busy_timeout(timeout_ms);
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DLY> LutSelection<SPI, CS, BUSY, DC, RST, DLY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DLY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DLY: DelayMs<u8>,
+{
     fn set_lut(
         &mut self,
         spi: &mut SPI,
         _refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
+        _delay: &mut DLY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.interface
             .cmd_with_data(spi, Command::LutForVcom, LUT_VCOM0)?;
         self.interface
@@ -332,9 +387,75 @@ where
 
         Ok(())
     }
+}
 
-    fn is_busy(&self) -> bool {
-        self.interface.is_busy(IS_BUSY_LOW)
+impl<SPI, CS, BUSY, DC, RST, DELAY> StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn begin_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
+        self.send_resolution(spi)?;
+        self.streaming_chromatic = false;
+        self.command(spi, Command::DataStartTransmission1)
+    }
+
+    fn write_frame_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        if self.streaming_chromatic {
+            return self.send_data(spi, data);
+        }
+        // The black/white RAM takes two bits per pixel
+        let mut expanded = [0u8; 64];
+        for chunk in data.chunks(expanded.len() / 2) {
+            for (b, bits) in chunk.iter().zip(expanded.chunks_mut(2)) {
+                bits.copy_from_slice(&expand_bits(*b));
+            }
+            self.send_data(spi, &expanded[..chunk.len() * 2])?;
+        }
+        Ok(())
+    }
+
+    fn end_frame(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> StreamingColorFrame<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd1in54b<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    fn begin_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.wait_until_idle(delay)?;
+        self.streaming_chromatic = true;
+        self.command(spi, Command::DataStartTransmission2)
     }
 }
 
@@ -347,11 +468,19 @@ where
     RST: OutputPin,
     DELAY: DelayMs<u8>,
 {
-    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
+    fn command(
+        &mut self,
+        spi: &mut SPI,
+        command: Command,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.interface.cmd(spi, command)
     }
 
-    fn send_data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+    fn send_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.interface.data(spi, data)
     }
 
@@ -360,15 +489,21 @@ where
         spi: &mut SPI,
         command: Command,
         data: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.interface.cmd_with_data(spi, command, data)
     }
 
-    fn wait_until_idle(&mut self) {
-        let _ = self.interface.wait_until_idle(IS_BUSY_LOW);
+    fn wait_until_idle(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(delay, IS_BUSY_LOW)
     }
 
-    fn send_resolution(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
+    fn send_resolution(
+        &mut self,
+        spi: &mut SPI,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         let w = self.width();
         let h = self.height();
 
@@ -401,4 +536,61 @@ mod tests {
         assert_eq!(HEIGHT, 200);
         assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
     }
+
+    #[cfg(feature = "graphics")]
+    #[test]
+    fn paged_render_matches_color_frame() {
+        use crate::paged::PagedDisplay;
+        use crate::simulator::SimulatedPanel;
+        use crate::trace::{CommandSet, Recorder, Transaction};
+        use embedded_graphics::prelude::*;
+
+        // The IL0398 simulation only provides bus and pins, the data is checked in the trace
+        let panel = SimulatedPanel::il0398(WIDTH, HEIGHT);
+        let recorder = Recorder::new();
+        let (mut epd, mut spi, mut delay) =
+            panel.connect_traced::<Epd1in54b<_, _, _, _, _, _>>(&recorder);
+        let planes = |recorder: &Recorder| {
+            let data = |command: Command| -> std::vec::Vec<u8> {
+                recorder
+                    .decode(CommandSet::Epd1in54b)
+                    .transactions()
+                    .iter()
+                    .filter_map(|transaction| match transaction {
+                        Transaction::Command { address, data, .. } if *address == command as u8 => {
+                            Some(data.clone())
+                        }
+                        _ => None,
+                    })
+                    .flatten()
+                    .collect()
+            };
+            (
+                data(Command::DataStartTransmission1),
+                data(Command::DataStartTransmission2),
+            )
+        };
+
+        recorder.clear();
+        let mut band = [0; 5 * WIDTH as usize / 8];
+        let mut display = PagedDisplay::new(&mut band, WIDTH, HEIGHT, TriColor::White);
+        display
+            .render(&mut epd, &mut spi, &mut delay, |display| {
+                let _ = Pixel(Point::new(0, 0), TriColor::Chromatic).draw(display);
+                let _ = Pixel(Point::new(9, 0), TriColor::Black).draw(display);
+            })
+            .unwrap();
+        let (bw, chromatic) = planes(&recorder);
+        let len = buffer_len(WIDTH as usize, HEIGHT as usize);
+        // 2 bits per pixel in the b/w RAM, 1 bit in the chromatic RAM
+        assert_eq!((bw.len(), chromatic.len()), (2 * len, len));
+
+        recorder.clear();
+        let (mut black, mut red) = (std::vec![0xFF; len], std::vec![0xFF; len]);
+        black[1] = 0xBF;
+        red[0] = 0x7F;
+        epd.update_color_frame(&mut spi, &black, &red, &mut delay)
+            .unwrap();
+        assert_eq!(planes(&recorder), (bw, chromatic));
+    }
 }
@@ -1,51 +1,52 @@
-#[rustfmt::skip]
-// Original Waveforms from Waveshare
-pub(crate) const LUT_FULL_UPDATE: [u8; 70] =[
-    0x80,0x60,0x40,0x00,0x00,0x00,0x00,             // LUT0: BB:     VS 0 ~7
-    0x10,0x60,0x20,0x00,0x00,0x00,0x00,             // LUT1: BW:     VS 0 ~7
-    0x80,0x60,0x40,0x00,0x00,0x00,0x00,             // LUT2: WB:     VS 0 ~7
-    0x10,0x60,0x20,0x00,0x00,0x00,0x00,             // LUT3: WW:     VS 0 ~7
-    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT4: VCOM:   VS 0 ~7
-
-    0x03,0x03,0x00,0x00,0x02,                       //  TP0 A~D RP0
-    0x09,0x09,0x00,0x00,0x02,                       //  TP1 A~D RP1
-    0x03,0x03,0x00,0x00,0x02,                       //  TP2 A~D RP2
-    0x00,0x00,0x00,0x00,0x00,                       //  TP3 A~D RP3
-    0x00,0x00,0x00,0x00,0x00,                       //  TP4 A~D RP4
-    0x00,0x00,0x00,0x00,0x00,                       //  TP5 A~D RP5
-    0x00,0x00,0x00,0x00,0x00,                       //  TP6 A~D RP6
-];
+use crate::lut::scale_phases;
 
+// Partial refresh waveform of the Waveshare 2.13" V3 (also an SSD1680 with 122x250 pixels).
+// The LUT register of the SSD1680 holds 153 bytes: 5 voltage groups of 12 phases, 12 timing
+// groups and the frame rates, unlike the 70 byte LUTs of the SSD1675.
 #[rustfmt::skip]
-pub(crate) const LUT_PARTIAL_UPDATE: [u8; 70] =[
-    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT0: BB:     VS 0 ~7
-    0x80,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT1: BW:     VS 0 ~7
-    0x40,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT2: WB:     VS 0 ~7
-    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT3: WW:     VS 0 ~7
-    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT4: VCOM:   VS 0 ~7
+pub(crate) const LUT_PARTIAL_UPDATE: [u8; 153] =[
+    0x00,0x40,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,    // LUT0: BB:     VS 0 ~11
+    0x80,0x80,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,    // LUT1: BW:     VS 0 ~11
+    0x40,0x40,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,    // LUT2: WB:     VS 0 ~11
+    0x00,0x80,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,    // LUT3: WW:     VS 0 ~11
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,0x00,    // LUT4: VCOM:   VS 0 ~11
+
+    0x14,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP0 A~D SR0 RP0
+    0x01,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP1 A~D SR1 RP1
+    0x01,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP2 A~D SR2 RP2
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP3 A~D SR3 RP3
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP4 A~D SR4 RP4
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP5 A~D SR5 RP5
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP6 A~D SR6 RP6
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP7 A~D SR7 RP7
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP8 A~D SR8 RP8
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP9 A~D SR9 RP9
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP10 A~D SR10 RP10
+    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             //  TP11 A~D SR11 RP11
 
-    0x0A,0x00,0x00,0x00,0x00,                       //  TP0 A~D RP0
-    0x00,0x00,0x00,0x00,0x00,                       //  TP1 A~D RP1
-    0x00,0x00,0x00,0x00,0x00,                       //  TP2 A~D RP2
-    0x00,0x00,0x00,0x00,0x00,                       //  TP3 A~D RP3
-    0x00,0x00,0x00,0x00,0x00,                       //  TP4 A~D RP4
-    0x00,0x00,0x00,0x00,0x00,                       //  TP5 A~D RP5
-    0x00,0x00,0x00,0x00,0x00,                       //  TP6 A~D RP6
+    0x22,0x22,0x22,0x22,0x22,0x22,                  //  FR0 ~ FR11
+    0x00,0x00,0x00,                                 //  XON
 ];
 
-#[rustfmt::skip]
-pub(crate) const LUT_PARTIAL_UPDATEZZ: [u8; 70] =[
-    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT0: BB:     VS 0 ~7
-    0x0,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT1: BW:     VS 0 ~7
-    0x0,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT2: WB:     VS 0 ~7
-    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT3: WW:     VS 0 ~7
-    0x00,0x00,0x00,0x00,0x00,0x00,0x00,             // LUT4: VCOM:   VS 0 ~7
+/// Estimate of [LUT_PARTIAL_UPDATE] for
+/// [TemperatureBand::Cold](crate::traits::TemperatureBand::Cold): the frames of all phases
+/// are stretched by 1.5, Waveshare and Good Display don't publish a cold waveform
+pub(crate) const LUT_PARTIAL_UPDATE_COLD: [u8; 153] = scale_timing(LUT_PARTIAL_UPDATE, 3, 2);
+/// Estimate of [LUT_PARTIAL_UPDATE] for
+/// [TemperatureBand::Hot](crate::traits::TemperatureBand::Hot): the frames of all phases
+/// are shortened to 0.75, Waveshare and Good Display don't publish a hot waveform
+pub(crate) const LUT_PARTIAL_UPDATE_HOT: [u8; 153] = scale_timing(LUT_PARTIAL_UPDATE, 3, 4);
 
-    0x0,0x00,0x00,0x00,0x00,                       //  TP0 A~D RP0
-    0x00,0x00,0x00,0x00,0x00,                       //  TP1 A~D RP1
-    0x00,0x00,0x00,0x00,0x00,                       //  TP2 A~D RP2
-    0x00,0x00,0x00,0x00,0x00,                       //  TP3 A~D RP3
-    0x00,0x00,0x00,0x00,0x00,                       //  TP4 A~D RP4
-    0x00,0x00,0x00,0x00,0x00,                       //  TP5 A~D RP5
-    0x00,0x00,0x00,0x00,0x00,                       //  TP6 A~D RP6
-];
+/// Scales the frames of the phases (TP\[nA\], TP\[nB\], TP\[nC\] and TP\[nD\]) of the 12
+/// timing groups of a 153 byte LUT, see [scale_phases]
+const fn scale_timing(lut: [u8; 153], num: u16, den: u16) -> [u8; 153] {
+    // TP[nA], TP[nB], SR[nAB], TP[nC], TP[nD], SR[nCD], RP[n]
+    scale_phases(lut, 60, 12, 7, [0, 1, 3, 4], num, den)
+}
+
+/// Sent along with [LUT_PARTIAL_UPDATE]: end option, gate voltage (20V), source voltages
+/// VSH1 (15V), VSH2 (off), VSL (-15V) and VCOM
+pub(crate) const LUT_PARTIAL_UPDATE_END_OPTION: u8 = 0x22;
+pub(crate) const LUT_PARTIAL_UPDATE_GATE_VOLTAGE: u8 = 0x17;
+pub(crate) const LUT_PARTIAL_UPDATE_SOURCE_VOLTAGES: [u8; 3] = [0x41, 0x00, 0x32];
+pub(crate) const LUT_PARTIAL_UPDATE_VCOM: u8 = 0x36;
@@ -1,69 +1,18 @@
 use crate::buffer_len;
-use crate::epd2in13_GDEQ0213B74::{DEFAULT_BACKGROUND_COLOR, HEIGHT, WIDTH};
-use crate::graphics::{Display, DisplayRotation};
+use crate::display_buffer::DisplayBuffer;
+use crate::epd2in13_GDEQ0213B74::{HEIGHT, WIDTH};
 use embedded_graphics_core::pixelcolor::BinaryColor;
-use embedded_graphics_core::prelude::*;
 
 /// Full size buffer for use with the 2in13 v2 EPD
 ///
-/// Can also be manually constructed:
-/// `buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); WIDTH / 8 * HEIGHT]`
-pub struct Display2in13 {
-    buffer: [u8; buffer_len(WIDTH as usize, HEIGHT as usize)],
-    rotation: DisplayRotation,
-}
-
-impl Default for Display2in13 {
-    fn default() -> Self {
-        Display2in13 {
-            buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value();
-                buffer_len(WIDTH as usize, HEIGHT as usize)],
-            rotation: DisplayRotation::default(),
-        }
-    }
-}
-
-impl DrawTarget for Display2in13 {
-    type Color = BinaryColor;
-    type Error = core::convert::Infallible;
-
-    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = Pixel<Self::Color>>,
-    {
-        for pixel in pixels {
-            self.draw_helper(WIDTH, HEIGHT, pixel)?;
-        }
-        Ok(())
-    }
-}
-
-impl OriginDimensions for Display2in13 {
-    fn size(&self) -> Size {
-        match self.rotation() {
-            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => Size::new(WIDTH, HEIGHT),
-            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => Size::new(HEIGHT, WIDTH),
-        }
-    }
-}
-
-impl Display for Display2in13 {
-    fn buffer(&self) -> &[u8] {
-        &self.buffer
-    }
-
-    fn get_mut_buffer(&mut self) -> &mut [u8] {
-        &mut self.buffer
-    }
-
-    fn set_rotation(&mut self, rotation: DisplayRotation) {
-        self.rotation = rotation;
-    }
-
-    fn rotation(&self) -> DisplayRotation {
-        self.rotation
-    }
-}
+/// Its [size](embedded_graphics_core::geometry::OriginDimensions::size) follows the rotation.
+pub type Display2in13 = DisplayBuffer<
+    WIDTH,
+    HEIGHT,
+    { buffer_len(WIDTH as usize, HEIGHT as usize) },
+    BinaryColor,
+    true,
+>;
 
 #[cfg(test)]
 mod tests {
@@ -1,62 +1,45 @@
-use crate::epd2in13_GDEW0213T5D::{DEFAULT_BACKGROUND_COLOR, FRAME_BUFFER_SIZE, HEIGHT, WIDTH};
-use crate::graphics::{Display, DisplayRotation};
-use embedded_graphics_core::pixelcolor::BinaryColor;
-use embedded_graphics_core::prelude::*;
+use crate::color::QuadColor;
+use crate::display_buffer::DisplayBuffer;
+use crate::epd2in13_GDEW0213T5D::{FRAME_BUFFER_SIZE, GRAY_FRAME_BUFFER_SIZE, HEIGHT, WIDTH};
 
-/// Full size buffer for use with the 4in2 EPD
-///
-/// Can also be manuall constructed:
-/// `buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); WIDTH / 8 * HEIGHT]`
-pub struct DisplayT5D {
-    // buffer: [u8; WIDTH as usize * HEIGHT as usize / 8],
-    buffer: [u8; FRAME_BUFFER_SIZE],
-    rotation: DisplayRotation,
-}
+/// Full size buffer for use with the GDEW0213T5D EPD
+pub type DisplayT5D = DisplayBuffer<WIDTH, HEIGHT, FRAME_BUFFER_SIZE>;
 
-impl Default for DisplayT5D {
-    fn default() -> Self {
-        DisplayT5D {
-            buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); FRAME_BUFFER_SIZE],
-            rotation: DisplayRotation::default(),
-        }
-    }
-}
-
-impl DrawTarget for DisplayT5D {
-    type Color = BinaryColor;
-    type Error = core::convert::Infallible;
-    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = Pixel<Self::Color>>,
-    {
-        for pixel in pixels {
-            self.draw_helper(WIDTH, HEIGHT, pixel)?;
-        }
-        Ok(())
-    }
-}
-
-impl OriginDimensions for DisplayT5D {
-    fn size(&self) -> Size {
-        Size::new(WIDTH, HEIGHT)
-    }
-}
-
-impl Display for DisplayT5D {
-    fn buffer(&self) -> &[u8] {
-        &self.buffer
+/// Full size 4 grayscale buffer for use with the GDEW0213T5D EPD
+///
+/// Stores 2 bits per pixel and is sent with
+/// [update_gray_frame](crate::traits::GrayscaleDisplay::update_gray_frame). Its
+/// [size](embedded_graphics_core::geometry::OriginDimensions::size) follows the rotation.
+pub type DisplayT5DGray = DisplayBuffer<WIDTH, HEIGHT, GRAY_FRAME_BUFFER_SIZE, QuadColor, true>;
+
+#[cfg(test)]
+mod gray_tests {
+    use super::*;
+    use crate::graphics::QuadDisplay;
+    use embedded_graphics::{
+        prelude::*,
+        primitives::{Line, PrimitiveStyle},
+    };
+
+    #[test]
+    fn gray_graphics_size() {
+        let display = DisplayT5DGray::default();
+        assert_eq!(display.buffer().len(), 5512);
     }
 
-    fn get_mut_buffer(&mut self) -> &mut [u8] {
-        &mut self.buffer
-    }
+    #[test]
+    fn gray_graphics_rotation_0() {
+        let mut display = DisplayT5DGray::default();
+        let _ = Line::new(Point::new(0, 0), Point::new(3, 0))
+            .into_styled(PrimitiveStyle::with_stroke(QuadColor::DarkGray, 1))
+            .draw(&mut display);
 
-    fn set_rotation(&mut self, rotation: DisplayRotation) {
-        self.rotation = rotation;
-    }
+        let buffer = display.buffer();
 
-    fn rotation(&self) -> DisplayRotation {
-        self.rotation
+        assert_eq!(buffer[0], QuadColor::DarkGray.get_byte_value());
+        for &byte in buffer.iter().skip(1) {
+            assert_eq!(byte, QuadColor::White.get_byte_value());
+        }
     }
 }
 
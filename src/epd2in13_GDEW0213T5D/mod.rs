@@ -1,4 +1,4 @@
-//! A simple Driver for the 2.13" Good Display EPD GDEW0213T5D
+//! A simple Driver for the 2.13" Good Display EPD GDEW0213T5D, also used on Lilygo T5 boards
 //!
 //!
 //! Screen supports 4 grayscales and partial updating. For now in
@@ -9,11 +9,11 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 //!};
-//!use epd_waveshare::{Epd2in13_T5D::*, prelude::*};
+//!use epd_waveshare::{epd2in13_GDEW0213T5D::*, prelude::*};
 //!#
 //!# let expectations = [];
 //!# let mut spi = spi::Mock::new(&expectations);
@@ -48,19 +48,15 @@
 //!
 //!
 //! BE CAREFUL! The screen can get ghosting/burn-ins through the Partial Fast Update Drawing.
+//!
+//! Preset of the [UC8151 driver](crate::uc8151), which also describes the grayscale mode,
+//! custom waveforms and temperature compensation. Grayscale frames are drawn into a
+//! [DisplayT5DGray] and sent with [update_and_display_gray_frame](crate::traits::GrayscaleDisplay::update_and_display_gray_frame).
 
-use embedded_hal::{
-    blocking::{delay::*, spi::Write},
-    digital::v2::*,
-};
-
-use crate::interface::DisplayInterface;
-use crate::traits::{InternalWiAdditions, QuickRefresh, RefreshLut, WaveshareDisplay};
+use crate::color::Color;
+use crate::uc8151::{Panel, Uc8151};
 
-// For now, we use the internal LUT's in OTP memory
-// The Lookup Tables for the Display
-mod constants;
-use crate::epd2in13_GDEW0213T5D::constants::*;
+pub use crate::uc8151::{Level, LutSource, Phase, Uc8151Lut, Waveform};
 
 /// Width of the display.
 pub const WIDTH: u32 = 104;
@@ -74,508 +70,30 @@ pub const NUM_PIXELS: u32 = WIDTH * HEIGHT;
 /// Number of pixels * 1bpp (* 1/8 bytes);
 pub const FRAME_BUFFER_SIZE: usize = NUM_PIXELS as usize / 8;
 
+/// Number of pixels * 2bpp (* 1/8 bytes), used for the 4 grayscale mode
+pub const GRAY_FRAME_BUFFER_SIZE: usize = NUM_PIXELS as usize / 4;
+
 /// Default Background Color
 pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
 
-const IS_BUSY_LOW: bool = true;
-
-use crate::color::Color;
-
-pub(crate) mod command;
-use self::command::Command;
-
 #[cfg(feature = "graphics")]
 mod graphics;
 #[cfg(feature = "graphics")]
-pub use self::graphics::DisplayT5D;
-
-/// Epd2in13_T5D driver
-///
-pub struct Epd2in13_T5D<SPI, CS, BUSY, DC, RST, DELAY> {
-    /// Connection Interface
-    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>,
-    /// Background Color
-    color: Color,
-    /// Refresh LUT
-    refresh: RefreshLut,
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd2in13_T5D<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayMs<u8>,
-{
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        // reset the device
-        self.interface.reset(delay, 10);
-        self.interface.reset(delay, 20);
-        self.interface.reset(delay, 20);
-
-        // set the power settings
-        self.interface.cmd_with_data(
-            spi,
-            Command::PowerSetting,
-            // &[0x03, 0x00, 0x2b, 0x2b, 0xff],
-            &[0x03, 0x00, 0x2b, 0x2b, 0x03],
-        )?;
-
-        // start the booster
-        self.interface
-            .cmd_with_data(spi, Command::BoosterSoftStart, &[0x17, 0x17, 0x17])?;
-
-        // power on
-        self.command(spi, Command::PowerOn)?;
-        delay.delay_ms(5);
-        self.wait_until_idle();
-
-        // set the panel settings
-        self.cmd_with_data(spi, Command::PanelSetting, &[0x3F])?; // from registers
-                                                                  // self.cmd_with_data(spi, Command::PanelSetting, &[0x1F])?; // LUT From OTP
+pub use self::graphics::{DisplayT5D, DisplayT5DGray};
 
-        // Set Frequency, 200 Hz didn't work on my board
-        // 150Hz and 171Hz wasn't tested yet
-        // TODO: Test these other frequencies
-        // 0x3a -> 100Hz, 0x29 -> 150Hz, 0x39 -> 200Hz, 0x31 -> 171Hz, 0x3c -> 50Hz (default)
-        self.cmd_with_data(spi, Command::PllControl, &[0x3a])?;
+/// Good Display GDEW0213T5D panel
+pub struct Gdew0213t5d;
 
-        self.send_resolution(spi)?;
-
-        self.interface
-            .cmd_with_data(spi, Command::VcmDcSetting, &[0x12])?;
-
-        //VBDF 17|D7 VBDW 97  VBDB 57  VBDF F7  VBDW 77  VBDB 37  VBDR B7
-        self.interface
-            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x97])?;
-
-        self.set_lut(spi, None)?;
-
-        self.wait_until_idle();
-        Ok(())
-    }
+impl Panel for Gdew0213t5d {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd2in13_T5D<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayMs<u8>,
-{
-    type DisplayColor = Color;
-    fn new(
-        spi: &mut SPI,
-        cs: CS,
-        busy: BUSY,
-        dc: DC,
-        rst: RST,
-        delay: &mut DELAY,
-    ) -> Result<Self, SPI::Error> {
-        let interface = DisplayInterface::new(cs, busy, dc, rst);
-        let color = DEFAULT_BACKGROUND_COLOR;
-
-        let mut epd = Epd2in13_T5D {
-            interface,
-            color,
-            refresh: RefreshLut::Quick,
-        };
-
-        epd.init(spi, delay)?;
-
-        Ok(epd)
-    }
-
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.init(spi, delay)
-    }
-
-    fn sleep(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.interface
-            .cmd_with_data(spi, Command::VcomAndDataIntervalSetting, &[0x17])?; //border floating
-        self.command(spi, Command::VcmDcSetting)?; // VCOM to 0V
-        self.command(spi, Command::PanelSetting)?;
-
-        self.command(spi, Command::PowerSetting)?; //VG&VS to 0V fast
-        for _ in 0..4 {
-            self.send_data(spi, &[0x00])?;
-        }
-
-        self.command(spi, Command::PowerOff)?;
-        self.wait_until_idle();
-        self.interface
-            .cmd_with_data(spi, Command::DeepSleep, &[0xA5])?;
-        Ok(())
-    }
-
-    fn update_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        _delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        let color_value = self.color.get_byte_value();
-
-        self.interface.cmd(spi, Command::DataStartTransmission1)?;
-        self.interface
-            .data_x_times(spi, color_value, WIDTH / 8 * HEIGHT)?;
-
-        self.interface
-            .cmd_with_data(spi, Command::DataStartTransmission2, buffer)?;
-        Ok(())
-    }
-
-    fn update_partial_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        if buffer.len() as u32 != width / 8 * height {
-            //TODO: panic!! or sth like that
-            //return Err("Wrong buffersize");
-        }
-
-        self.command(spi, Command::PartialIn)?;
-        self.command(spi, Command::PartialWindow)?;
-        self.send_data(spi, &[(x >> 8) as u8])?;
-        let tmp = x & 0xf8;
-        self.send_data(spi, &[tmp as u8])?; // x should be the multiple of 8, the last 3 bit will always be ignored
-        let tmp = tmp + width - 1;
-        self.send_data(spi, &[(tmp >> 8) as u8])?;
-        self.send_data(spi, &[(tmp | 0x07) as u8])?;
-
-        self.send_data(spi, &[(y >> 8) as u8])?;
-        self.send_data(spi, &[y as u8])?;
-
-        self.send_data(spi, &[((y + height - 1) >> 8) as u8])?;
-        self.send_data(spi, &[(y + height - 1) as u8])?;
-
-        self.send_data(spi, &[0x01])?; // Gates scan both inside and outside of the partial window. (default)
-
-        //TODO: handle dtm somehow
-        let is_dtm1 = false;
-        if is_dtm1 {
-            self.command(spi, Command::DataStartTransmission1)? //TODO: check if data_start transmission 1 also needs "old"/background data here
-        } else {
-            self.command(spi, Command::DataStartTransmission2)?
-        }
-
-        self.send_data(spi, buffer)?;
-
-        self.command(spi, Command::PartialOut)?;
-        Ok(())
-    }
-
-    fn display_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
-    }
-
-    fn update_and_display_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_frame(spi, buffer, delay)?;
-        self.command(spi, Command::DisplayRefresh)?;
-        Ok(())
-    }
-
-    fn clear_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.send_resolution(spi)?;
-
-        let color_value = self.color.get_byte_value();
-
-        self.interface.cmd(spi, Command::DataStartTransmission1)?;
-        self.interface
-            .data_x_times(spi, color_value, WIDTH / 8 * HEIGHT)?;
-
-        self.interface.cmd(spi, Command::DataStartTransmission2)?;
-        self.interface
-            .data_x_times(spi, color_value, WIDTH / 8 * HEIGHT)?;
-        Ok(())
-    }
-
-    fn set_background_color(&mut self, color: Color) {
-        self.color = color;
-    }
-
-    fn background_color(&self) -> &Color {
-        &self.color
-    }
-
-    fn width(&self) -> u32 {
-        WIDTH
-    }
-
-    fn height(&self) -> u32 {
-        HEIGHT
-    }
-
-    fn set_lut(
-        &mut self,
-        spi: &mut SPI,
-        refresh_rate: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error> {
-        if let Some(refresh_lut) = refresh_rate {
-            self.refresh = refresh_lut;
-        }
-
-        match self.refresh {
-            RefreshLut::Full => self.set_lut_helper(spi, &constants::FULL_LUT),
-            RefreshLut::Quick => self.set_lut_helper(spi, &constants::DIRECT_LUT),
-            _ => self.set_lut_helper(spi, &constants::Uc8151Lut::default()),
-        }
-    }
-
-    fn is_busy(&self) -> bool {
-        self.interface.is_busy(IS_BUSY_LOW)
-    }
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> Epd2in13_T5D<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayMs<u8>,
-{
-    fn command(&mut self, spi: &mut SPI, command: Command) -> Result<(), SPI::Error> {
-        self.interface.cmd(spi, command)
-    }
-
-    fn send_data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
-        self.interface.data(spi, data)
-    }
-
-    fn cmd_with_data(
-        &mut self,
-        spi: &mut SPI,
-        command: Command,
-        data: &[u8],
-    ) -> Result<(), SPI::Error> {
-        self.interface.cmd_with_data(spi, command, data)
-    }
-
-    fn wait_until_idle(&mut self) {
-        let _ = self.interface.wait_until_idle(IS_BUSY_LOW);
-    }
-
-    fn send_resolution(&mut self, spi: &mut SPI) -> Result<(), SPI::Error> {
-        let w = self.width();
-        let h = self.height();
-
-        self.command(spi, Command::ResolutionSetting)?;
-        self.send_data(spi, &[w as u8 & 0xf8])?;
-        self.send_data(spi, &[(h >> 8) as u8])?;
-        self.send_data(spi, &[h as u8])
-    }
-
-    fn set_lut_helper(&mut self, spi: &mut SPI, lut: &Uc8151Lut) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.cmd_with_data(spi, Command::LutForVcom, &lut.vcom0)?;
-        self.cmd_with_data(spi, Command::LutWhiteToWhite, &lut.ww)?;
-        self.cmd_with_data(spi, Command::LutBlackToWhite, &lut.bw)?;
-        self.cmd_with_data(spi, Command::LutWhiteToBlack, &lut.wb)?;
-        self.cmd_with_data(spi, Command::LutBlackToBlack, &lut.bb)?;
-        Ok(())
-    }
-
-    /// Helper function. Sets up the display to send pixel data to a custom
-    /// starting point.
-    pub fn shift_display(
-        &mut self,
-        spi: &mut SPI,
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Result<(), SPI::Error> {
-        self.send_data(spi, &[(x >> 8) as u8])?;
-        let tmp = x & 0xf8;
-        self.send_data(spi, &[tmp as u8])?; // x should be the multiple of 8, the last 3 bit will always be ignored
-        let tmp = tmp + width - 1;
-        self.send_data(spi, &[(tmp >> 8) as u8])?;
-        self.send_data(spi, &[(tmp | 0x07) as u8])?;
-
-        self.send_data(spi, &[(y >> 8) as u8])?;
-        self.send_data(spi, &[y as u8])?;
-
-        self.send_data(spi, &[((y + height - 1) >> 8) as u8])?;
-        self.send_data(spi, &[(y + height - 1) as u8])?;
-
-        self.send_data(spi, &[0x01])?; // Gates scan both inside and outside of the partial window. (default)
-
-        Ok(())
-    }
-}
-
-impl<SPI, CS, BUSY, DC, RST, DELAY> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
-    for Epd2in13_T5D<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayMs<u8>,
-{
-    /// To be followed immediately after by `update_old_frame`.
-    fn update_old_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        _delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-
-        self.interface.cmd(spi, Command::DataStartTransmission1)?;
-
-        self.interface.data(spi, buffer)?;
-
-        Ok(())
-    }
-
-    /// To be used immediately after `update_old_frame`.
-    fn update_new_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        _delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        // self.send_resolution(spi)?;
-
-        self.interface.cmd(spi, Command::DataStartTransmission2)?;
-
-        self.interface.data(spi, buffer)?;
-
-        Ok(())
-    }
-
-    /// This is a wrapper around `display_frame` for using this device as a true
-    /// `QuickRefresh` device.
-    fn display_new_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error> {
-        self.display_frame(spi, delay)
-    }
-
-    /// This is wrapper around `update_new_frame` and `display_frame` for using
-    /// this device as a true `QuickRefresh` device.
-    ///
-    /// To be used immediately after `update_old_frame`.
-    fn update_and_display_new_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        delay: &mut DELAY,
-    ) -> Result<(), SPI::Error> {
-        self.update_new_frame(spi, buffer, delay)?;
-        self.display_frame(spi, delay)
-    }
-
-    fn update_partial_old_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-
-        if buffer.len() as u32 != width / 8 * height {
-            //TODO: panic!! or sth like that
-            //return Err("Wrong buffersize");
-        }
-
-        self.interface.cmd(spi, Command::PartialIn)?;
-        self.interface.cmd(spi, Command::PartialWindow)?;
-
-        self.shift_display(spi, x, y, width, height)?;
-
-        self.interface.cmd(spi, Command::DataStartTransmission1)?;
-
-        self.interface.data(spi, buffer)?;
-
-        Ok(())
-    }
-
-    /// Always call `update_partial_old_frame` before this, with buffer-updating code
-    /// between the calls.
-    fn update_partial_new_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        if buffer.len() as u32 != width / 8 * height {
-            //TODO: panic!! or sth like that
-            //return Err("Wrong buffersize");
-        }
-
-        self.shift_display(spi, x, y, width, height)?;
-
-        self.interface.cmd(spi, Command::DataStartTransmission2)?;
-
-        self.interface.data(spi, buffer)?;
-
-        self.interface.cmd(spi, Command::PartialOut)?;
-        Ok(())
-    }
-
-    fn clear_partial_frame(
-        &mut self,
-        spi: &mut SPI,
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Result<(), SPI::Error> {
-        self.wait_until_idle();
-        self.send_resolution(spi)?;
-
-        let color_value = self.color.get_byte_value();
-
-        self.interface.cmd(spi, Command::PartialIn)?;
-        self.interface.cmd(spi, Command::PartialWindow)?;
-
-        self.shift_display(spi, x, y, width, height)?;
-
-        self.interface.cmd(spi, Command::DataStartTransmission1)?;
-        self.interface
-            .data_x_times(spi, color_value, width / 8 * height)?;
-
-        self.interface.cmd(spi, Command::DataStartTransmission2)?;
-        self.interface
-            .data_x_times(spi, color_value, width / 8 * height)?;
-
-        self.interface.cmd(spi, Command::PartialOut)?;
-        Ok(())
-    }
-}
+/// Epd2in13_T5D driver
+///
+#[allow(non_camel_case_types)]
+pub type Epd2in13_T5D<SPI, CS, BUSY, DC, RST, DELAY> =
+    Uc8151<SPI, CS, BUSY, DC, RST, DELAY, Gdew0213t5d>;
 
 #[cfg(test)]
 mod tests {
@@ -587,4 +105,14 @@ mod tests {
         assert_eq!(HEIGHT, 212);
         assert_eq!(DEFAULT_BACKGROUND_COLOR, Color::White);
     }
+
+    #[test]
+    fn panel_info() {
+        use crate::traits::PanelInfo;
+
+        type Epd = Epd2in13_T5D<(), (), (), (), (), ()>;
+        assert_eq!((Epd::WIDTH, Epd::HEIGHT), (WIDTH, HEIGHT));
+        assert_eq!(Epd::COLOR_DEPTH, 1);
+        assert_eq!(Epd::IS_BUSY_LOW, Gdew0213t5d::IS_BUSY_LOW);
+    }
 }
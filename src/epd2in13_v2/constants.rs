@@ -1,6 +1,5 @@
-#[rustfmt::skip]
-
 // Original Waveforms from Waveshare
+#[rustfmt::skip]
 pub(crate) const LUT_FULL_UPDATE: [u8; 70] =[
     0x80,0x60,0x40,0x00,0x00,0x00,0x00,             // LUT0: BB:     VS 0 ~7
     0x10,0x60,0x20,0x00,0x00,0x00,0x00,             // LUT1: BW:     VS 0 ~7
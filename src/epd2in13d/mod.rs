@@ -0,0 +1,42 @@
+//! A Driver for the flexible Waveshare 2.13" E-Ink Display (D) via SPI
+//!
+//! Preset of the [UC8151 driver](crate::uc8151): the panel has an IL0373 controller and the
+//! resolution of the [GDEW0213T5D](crate::epd2in13_GDEW0213T5D), whose waveforms and display
+//! buffers it uses.
+//!
+//! Untested on the panel: the waveforms and init sequence of the Waveshare driver of this panel
+//! aren't ported, quick and partial refreshes may look different from the ones of the
+//! GDEW0213T5D.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/2.13inch_e-Paper_HAT_(D))
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_2in13d.c)
+
+use crate::color::Color;
+use crate::uc8151::{Panel, Uc8151};
+
+#[cfg(feature = "graphics")]
+pub use crate::epd2in13_GDEW0213T5D::{
+    DisplayT5D as Display2in13d, DisplayT5DGray as Display2in13dGray,
+};
+
+/// Width of the display
+pub const WIDTH: u32 = 104;
+/// Height of the display
+pub const HEIGHT: u32 = 212;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// Waveshare 2.13" (D) flexible panel
+pub struct Waveshare2in13d;
+
+impl Panel for Waveshare2in13d {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+}
+
+/// Epd2in13 (D) driver
+///
+pub type Epd2in13d<SPI, CS, BUSY, DC, RST, DELAY> =
+    Uc8151<SPI, CS, BUSY, DC, RST, DELAY, Waveshare2in13d>;
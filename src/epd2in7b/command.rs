@@ -5,7 +5,7 @@ use crate::traits;
 ///
 /// More information can be found in the [specification](https://www.waveshare.com/w/upload/d/d8/2.7inch-e-paper-b-specification.pdf)
 #[allow(dead_code)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub(crate) enum Command {
     /// Set Resolution, LUT selection, BWR pixels, gate scan direction, source shift direction, booster switch, soft reset
     PanelSetting = 0x00,
@@ -124,6 +124,54 @@ impl traits::Command for Command {
     }
 }
 
+impl Command {
+    /// The command with this address, used to decode [traces](crate::trace)
+    #[cfg(any(test, feature = "std"))]
+    pub(crate) fn from_address(address: u8) -> Option<Self> {
+        Some(match address {
+            0x00 => Command::PanelSetting,
+            0x01 => Command::PowerSetting,
+            0x02 => Command::PowerOff,
+            0x03 => Command::PowerOffSequenceSetting,
+            0x04 => Command::PowerOn,
+            0x05 => Command::PowerOnMeasure,
+            0x06 => Command::BoosterSoftStart,
+            0x07 => Command::DeepSleep,
+            0x10 => Command::DataStartTransmission1,
+            0x11 => Command::DataStop,
+            0x12 => Command::DisplayRefresh,
+            0x13 => Command::DataStartTransmission2,
+            0x14 => Command::PartialDataStartTransmission1,
+            0x15 => Command::PartialDataStartTransmission2,
+            0x16 => Command::PartialDisplayRefresh,
+            0x20 => Command::LutForVcom,
+            0x21 => Command::LutWhiteToWhite,
+            0x22 => Command::LutBlackToWhite,
+            0x23 => Command::LutWhiteToBlack,
+            0x24 => Command::LutBlackToBlack,
+            0x30 => Command::PllControl,
+            0x40 => Command::TemperatureSensor,
+            0x41 => Command::TemperatureSensorCalibration,
+            0x42 => Command::TemperatureSensorWrite,
+            0x43 => Command::TemperatureSensorRead,
+            0x50 => Command::VcomAndDataIntervalSetting,
+            0x51 => Command::LowPowerDetection,
+            0x60 => Command::TconSetting,
+            0x61 => Command::ResolutionSetting,
+            0x62 => Command::SourceAndGateSetting,
+            0x71 => Command::GetStatus,
+            0x80 => Command::AutoMeasurementVcom,
+            0x81 => Command::ReadVcomValue,
+            0x82 => Command::VcmDcSetting,
+            0xA0 => Command::ProgramMode,
+            0xA1 => Command::ActiveProgramming,
+            0xA2 => Command::ReadOtp,
+            0xF8 => Command::PowerOptimization,
+            _ => return None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
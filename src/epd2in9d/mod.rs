@@ -0,0 +1,68 @@
+//! A Driver for the flexible Waveshare 2.9" E-Ink Display (D) via SPI
+//!
+//! Preset of the [UC8151 driver](crate::uc8151) with an IL0373 controller and the waveforms of
+//! the [GDEW0213T5D](crate::epd2in13_GDEW0213T5D). It has the resolution of the
+//! [Epd 2in9](crate::epd2in9), whose display buffer it uses.
+//!
+//! Untested on the panel: the waveforms and init sequence of the Waveshare driver of this panel
+//! aren't ported, quick and partial refreshes may look different from the ones of the
+//! GDEW0213T5D.
+//!
+//! # References
+//!
+//! - [Waveshare product page](https://www.waveshare.com/wiki/2.9inch_e-Paper_HAT_(D))
+//! - [Waveshare C driver](https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/c/lib/e-Paper/EPD_2in9d.c)
+
+use crate::color::Color;
+use crate::uc8151::{Panel, Uc8151};
+
+#[cfg(feature = "graphics")]
+pub use crate::epd2in9::Display2in9 as Display2in9d;
+
+/// Width of the display
+pub const WIDTH: u32 = 128;
+/// Height of the display
+pub const HEIGHT: u32 = 296;
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+/// Waveshare 2.9" (D) flexible panel
+pub struct Waveshare2in9d;
+
+impl Panel for Waveshare2in9d {
+    const WIDTH: u32 = WIDTH;
+    const HEIGHT: u32 = HEIGHT;
+}
+
+/// Epd2in9 (D) driver
+///
+pub type Epd2in9d<SPI, CS, BUSY, DC, RST, DELAY> =
+    Uc8151<SPI, CS, BUSY, DC, RST, DELAY, Waveshare2in9d>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_len;
+    use crate::simulator::SimulatedPanel;
+    use crate::traits::{PartialUpdate, WaveshareDisplay};
+
+    #[test]
+    fn preset_on_panel() {
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in9d<_, _, _, _, _, _>>();
+        assert_eq!((epd.width(), epd.height()), (128, 296));
+
+        let mut buffer = std::vec![0xFF; buffer_len(WIDTH as usize, HEIGHT as usize)];
+        buffer[16 * 295 + 15] = 0x00;
+        epd.update_and_display_frame(&mut spi, &buffer, &mut delay)
+            .unwrap();
+        assert!(panel.image().is_black_window(120, 295, 8, 1));
+
+        epd.clear_frame(&mut spi, &mut delay).unwrap();
+        epd.update_partial_frame(&mut spi, &[0x00; 2 * 4], 104, 280, 16, 4, &mut delay)
+            .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+        assert!(panel.image().is_black_window(104, 280, 16, 4));
+        assert_eq!(panel.violations(), []);
+    }
+}
@@ -8,7 +8,7 @@ use crate::traits;
 ///
 /// The description of the single commands is mostly taken from IL0398.pdf
 #[allow(dead_code)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub(crate) enum Command {
     /// Set Resolution, LUT selection, BWR pixels, gate scan direction, source shift direction, booster switch, soft reset
     /// One Byte of Data:
@@ -161,6 +161,55 @@ impl traits::Command for Command {
     }
 }
 
+impl Command {
+    /// The command with this address, used to decode [traces](crate::trace)
+    #[cfg(any(test, feature = "std"))]
+    pub(crate) fn from_address(address: u8) -> Option<Self> {
+        Some(match address {
+            0x00 => Command::PanelSetting,
+            0x01 => Command::PowerSetting,
+            0x02 => Command::PowerOff,
+            0x03 => Command::PowerOffSequenceSetting,
+            0x04 => Command::PowerOn,
+            0x05 => Command::PowerOnMeasure,
+            0x06 => Command::BoosterSoftStart,
+            0x07 => Command::DeepSleep,
+            0x10 => Command::DataStartTransmission1,
+            0x11 => Command::DataStop,
+            0x12 => Command::DisplayRefresh,
+            0x13 => Command::DataStartTransmission2,
+            0x20 => Command::LutForVcom,
+            0x21 => Command::LutWhiteToWhite,
+            0x22 => Command::LutBlackToWhite,
+            0x23 => Command::LutWhiteToBlack,
+            0x24 => Command::LutBlackToBlack,
+            0x30 => Command::PllControl,
+            0x40 => Command::TemperatureSensor,
+            0x41 => Command::TemperatureSensorSelection,
+            0x42 => Command::TemperatureSensorWrite,
+            0x43 => Command::TemperatureSensorRead,
+            0x50 => Command::VcomAndDataIntervalSetting,
+            0x51 => Command::LowPowerDetection,
+            0x60 => Command::TconSetting,
+            0x61 => Command::ResolutionSetting,
+            0x65 => Command::GsstSetting,
+            0x70 => Command::Revision,
+            0x71 => Command::GetStatus,
+            0x80 => Command::AutoMeasurementVcom,
+            0x81 => Command::ReadVcomValue,
+            0x82 => Command::VcmDcSetting,
+            0x90 => Command::PartialWindow,
+            0x91 => Command::PartialIn,
+            0x92 => Command::PartialOut,
+            0xA0 => Command::ProgramMode,
+            0xA1 => Command::ActiveProgramming,
+            0xA2 => Command::ReadOtp,
+            0xE3 => Command::PowerSaving,
+            _ => return None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
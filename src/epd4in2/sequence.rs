@@ -0,0 +1,208 @@
+//! Command sequences of the 4.2" display, shared by the blocking and the async driver
+
+use super::command::Command;
+use super::constants::*;
+use super::{HEIGHT, WIDTH};
+use crate::sequence::Step;
+use crate::traits::RefreshLut;
+
+/// Bytes of one full frame
+const FRAME_BYTES: u32 = WIDTH / 8 * HEIGHT;
+
+/// Data of [Command::ResolutionSetting]
+const RESOLUTION: [u8; 4] = [
+    (WIDTH >> 8) as u8,
+    WIDTH as u8,
+    (HEIGHT >> 8) as u8,
+    HEIGHT as u8,
+];
+
+pub(super) fn init() -> [Step<'static>; 18] {
+    [
+        // reset the device
+        Step::Reset(10),
+        // set the power settings
+        Step::cmd(Command::PowerSetting),
+        Step::Data(&[0x03, 0x00, 0x2b, 0x2b, 0xff]),
+        // start the booster
+        Step::cmd(Command::BoosterSoftStart),
+        Step::Data(&[0x17, 0x17, 0x17]),
+        // power on
+        Step::cmd(Command::PowerOn),
+        Step::Delay(5),
+        Step::WaitUntilIdle,
+        // set the panel settings
+        Step::cmd(Command::PanelSetting),
+        Step::Data(&[0x3F]),
+        // Set Frequency, 200 Hz didn't work on my board
+        // 150Hz and 171Hz wasn't tested yet
+        // TODO: Test these other frequencies
+        // 3A 100HZ   29 150Hz 39 200HZ  31 171HZ DEFAULT: 3c 50Hz
+        Step::cmd(Command::PllControl),
+        Step::Data(&[0x3A]),
+        Step::cmd(Command::ResolutionSetting),
+        Step::Data(&RESOLUTION),
+        Step::cmd(Command::VcmDcSetting),
+        Step::Data(&[0x12]),
+        //VBDF 17|D7 VBDW 97  VBDB 57  VBDF F7  VBDW 77  VBDB 37  VBDR B7
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(&[0x97]),
+    ]
+}
+
+pub(super) fn sleep() -> [Step<'static>; 11] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(&[0x17]),              //border floating
+        Step::cmd(Command::VcmDcSetting), // VCOM to 0V
+        Step::cmd(Command::PanelSetting),
+        Step::cmd(Command::PowerSetting), //VG&VS to 0V fast
+        Step::DataRepeat(0x00, 4),
+        Step::cmd(Command::PowerOff),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DeepSleep),
+        Step::Data(&[0xA5]),
+    ]
+}
+
+pub(super) fn update_frame(color: u8, buffer: &[u8]) -> [Step<'_>; 5] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, FRAME_BYTES),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::Data(buffer),
+    ]
+}
+
+pub(super) fn begin_frame(color: u8) -> [Step<'static>; 4] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, FRAME_BYTES),
+        Step::cmd(Command::DataStartTransmission2),
+    ]
+}
+
+pub(super) fn display_frame() -> [Step<'static>; 2] {
+    [Step::WaitUntilIdle, Step::cmd(Command::DisplayRefresh)]
+}
+
+pub(super) fn update_and_display_frame(color: u8, buffer: &[u8]) -> [Step<'_>; 6] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, FRAME_BYTES),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::Data(buffer),
+        Step::cmd(Command::DisplayRefresh),
+    ]
+}
+
+pub(super) fn clear_frame(color: u8) -> [Step<'static>; 7] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::ResolutionSetting),
+        Step::Data(&RESOLUTION),
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, FRAME_BYTES),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::DataRepeat(color, FRAME_BYTES),
+    ]
+}
+
+pub(super) fn set_lut(refresh: RefreshLut) -> [Step<'static>; 11] {
+    let (vcom, ww, bw, wb, bb) = match refresh {
+        RefreshLut::Full => (&LUT_VCOM0, &LUT_WW, &LUT_BW, &LUT_WB, &LUT_BB),
+        RefreshLut::Quick => (
+            &LUT_VCOM0_QUICK,
+            &LUT_WW_QUICK,
+            &LUT_BW_QUICK,
+            &LUT_WB_QUICK,
+            &LUT_BB_QUICK,
+        ),
+    };
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::LutForVcom),
+        Step::Data(vcom),
+        Step::cmd(Command::LutWhiteToWhite),
+        Step::Data(ww),
+        Step::cmd(Command::LutBlackToWhite),
+        Step::Data(bw),
+        Step::cmd(Command::LutWhiteToBlack),
+        Step::Data(wb),
+        Step::cmd(Command::LutBlackToBlack),
+        Step::Data(bb),
+    ]
+}
+
+/// Sends `buffer` as old (DTM1) or new (DTM2) frame
+pub(super) fn update_old_or_new_frame(old: bool, buffer: &[u8]) -> [Step<'_>; 3] {
+    let command = if old {
+        Command::DataStartTransmission1
+    } else {
+        Command::DataStartTransmission2
+    };
+    [Step::WaitUntilIdle, Step::cmd(command), Step::Data(buffer)]
+}
+
+pub(super) fn update_partial_old_frame<'a>(window: &'a [u8; 9], buffer: &'a [u8]) -> [Step<'a>; 6] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PartialIn),
+        Step::cmd(Command::PartialWindow),
+        Step::Data(window),
+        Step::cmd(Command::DataStartTransmission1),
+        Step::Data(buffer),
+    ]
+}
+
+pub(super) fn update_partial_new_frame<'a>(window: &'a [u8; 9], buffer: &'a [u8]) -> [Step<'a>; 7] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PartialIn),
+        Step::cmd(Command::PartialWindow),
+        Step::Data(window),
+        //TODO: check if data_start transmission 1 also needs "old"/background data here
+        Step::cmd(Command::DataStartTransmission2),
+        Step::Data(buffer),
+        Step::cmd(Command::PartialOut),
+    ]
+}
+
+pub(super) fn clear_partial_frame(window: &[u8; 9], color: u8, bytes: u32) -> [Step<'_>; 11] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::ResolutionSetting),
+        Step::Data(&RESOLUTION),
+        Step::cmd(Command::PartialIn),
+        Step::cmd(Command::PartialWindow),
+        Step::Data(window),
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, bytes),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::DataRepeat(color, bytes),
+        Step::cmd(Command::PartialOut),
+    ]
+}
+
+/// Data of [Command::PartialWindow] for the given window
+pub(super) fn partial_window(x: u32, y: u32, width: u32, height: u32) -> [u8; 9] {
+    // x should be the multiple of 8, the last 3 bit will always be ignored
+    let x_start = x & 0xf8;
+    let x_end = x_start + width - 1;
+    let y_end = y + height - 1;
+    [
+        (x >> 8) as u8,
+        x_start as u8,
+        (x_end >> 8) as u8,
+        (x_end | 0x07) as u8,
+        (y >> 8) as u8,
+        y as u8,
+        (y_end >> 8) as u8,
+        y_end as u8,
+        0x01, // Gates scan both inside and outside of the partial window. (default)
+    ]
+}
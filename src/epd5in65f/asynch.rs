@@ -0,0 +1,147 @@
+//! Async implementation of the 5.65" (F) driver
+
+use embedded_hal_1::digital::{InputPin, OutputPin};
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiBus};
+
+use super::{sequence, Epd5in65f, DEFAULT_BACKGROUND_COLOR, HEIGHT, IS_BUSY_LOW, WIDTH};
+use crate::asynch::{AsyncDriverError, AsyncWaveshareDisplay};
+use crate::color::OctColor;
+use crate::error::EpdError;
+use crate::interface::DisplayInterface;
+use crate::sequence::Step;
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd5in65f<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    async fn run_async(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        steps: &[Step<'_>],
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .run_async(spi, delay, IS_BUSY_LOW, steps)
+            .await
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> AsyncWaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd5in65f<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = OctColor;
+
+    async fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Epd5in65f { interface, color };
+
+        let vcom = sequence::vcom(epd.color);
+        epd.run_async(spi, delay, &sequence::init(&vcom)).await?;
+
+        Ok(epd)
+    }
+
+    async fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::sleep()).await
+    }
+
+    async fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let vcom = sequence::vcom(self.color);
+        self.run_async(spi, delay, &sequence::init(&vcom)).await
+    }
+
+    fn set_background_color(&mut self, color: OctColor) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &OctColor {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    async fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size((WIDTH * HEIGHT / 2) as usize, buffer.len())?;
+
+        let vcom = sequence::vcom(self.color);
+        self.run_async(spi, delay, &sequence::update_frame(&vcom, buffer))
+            .await
+    }
+
+    async fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::display_frame()).await
+    }
+
+    async fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        AsyncWaveshareDisplay::update_frame(self, spi, buffer, delay).await?;
+        AsyncWaveshareDisplay::display_frame(self, spi, delay).await
+    }
+
+    async fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let bg = OctColor::colors_byte(self.color, self.color);
+        let vcom = sequence::vcom(self.color);
+        self.run_async(spi, delay, &sequence::clear_frame(&vcom, bg))
+            .await
+    }
+
+    fn is_busy(&mut self) -> Result<bool, AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.is_busy_now(IS_BUSY_LOW)
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.interface.set_busy_timeout(timeout_ms);
+    }
+}
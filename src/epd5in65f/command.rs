@@ -8,7 +8,7 @@ use crate::traits;
 ///
 /// For more infos about the addresses and what they are doing look into the PDFs.
 #[allow(dead_code)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub(crate) enum Command {
     /// Set Resolution, LUT selection, BWR pixels, gate scan direction, source shift
     /// direction, booster switch, soft reset.
@@ -137,6 +137,50 @@ impl traits::Command for Command {
     }
 }
 
+impl Command {
+    /// The command with this address, used to decode [traces](crate::trace)
+    #[cfg(any(test, feature = "std"))]
+    pub(crate) fn from_address(address: u8) -> Option<Self> {
+        Some(match address {
+            0x00 => Command::PanelSetting,
+            0x01 => Command::PowerSetting,
+            0x02 => Command::PowerOff,
+            0x03 => Command::PowerOffSequenceSetting,
+            0x04 => Command::PowerOn,
+            0x06 => Command::BoosterSoftStart,
+            0x07 => Command::DeepSleep,
+            0x10 => Command::DataStartTransmission1,
+            0x11 => Command::DataStop,
+            0x12 => Command::DisplayRefresh,
+            0x13 => Command::ImageProcess,
+            0x20 => Command::LutForVcom,
+            0x21 => Command::LutBlack,
+            0x22 => Command::LutWhite,
+            0x23 => Command::LutGray1,
+            0x24 => Command::LutGray2,
+            0x25 => Command::LutRed0,
+            0x26 => Command::LutRed1,
+            0x27 => Command::LutRed2,
+            0x28 => Command::LutRed3,
+            0x29 => Command::LutXon,
+            0x30 => Command::PllControl,
+            0x40 => Command::TemperatureSensor,
+            0x41 => Command::TemperatureCalibration,
+            0x42 => Command::TemperatureSensorWrite,
+            0x43 => Command::TemperatureSensorRead,
+            0x50 => Command::VcomAndDataIntervalSetting,
+            0x51 => Command::LowPowerDetection,
+            0x60 => Command::TconSetting,
+            0x61 => Command::TconResolution,
+            0x71 => Command::GetStatus,
+            0x81 => Command::ReadVcomValue,
+            0x82 => Command::VcmDcSetting,
+            0xE3 => Command::FlashMode,
+            _ => return None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,65 +1,10 @@
 use crate::color::OctColor;
-use crate::epd5in65f::{DEFAULT_BACKGROUND_COLOR, HEIGHT, WIDTH};
-use crate::graphics::{DisplayRotation, OctDisplay};
-use embedded_graphics_core::prelude::*;
+use crate::display_buffer::DisplayBuffer;
+use crate::epd5in65f::{HEIGHT, WIDTH};
 
 /// Full size buffer for use with the 5in65f EPD
-///
-/// Can also be manually constructed:
-/// `buffer: [DEFAULT_BACKGROUND_COLOR.get_byte_value(); WIDTH / 2 * HEIGHT]`
-pub struct Display5in65f {
-    buffer: [u8; WIDTH as usize * HEIGHT as usize / 2],
-    rotation: DisplayRotation,
-}
-
-impl Default for Display5in65f {
-    fn default() -> Self {
-        Display5in65f {
-            buffer: [OctColor::colors_byte(DEFAULT_BACKGROUND_COLOR, DEFAULT_BACKGROUND_COLOR);
-                WIDTH as usize * HEIGHT as usize / 2],
-            rotation: DisplayRotation::default(),
-        }
-    }
-}
-
-impl DrawTarget for Display5in65f {
-    type Color = OctColor;
-    type Error = core::convert::Infallible;
-
-    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
-    where
-        I: IntoIterator<Item = Pixel<Self::Color>>,
-    {
-        for pixel in pixels {
-            self.draw_helper(WIDTH, HEIGHT, pixel)?;
-        }
-        Ok(())
-    }
-}
-
-impl OriginDimensions for Display5in65f {
-    fn size(&self) -> Size {
-        Size::new(WIDTH, HEIGHT)
-    }
-}
-
-impl OctDisplay for Display5in65f {
-    fn buffer(&self) -> &[u8] {
-        &self.buffer
-    }
-
-    fn get_mut_buffer(&mut self) -> &mut [u8] {
-        &mut self.buffer
-    }
-
-    fn set_rotation(&mut self, rotation: DisplayRotation) {
-        self.rotation = rotation;
-    }
-
-    fn rotation(&self) -> DisplayRotation {
-        self.rotation
-    }
-}
+pub type Display5in65f =
+    DisplayBuffer<WIDTH, HEIGHT, { WIDTH as usize * HEIGHT as usize / 2 }, OctColor>;
 
 #[cfg(test)]
 mod tests {
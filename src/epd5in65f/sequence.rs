@@ -0,0 +1,111 @@
+//! Command sequences of the 5.65" (F) display, shared by the blocking and the async driver
+
+use super::command::Command;
+use super::{HEIGHT, WIDTH};
+use crate::color::OctColor;
+use crate::sequence::Step;
+
+/// Bytes of one full frame, two pixels per byte
+const FRAME_BYTES: u32 = WIDTH * HEIGHT / 2;
+
+/// Parameters of [Command::TconResolution]
+const RESOLUTION: [u8; 4] = [
+    (WIDTH >> 8) as u8,
+    WIDTH as u8,
+    (HEIGHT >> 8) as u8,
+    HEIGHT as u8,
+];
+
+/// Parameter of [Command::VcomAndDataIntervalSetting], selecting the border color
+pub(super) fn vcom(color: OctColor) -> [u8; 1] {
+    [0x17 | ((color.get_nibble() & 0b111) << 5)]
+}
+
+pub(super) fn init(vcom: &[u8; 1]) -> [Step<'_>; 24] {
+    [
+        // Reset the device
+        Step::Reset(2),
+        Step::cmd(Command::PanelSetting),
+        Step::Data(&[0xEF, 0x08]),
+        Step::cmd(Command::PowerSetting),
+        Step::Data(&[0x37, 0x00, 0x23, 0x23]),
+        Step::cmd(Command::PowerOffSequenceSetting),
+        Step::Data(&[0x00]),
+        Step::cmd(Command::BoosterSoftStart),
+        Step::Data(&[0xC7, 0xC7, 0x1D]),
+        Step::cmd(Command::PllControl),
+        Step::Data(&[0x3C]),
+        Step::cmd(Command::TemperatureSensor),
+        Step::Data(&[0x00]),
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(vcom),
+        Step::cmd(Command::TconSetting),
+        Step::Data(&[0x22]),
+        Step::cmd(Command::TconResolution),
+        Step::Data(&RESOLUTION),
+        Step::cmd(Command::FlashMode),
+        Step::Data(&[0xAA]),
+        Step::Delay(100),
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(vcom),
+    ]
+}
+
+pub(super) fn sleep() -> [Step<'static>; 2] {
+    [Step::cmd(Command::DeepSleep), Step::Data(&[0xA5])]
+}
+
+pub(super) fn begin_frame(vcom: &[u8; 1]) -> [Step<'_>; 6] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(vcom),
+        Step::cmd(Command::TconResolution),
+        Step::Data(&RESOLUTION),
+        Step::cmd(Command::DataStartTransmission1),
+    ]
+}
+
+pub(super) fn update_frame<'a>(vcom: &'a [u8; 1], buffer: &'a [u8]) -> [Step<'a>; 7] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(vcom),
+        Step::cmd(Command::TconResolution),
+        Step::Data(&RESOLUTION),
+        Step::cmd(Command::DataStartTransmission1),
+        Step::Data(buffer),
+    ]
+}
+
+pub(super) fn display_frame() -> [Step<'static>; 7] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PowerOn),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DisplayRefresh),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PowerOff),
+        // The busy pin drops once the power off sequence has started
+        Step::WaitUntilBusy,
+    ]
+}
+
+pub(super) fn clear_frame(vcom: &[u8; 1], color: u8) -> [Step<'_>; 14] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(vcom),
+        Step::cmd(Command::TconResolution),
+        Step::Data(&RESOLUTION),
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, FRAME_BYTES),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PowerOn),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DisplayRefresh),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PowerOff),
+        Step::WaitUntilBusy,
+    ]
+}
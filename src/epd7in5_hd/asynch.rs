@@ -0,0 +1,144 @@
+//! Async implementation of the 7.5" HD driver
+
+use embedded_hal_1::digital::{InputPin, OutputPin};
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiBus};
+
+use super::{sequence, Epd7in5, DEFAULT_BACKGROUND_COLOR, HEIGHT, IS_BUSY_LOW, WIDTH};
+use crate::asynch::{AsyncDriverError, AsyncWaveshareDisplay};
+use crate::buffer_len;
+use crate::color::Color;
+use crate::error::EpdError;
+use crate::interface::DisplayInterface;
+use crate::sequence::Step;
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> Epd7in5<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    async fn run_async(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        steps: &[Step<'_>],
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .run_async(spi, delay, IS_BUSY_LOW, steps)
+            .await
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY> AsyncWaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Epd7in5<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+{
+    type DisplayColor = Color;
+
+    async fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Epd7in5 { interface, color };
+
+        epd.run_async(spi, delay, &sequence::init()).await?;
+
+        Ok(epd)
+    }
+
+    async fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::sleep()).await
+    }
+
+    async fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::init()).await
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        HEIGHT
+    }
+
+    async fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(buffer_len(WIDTH as usize, HEIGHT as usize), buffer.len())?;
+
+        self.run_async(spi, delay, &sequence::update_frame(buffer))
+            .await
+    }
+
+    async fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::display_frame()).await
+    }
+
+    async fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        AsyncWaveshareDisplay::update_frame(self, spi, buffer, delay).await?;
+        AsyncWaveshareDisplay::display_frame(self, spi, delay).await
+    }
+
+    async fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let background_color_byte = self.color.get_byte_value();
+        let steps = sequence::clear_frame(background_color_byte);
+        self.run_async(spi, delay, &steps).await
+    }
+
+    fn is_busy(&mut self) -> Result<bool, AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.is_busy_now(IS_BUSY_LOW)
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.interface.set_busy_timeout(timeout_ms);
+    }
+}
@@ -9,7 +9,7 @@ use crate::traits;
 /// For more infos about the addresses and what they are doing look into the PDFs.
 #[allow(dead_code)]
 #[allow(non_camel_case_types)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub(crate) enum Command {
     DriverOutputControl = 0x01,
 
@@ -145,6 +145,56 @@ impl traits::Command for Command {
     }
 }
 
+impl Command {
+    /// The command with this address, used to decode [traces](crate::trace)
+    #[cfg(any(test, feature = "std"))]
+    pub(crate) fn from_address(address: u8) -> Option<Self> {
+        Some(match address {
+            0x01 => Command::DriverOutputControl,
+            0x03 => Command::GateDrivingVoltageControl,
+            0x04 => Command::SourceDrivingVoltageControl,
+            0x0C => Command::SoftStart,
+            0x0F => Command::GateScanStartPosition,
+            0x10 => Command::DeepSleep,
+            0x11 => Command::DataEntry,
+            0x12 => Command::SwReset,
+            0x14 => Command::HvReadyDetection,
+            0x15 => Command::VciDetection,
+            0x18 => Command::TemperatureSensorControl,
+            0x1A => Command::TemperatureSensorWrite,
+            0x1B => Command::TemperatureSensorRead,
+            0x1C => Command::TemperatureSensorWriteExternal,
+            0x20 => Command::MasterActivation,
+            0x21 => Command::DisplayUpdateControl1,
+            0x22 => Command::DisplayUpdateControl2,
+            0x24 => Command::WriteRamBw,
+            0x26 => Command::WriteRamRed,
+            0x27 => Command::ReadRam,
+            0x28 => Command::VcomSense,
+            0x29 => Command::VcomSenseDuration,
+            0x2A => Command::VcomProgramOtp,
+            0x2B => Command::VcomControl,
+            0x2C => Command::VcomWrite,
+            0x2D => Command::OtpRead,
+            0x34 => Command::CrcCalculation,
+            0x35 => Command::CrcRead,
+            0x36 => Command::ProgramSelection,
+            0x37 => Command::DisplayOptionWrite,
+            0x38 => Command::UserIdWrite,
+            0x3C => Command::VbdControl,
+            0x41 => Command::ReadRamOption,
+            0x44 => Command::SetRamXStartEnd,
+            0x45 => Command::SetRamYStartEnd,
+            0x46 => Command::AutoWriteRed,
+            0x47 => Command::AutoWriteBw,
+            0x4E => Command::SetRamXAc,
+            0x4F => Command::SetRamYAc,
+            0x7F => Command::Nop,
+            _ => return None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
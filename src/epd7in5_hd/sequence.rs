@@ -0,0 +1,106 @@
+//! Command sequences of the 7.5" HD display, shared by the blocking and the async driver
+
+use super::command::Command;
+use super::{HEIGHT, WIDTH};
+use crate::sequence::Step;
+
+/// Bytes of one full frame
+const FRAME_BYTES: u32 = WIDTH * HEIGHT / 8;
+
+pub(super) fn init() -> [Step<'static>; 32] {
+    [
+        // Reset the device
+        Step::Reset(2),
+        // HD procedure as described here:
+        // https://github.com/waveshare/e-Paper/blob/master/RaspberryPi_JetsonNano/python/lib/waveshare_epd/epd7in5_HD.py
+        // and as per specs:
+        // https://www.waveshare.com/w/upload/2/27/7inch_HD_e-Paper_Specification.pdf
+        Step::WaitUntilIdle,
+        Step::cmd(Command::SwReset),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::AutoWriteRed),
+        Step::Data(&[0xF7]),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::AutoWriteBw),
+        Step::Data(&[0xF7]),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::SoftStart),
+        Step::Data(&[0xAE, 0xC7, 0xC3, 0xC0, 0x40]),
+        Step::cmd(Command::DriverOutputControl),
+        Step::Data(&[0xAF, 0x02, 0x01]),
+        Step::cmd(Command::DataEntry),
+        Step::Data(&[0x01]),
+        Step::cmd(Command::SetRamXStartEnd),
+        Step::Data(&[0x00, 0x00, 0x6F, 0x03]),
+        Step::cmd(Command::SetRamYStartEnd),
+        Step::Data(&[0xAF, 0x02, 0x00, 0x00]),
+        Step::cmd(Command::VbdControl),
+        Step::Data(&[0x05]),
+        Step::cmd(Command::TemperatureSensorControl),
+        Step::Data(&[0x80]),
+        Step::cmd(Command::DisplayUpdateControl2),
+        Step::Data(&[0xB1]),
+        Step::cmd(Command::MasterActivation),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::SetRamXAc),
+        Step::Data(&[0x00, 0x00]),
+        Step::cmd(Command::SetRamYAc),
+        Step::Data(&[0x00, 0x00]),
+    ]
+}
+
+pub(super) fn sleep() -> [Step<'static>; 3] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DeepSleep),
+        Step::Data(&[0x01]),
+    ]
+}
+
+pub(super) fn update_frame(buffer: &[u8]) -> [Step<'_>; 7] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::SetRamYAc),
+        Step::Data(&[0x00, 0x00]),
+        Step::cmd(Command::WriteRamBw),
+        Step::Data(buffer),
+        Step::cmd(Command::DisplayUpdateControl2),
+        Step::Data(&[0xF7]),
+    ]
+}
+
+pub(super) fn begin_frame() -> [Step<'static>; 4] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::SetRamYAc),
+        Step::Data(&[0x00, 0x00]),
+        Step::cmd(Command::WriteRamBw),
+    ]
+}
+
+pub(super) fn end_frame() -> [Step<'static>; 2] {
+    [
+        Step::cmd(Command::DisplayUpdateControl2),
+        Step::Data(&[0xF7]),
+    ]
+}
+
+pub(super) fn display_frame() -> [Step<'static>; 2] {
+    [Step::cmd(Command::MasterActivation), Step::WaitUntilIdle]
+}
+
+pub(super) fn clear_frame(color: u8) -> [Step<'static>; 11] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::SetRamYAc),
+        Step::Data(&[0x00, 0x00]),
+        Step::cmd(Command::WriteRamBw),
+        Step::DataRepeat(color, FRAME_BYTES),
+        Step::cmd(Command::WriteRamRed),
+        Step::DataRepeat(color, FRAME_BYTES),
+        Step::cmd(Command::DisplayUpdateControl2),
+        Step::Data(&[0xF7]),
+        Step::cmd(Command::MasterActivation),
+        Step::WaitUntilIdle,
+    ]
+}
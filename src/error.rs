@@ -0,0 +1,141 @@
+//! Errors returned by the drivers
+
+use core::fmt;
+
+use crate::lut::LutError;
+
+/// Errors of the display drivers
+///
+/// `SpiE` is the error type of the SPI peripheral and `BusyE`, `CsE`, `DcE` and `RstE` the
+/// ones of the pins. The output pins default to the error type of the BUSY pin, so
+/// `EpdError<SpiE, PinE>` names the error of pins sharing one error type.
+///
+/// Drivers return [DriverError](crate::traits::DriverError), which picks the types from
+/// the SPI peripheral and pins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpdError<SpiE, BusyE, CsE = BusyE, DcE = BusyE, RstE = BusyE> {
+    /// Writing to the SPI bus failed
+    Spi(SpiE),
+    /// Setting the CS pin failed
+    CsPin(CsE),
+    /// Setting the DC pin failed
+    DcPin(DcE),
+    /// Setting the RST pin failed
+    RstPin(RstE),
+    /// Reading the BUSY pin failed
+    BusyPin(BusyE),
+    /// The display was still busy after the configured busy timeout
+    ///
+    /// See [`WaveshareDisplay::set_busy_timeout`](crate::prelude::WaveshareDisplay::set_busy_timeout)
+    BusyTimeout,
+    /// The buffer doesn't have the length needed for the frame or window
+    BufferSize {
+        /// Number of bytes the display needs
+        expected: usize,
+        /// Number of bytes of the given buffer
+        actual: usize,
+    },
+    /// The window is empty, outside of the display or not aligned to the 8 pixels
+    /// of a byte where the controller requires it
+    WindowAlignment,
+    /// A user waveform was refused before uploading it, see [Lut::validate](crate::lut::Lut::validate)
+    Lut(LutError),
+}
+
+impl<SpiE, BusyE, CsE, DcE, RstE> EpdError<SpiE, BusyE, CsE, DcE, RstE> {
+    /// Returns [EpdError::BufferSize] if `actual` differs from `expected`
+    pub(crate) fn check_buffer_size(expected: usize, actual: usize) -> Result<(), Self> {
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(EpdError::BufferSize { expected, actual })
+        }
+    }
+
+    /// Returns [EpdError::WindowAlignment] if the window is empty, doesn't fit into
+    /// `display_width` x `display_height` or `x` and `width` aren't multiples of 8
+    ///
+    /// `width` may end unaligned at the right edge for displays whose width isn't a multiple of 8.
+    pub(crate) fn check_window(
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        display_width: u32,
+        display_height: u32,
+    ) -> Result<(), Self> {
+        let fits = width > 0
+            && height > 0
+            && x.checked_add(width).is_some_and(|end| end <= display_width)
+            && y.checked_add(height)
+                .is_some_and(|end| end <= display_height);
+        if fits && x % 8 == 0 && (width % 8 == 0 || x + width == display_width) {
+            Ok(())
+        } else {
+            Err(EpdError::WindowAlignment)
+        }
+    }
+}
+
+impl<SpiE, BusyE, CsE, DcE, RstE> fmt::Display for EpdError<SpiE, BusyE, CsE, DcE, RstE>
+where
+    SpiE: fmt::Debug,
+    BusyE: fmt::Debug,
+    CsE: fmt::Debug,
+    DcE: fmt::Debug,
+    RstE: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EpdError::Spi(e) => write!(f, "SPI error: {:?}", e),
+            EpdError::CsPin(e) => write!(f, "CS pin error: {:?}", e),
+            EpdError::DcPin(e) => write!(f, "DC pin error: {:?}", e),
+            EpdError::RstPin(e) => write!(f, "RST pin error: {:?}", e),
+            EpdError::BusyPin(e) => write!(f, "BUSY pin error: {:?}", e),
+            EpdError::BusyTimeout => write!(f, "Timeout while waiting for the BUSY pin"),
+            EpdError::BufferSize { expected, actual } => write!(
+                f,
+                "Buffer has {} bytes, but {} bytes are needed",
+                actual, expected
+            ),
+            EpdError::WindowAlignment => write!(f, "Invalid or misaligned window"),
+            EpdError::Lut(e) => write!(f, "Unsafe LUT: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Error = EpdError<(), ()>;
+
+    #[test]
+    fn buffer_size() {
+        assert_eq!(Error::check_buffer_size(10, 10), Ok(()));
+        assert_eq!(
+            Error::check_buffer_size(10, 9),
+            Err(EpdError::BufferSize {
+                expected: 10,
+                actual: 9
+            })
+        );
+    }
+
+    #[test]
+    fn window() {
+        assert_eq!(Error::check_window(8, 10, 16, 20, 104, 212), Ok(()));
+        assert_eq!(Error::check_window(0, 0, 104, 212, 104, 212), Ok(()));
+        assert_eq!(Error::check_window(112, 0, 10, 250, 122, 250), Ok(()));
+        // misaligned
+        assert!(Error::check_window(4, 0, 16, 20, 104, 212).is_err());
+        assert!(Error::check_window(8, 0, 12, 20, 104, 212).is_err());
+        // empty
+        assert!(Error::check_window(8, 0, 0, 20, 104, 212).is_err());
+        assert!(Error::check_window(8, 0, 8, 0, 104, 212).is_err());
+        // outside of the display
+        assert!(Error::check_window(96, 0, 16, 20, 104, 212).is_err());
+        assert!(Error::check_window(0, 200, 8, 20, 104, 212).is_err());
+        assert!(Error::check_window(u32::MAX - 7, 0, 8, 1, 104, 212).is_err());
+    }
+}
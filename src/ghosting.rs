@@ -0,0 +1,601 @@
+//! Automatic full refreshes between quick refreshes
+//!
+//! Quick (partial) refreshes only drive the pixels which change and leave a faint image of the
+//! old content behind, which adds up with every refresh until a full refresh clears it.
+//! [Ghosting] wraps a [QuickRefresh] driver, counts the quick refreshes of the whole screen and
+//! of every region and replaces a quick refresh with a full one as soon as the [GhostingPolicy]
+//! asks for it.
+//!
+//! The full refresh needs the whole frame, but quick refreshes of windows only pass the
+//! changed part. [Ghosting] keeps a copy of the new frame of the controller in a caller
+//! provided buffer (there is no allocator) and applies every frame and window written through
+//! it.
+//!
+//! The regions are the cells of a [GRID] × [GRID] grid over the display, a partial refresh
+//! counts for every cell its window touches. The time since the last full refresh isn't
+//! measured by the driver (embedded-hal has no clock), it needs to be reported with
+//! [Ghosting::advance].
+//!
+//! Example:
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
+//!use epd_waveshare::{epd4in2::*, ghosting::{Ghosting, GhostingPolicy}, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!let mut epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!epd.set_lut(&mut spi, Some(RefreshLut::Quick), &mut delay)?;
+//!
+//!// A full refresh after 10 quick refreshes or 3 minutes
+//!let policy = GhostingPolicy::new()
+//!    .with_max_quick_refreshes(10)
+//!    .with_max_interval_ms(180_000);
+//!const FRAME_BYTES: usize = WIDTH as usize / 8 * HEIGHT as usize;
+//!let mut frame = [0xFF; FRAME_BYTES];
+//!let mut epd = Ghosting::new(epd, policy, &mut frame);
+//!
+//!let (old, new) = ([0xFF; FRAME_BYTES], [0x00; FRAME_BYTES]);
+//!epd.update_old_frame(&mut spi, &old, &mut delay)?;
+//!epd.update_and_display_new_frame(&mut spi, &new, &mut delay)?;
+//!
+//!// One minute later
+//!epd.advance(60_000);
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::error::EpdError;
+use crate::traits::{DriverError, FullRefresh, QuickRefresh, WaveshareDisplay};
+
+/// Number of regions per axis counted by [Ghosting]
+pub const GRID: usize = 8;
+
+/// When [Ghosting] replaces a quick refresh with a full one
+///
+/// Every limit is optional, the default policy never forces a full refresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GhostingPolicy {
+    /// Quick refreshes of any part of the screen between two full refreshes
+    pub max_quick_refreshes: Option<u32>,
+    /// Quick refreshes of a single region between two full refreshes
+    pub max_region_refreshes: Option<u16>,
+    /// Time in ms after the last full refresh from which on the next refresh is a full one
+    pub max_interval_ms: Option<u32>,
+}
+
+impl GhostingPolicy {
+    /// Policy without limits
+    pub const fn new() -> Self {
+        GhostingPolicy {
+            max_quick_refreshes: None,
+            max_region_refreshes: None,
+            max_interval_ms: None,
+        }
+    }
+
+    /// Limits the quick refreshes of any part of the screen
+    pub const fn with_max_quick_refreshes(mut self, refreshes: u32) -> Self {
+        self.max_quick_refreshes = Some(refreshes);
+        self
+    }
+
+    /// Limits the quick refreshes of every region
+    pub const fn with_max_region_refreshes(mut self, refreshes: u16) -> Self {
+        self.max_region_refreshes = Some(refreshes);
+        self
+    }
+
+    /// Limits the time since the last full refresh
+    pub const fn with_max_interval_ms(mut self, interval_ms: u32) -> Self {
+        self.max_interval_ms = Some(interval_ms);
+        self
+    }
+}
+
+/// Grid cells touched by a window, as ranges of columns and rows
+type Cells = (core::ops::Range<usize>, core::ops::Range<usize>);
+
+/// Wrapper around a [QuickRefresh] driver forcing full refreshes according to a [GhostingPolicy]
+///
+/// The driver needs to be set to [RefreshLut::Quick](crate::traits::RefreshLut::Quick) with
+/// [set_lut](crate::traits::LutSelection::set_lut) (or the driver specific method) before. The
+/// [QuickRefresh] methods of the wrapper are the ones of the driver, but
+/// [display_new_frame](QuickRefresh::display_new_frame) and
+/// [update_and_display_new_frame](QuickRefresh::update_and_display_new_frame) refresh the
+/// whole new frame with [FullRefresh::full_refresh_frame] when it's due, which also writes it
+/// as old frame.
+///
+/// Full refreshes and frames written directly with the driver (see [Ghosting::epd_mut]) need
+/// to be reported with [Ghosting::reset] and [Ghosting::frame_mut].
+pub struct Ghosting<'a, EPD> {
+    epd: EPD,
+    /// Copy of the new frame in the controller
+    frame: &'a mut [u8],
+    policy: GhostingPolicy,
+    quick_refreshes: u32,
+    region_refreshes: [[u16; GRID]; GRID],
+    elapsed_ms: u32,
+    /// Cells written by `update_partial_new_frame` since the last refresh
+    pending: Option<Cells>,
+}
+
+impl<'a, EPD> Ghosting<'a, EPD> {
+    /// Wraps `epd`, counting from a clean screen
+    ///
+    /// `frame` is the frame on the screen and needs to be as long as a frame of the display.
+    pub fn new(epd: EPD, policy: GhostingPolicy, frame: &'a mut [u8]) -> Self {
+        Ghosting {
+            epd,
+            frame,
+            policy,
+            quick_refreshes: 0,
+            region_refreshes: [[0; GRID]; GRID],
+            elapsed_ms: 0,
+            pending: None,
+        }
+    }
+
+    /// The wrapped driver
+    pub fn epd(&self) -> &EPD {
+        &self.epd
+    }
+
+    /// The wrapped driver, e.g. to send commands which are not part of [QuickRefresh]
+    pub fn epd_mut(&mut self) -> &mut EPD {
+        &mut self.epd
+    }
+
+    /// The copy of the new frame in the controller
+    pub fn frame(&self) -> &[u8] {
+        self.frame
+    }
+
+    /// The copy of the new frame in the controller, to update it after writing frames with
+    /// [Ghosting::epd_mut]
+    pub fn frame_mut(&mut self) -> &mut [u8] {
+        self.frame
+    }
+
+    /// Returns the wrapped driver
+    pub fn into_inner(self) -> EPD {
+        self.epd
+    }
+
+    /// The current policy
+    pub fn policy(&self) -> &GhostingPolicy {
+        &self.policy
+    }
+
+    /// Replaces the policy, it applies from the next refresh on
+    pub fn set_policy(&mut self, policy: GhostingPolicy) {
+        self.policy = policy;
+    }
+
+    /// Reports that `ms` milliseconds passed
+    pub fn advance(&mut self, ms: u32) {
+        self.elapsed_ms = self.elapsed_ms.saturating_add(ms);
+    }
+
+    /// Time in ms since the last full refresh, as reported with [advance](Ghosting::advance)
+    pub fn elapsed_ms(&self) -> u32 {
+        self.elapsed_ms
+    }
+
+    /// Number of quick refreshes since the last full refresh
+    pub fn quick_refreshes(&self) -> u32 {
+        self.quick_refreshes
+    }
+
+    /// Number of quick refreshes of the region in column `column` and row `row` of the grid
+    /// since the last full refresh
+    ///
+    /// Panics if `column` or `row` isn't below [GRID].
+    pub fn region_refreshes(&self, column: usize, row: usize) -> u16 {
+        self.region_refreshes[row][column]
+    }
+
+    /// Resets all counters and the elapsed time, e.g. after a full refresh with the driver
+    pub fn reset(&mut self) {
+        self.quick_refreshes = 0;
+        self.region_refreshes = [[0; GRID]; GRID];
+        self.elapsed_ms = 0;
+    }
+
+    /// Whether the policy asks for a full refresh instead of a quick refresh of `cells`
+    fn full_refresh_due(&self, cells: &Cells) -> bool {
+        let policy = &self.policy;
+        let exceeded = |count: u32, max: Option<u32>| max.is_some_and(|max| count >= max);
+
+        exceeded(self.quick_refreshes, policy.max_quick_refreshes)
+            || exceeded(self.elapsed_ms, policy.max_interval_ms)
+            || self.region_refreshes[cells.1.clone()].iter().any(|row| {
+                row[cells.0.clone()].iter().any(|&count| {
+                    exceeded(count.into(), policy.max_region_refreshes.map(u32::from))
+                })
+            })
+    }
+
+    /// Adds cells to the ones refreshed by the next `display_new_frame`
+    ///
+    /// Several windows are combined to the cells of their bounding box.
+    fn add_pending(&mut self, (columns, rows): Cells) {
+        self.pending = Some(match self.pending.take() {
+            Some((c, r)) => (
+                c.start.min(columns.start)..c.end.max(columns.end),
+                r.start.min(rows.start)..r.end.max(rows.end),
+            ),
+            None => (columns, rows),
+        });
+    }
+
+    fn count_quick_refresh(&mut self, cells: &Cells) {
+        self.quick_refreshes = self.quick_refreshes.saturating_add(1);
+        for row in &mut self.region_refreshes[cells.1.clone()] {
+            for count in &mut row[cells.0.clone()] {
+                *count = count.saturating_add(1);
+            }
+        }
+    }
+
+    /// Refreshes `buffer` with [FullRefresh::full_refresh_frame]
+    ///
+    /// `buffer` is written as old and new frame, ready for the next quick refresh.
+    pub fn full_refresh<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        EPD: FullRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+    {
+        EpdError::check_buffer_size(self.frame.len(), buffer.len())?;
+        self.frame.copy_from_slice(buffer);
+        self.full_refresh_frame(spi, delay)
+    }
+
+    /// Refreshes the copy of the new frame with [FullRefresh::full_refresh_frame]
+    fn full_refresh_frame<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        EPD: FullRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+    {
+        info!(
+            "ghosting: full refresh after {} quick refreshes",
+            self.quick_refreshes
+        );
+        self.epd.full_refresh_frame(spi, self.frame, delay)?;
+        self.pending = None;
+        self.reset();
+        Ok(())
+    }
+
+    /// Returns [EpdError::BufferSize] if the copy of the frame isn't as long as a frame of a
+    /// display of `size`
+    fn check_frame<SpiE, BusyE, CsE, DcE, RstE>(
+        &self,
+        size: (u32, u32),
+    ) -> Result<(), EpdError<SpiE, BusyE, CsE, DcE, RstE>> {
+        EpdError::check_buffer_size(
+            buffer_len(size.0 as usize, size.1 as usize),
+            self.frame.len(),
+        )
+    }
+
+    /// Rows of the window `x`, `y`, `width`, `height` in the copy of the frame of a display
+    /// `display_width` pixels wide
+    fn frame_rows(
+        &mut self,
+        (x, y, width, height): (u32, u32, u32, u32),
+        display_width: u32,
+    ) -> impl Iterator<Item = &mut [u8]> {
+        let stride = buffer_len(display_width as usize, 1);
+        let (start, len) = (x as usize / 8, buffer_len(width as usize, 1));
+        self.frame
+            .chunks_mut(stride)
+            .skip(y as usize)
+            .take(height as usize)
+            .map(move |row| &mut row[start..start + len])
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, EPD> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ghosting<'_, EPD>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    EPD: FullRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+        + WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY, DisplayColor = Color>,
+{
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.epd.update_old_frame(spi, buffer, delay)
+    }
+
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(self.frame.len(), buffer.len())?;
+        self.epd.update_new_frame(spi, buffer, delay)?;
+        self.frame.copy_from_slice(buffer);
+        self.pending = Some((0..GRID, 0..GRID));
+        Ok(())
+    }
+
+    /// Refreshes the copy of the new frame with [FullRefresh::full_refresh_frame] instead when
+    /// it's due
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let cells = self.pending.take().unwrap_or((0..GRID, 0..GRID));
+        if self.full_refresh_due(&cells) {
+            self.full_refresh_frame(spi, delay)
+        } else {
+            self.epd.display_new_frame(spi, delay)?;
+            self.count_quick_refresh(&cells);
+            Ok(())
+        }
+    }
+
+    /// Refreshes `buffer` with [full_refresh](Ghosting::full_refresh) instead when it's due
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let cells = (0..GRID, 0..GRID);
+        if self.full_refresh_due(&cells) {
+            self.full_refresh(spi, buffer, delay)
+        } else {
+            EpdError::check_buffer_size(self.frame.len(), buffer.len())?;
+            self.epd.update_and_display_new_frame(spi, buffer, delay)?;
+            self.frame.copy_from_slice(buffer);
+            self.pending = None;
+            self.count_quick_refresh(&cells);
+            Ok(())
+        }
+    }
+
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.epd
+            .update_partial_old_frame(spi, buffer, x, y, width, height, delay)
+    }
+
+    /// Remembers the window for the next [display_new_frame](QuickRefresh::display_new_frame)
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let size = (self.epd.width(), self.epd.height());
+        let window = (x, y, width, height);
+        self.check_frame(size)?;
+        self.epd
+            .update_partial_new_frame(spi, buffer, x, y, width, height, delay)?;
+        let len = buffer_len(width as usize, 1);
+        for (row, data) in self.frame_rows(window, size.0).zip(buffer.chunks(len)) {
+            row.copy_from_slice(data);
+        }
+        self.add_pending(cells(x, y, width, height, size));
+        Ok(())
+    }
+
+    /// Remembers the window for the next [display_new_frame](QuickRefresh::display_new_frame)
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let size = (self.epd.width(), self.epd.height());
+        let window = (x, y, width, height);
+        self.check_frame(size)?;
+        self.epd
+            .clear_partial_frame(spi, x, y, width, height, delay)?;
+        let color = self.epd.background_color().get_byte_value();
+        for row in self.frame_rows(window, size.0) {
+            row.fill(color);
+        }
+        self.add_pending(cells(x, y, width, height, size));
+        Ok(())
+    }
+}
+
+/// Grid cells touched by the window `x`, `y`, `width`, `height` on a display of `size`
+fn cells(x: u32, y: u32, width: u32, height: u32, size: (u32, u32)) -> Cells {
+    let cell = |position: u32, size: u32| {
+        ((u64::from(position) * GRID as u64 / u64::from(size.max(1))) as usize).min(GRID - 1)
+    };
+    let (width, height) = (width.max(1), height.max(1));
+    (
+        cell(x, size.0)..cell(x + width - 1, size.0) + 1,
+        cell(y, size.1)..cell(y + height - 1, size.1) + 1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_cells() {
+        let size = (122, 250);
+        assert_eq!(cells(0, 0, 122, 250, size), (0..GRID, 0..GRID));
+        assert_eq!(cells(0, 0, 8, 8, size), (0..1, 0..1));
+        assert_eq!(cells(40, 60, 16, 4, size), (2..4, 1..3));
+        assert_eq!(cells(120, 249, 2, 1, size), (7..8, 7..8));
+    }
+
+    #[test]
+    fn policy() {
+        let mut ghosting = Ghosting::new(
+            (),
+            GhostingPolicy::new().with_max_quick_refreshes(2),
+            &mut [],
+        );
+        let all = (0..GRID, 0..GRID);
+        assert!(!ghosting.full_refresh_due(&all));
+        ghosting.count_quick_refresh(&all);
+        ghosting.count_quick_refresh(&(1..2, 3..4));
+        assert_eq!(ghosting.region_refreshes(1, 3), 2);
+        assert_eq!(ghosting.region_refreshes(3, 1), 1);
+        assert!(ghosting.full_refresh_due(&(0..1, 0..1)));
+
+        ghosting.reset();
+        ghosting.set_policy(GhostingPolicy::new().with_max_interval_ms(1000));
+        ghosting.advance(999);
+        assert!(!ghosting.full_refresh_due(&all));
+        ghosting.advance(1);
+        assert!(ghosting.full_refresh_due(&all));
+        assert!(!Ghosting::new((), GhostingPolicy::default(), &mut []).full_refresh_due(&all));
+    }
+
+    #[test]
+    fn forces_full_refresh() {
+        use crate::color::TriColor;
+        use crate::epd2in13_GDEQ0213B74::{Epd2in13, HEIGHT, WIDTH};
+        use crate::simulator::ssd1680::FULL_REFRESH_MS;
+        use crate::simulator::{Plane, SimulatedPanel};
+        use crate::traits::{LutSelection, RefreshLut};
+
+        const WRITE_LUT_REGISTER: u8 = 0x32;
+
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13<_, _, _, _, _, _>>();
+        epd.set_lut(&mut spi, Some(RefreshLut::Quick), &mut delay)
+            .unwrap();
+        let quick_lut = panel.lut(WRITE_LUT_REGISTER);
+        assert!(quick_lut.is_some());
+        let policy = GhostingPolicy::new()
+            .with_max_region_refreshes(2)
+            .with_max_interval_ms(60_000);
+        let len = buffer_len(WIDTH as usize, HEIGHT as usize);
+        let mut frame = std::vec![0x00; len];
+        let mut ghosting = Ghosting::new(epd, policy, &mut frame);
+
+        let white = std::vec![0xFF; len];
+        ghosting.full_refresh(&mut spi, &white, &mut delay).unwrap();
+        assert_eq!(panel.ram(Plane::New), white);
+        assert_eq!(panel.ram(Plane::Old), white);
+
+        // Whether the refresh of a black window took as long as a full refresh
+        let mut refresh_window = |ghosting: &mut Ghosting<_>, x, y| {
+            let start = panel.elapsed_ms();
+            ghosting
+                .update_partial_new_frame(&mut spi, &[0x00; 2 * 4], x, y, 16, 4, &mut delay)
+                .unwrap();
+            ghosting.display_new_frame(&mut spi, &mut delay).unwrap();
+            panel.elapsed_ms() - start >= u64::from(FULL_REFRESH_MS)
+        };
+
+        // The region of the window reaches its limit, the other ones don't
+        assert!(!refresh_window(&mut ghosting, 40, 60));
+        assert!(!refresh_window(&mut ghosting, 40, 60));
+        assert!(!refresh_window(&mut ghosting, 0, 0));
+        assert_eq!(ghosting.quick_refreshes(), 3);
+        assert_eq!(ghosting.region_refreshes(2, 1), 2);
+        assert!(refresh_window(&mut ghosting, 40, 60));
+        assert_eq!(ghosting.quick_refreshes(), 0);
+
+        // The full refresh shows the whole frame and leaves it in both RAMs, the quick
+        // waveform is back for the following quick refreshes
+        let stride = WIDTH as usize / 8 + 1;
+        let mut expected = white.clone();
+        for row in 0..4 {
+            expected[row * stride..row * stride + 2].fill(0x00);
+            expected[(60 + row) * stride + 5..(60 + row) * stride + 7].fill(0x00);
+        }
+        assert_eq!(ghosting.frame(), expected);
+        assert_eq!(panel.ram(Plane::New), expected);
+        assert_eq!(panel.ram(Plane::Old), expected);
+        let image = panel.image();
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let black = (x < 16 && y < 4) || ((40..56).contains(&x) && (60..64).contains(&y));
+                let color = if black {
+                    TriColor::Black
+                } else {
+                    TriColor::White
+                };
+                assert_eq!(image.pixel(x, y), color, "pixel {x}, {y}");
+            }
+        }
+        assert_eq!(panel.lut(WRITE_LUT_REGISTER), quick_lut);
+        assert!(!refresh_window(&mut ghosting, 40, 60));
+
+        // The whole frame is refreshed in full once the interval is over
+        ghosting.advance(60_000);
+        let (mut spi, mut delay) = (panel.spi(), panel.delay());
+        let start = panel.elapsed_ms();
+        ghosting
+            .update_and_display_new_frame(&mut spi, &white, &mut delay)
+            .unwrap();
+        assert!(panel.elapsed_ms() - start >= u64::from(FULL_REFRESH_MS));
+        assert_eq!(panel.ram(Plane::New), white);
+        assert_eq!(panel.ram(Plane::Old), white);
+        assert!(panel.image().pixels().iter().all(|&p| p == TriColor::White));
+        assert_eq!(ghosting.elapsed_ms(), 0);
+        assert_eq!(panel.violations(), []);
+    }
+}
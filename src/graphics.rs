@@ -1,14 +1,18 @@
 //! Graphics Support for EPDs
 
 use crate::buffer_len;
-use crate::color::{Color, OctColor, TriColor};
+use crate::color::{Color, OctColor, QuadColor, TriColor};
 use embedded_graphics_core::pixelcolor::BinaryColor;
 use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+
+pub use crate::display_buffer::DisplayBuffer;
 
 /// Displayrotation
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub enum DisplayRotation {
     /// No rotation
+    #[default]
     Rotate0,
     /// Rotate by 90 degrees clockwise
     Rotate90,
@@ -18,9 +22,54 @@ pub enum DisplayRotation {
     Rotate270,
 }
 
-impl Default for DisplayRotation {
-    fn default() -> Self {
-        DisplayRotation::Rotate0
+/// Bounding box of the pixels of a [Display] changed since it was last flushed
+///
+/// In the coordinates of the buffer, i.e. without rotation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirtyRegion {
+    /// First and last changed column and row (inclusive)
+    bounds: Option<(u32, u32, u32, u32)>,
+}
+
+impl DirtyRegion {
+    /// Nothing changed
+    pub const fn new() -> Self {
+        DirtyRegion { bounds: None }
+    }
+
+    /// Whether no pixel changed
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_none()
+    }
+
+    /// Adds the pixel at `x`, `y`
+    pub fn add(&mut self, x: u32, y: u32) {
+        self.bounds = Some(match self.bounds {
+            None => (x, y, x, y),
+            Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+        });
+    }
+
+    /// Adds all pixels of a `width` x `height` buffer
+    pub fn add_all(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            self.add(0, 0);
+            self.add(width - 1, height - 1);
+        }
+    }
+
+    /// Changed window of a buffer `width` pixels wide, expanded to whole bytes
+    ///
+    /// The window starts at a multiple of 8 and its width is a multiple of 8 or ends at the
+    /// right edge of the buffer, as needed by `update_partial_frame`.
+    pub fn window(&self, width: u32) -> Option<Rectangle> {
+        let (x0, y0, x1, y1) = self.bounds?;
+        let x_start = x0 / 8 * 8;
+        let x_end = ((x1 / 8 + 1) * 8).min(width);
+        Some(Rectangle::new(
+            Point::new(x_start as i32, y0 as i32),
+            Size::new(x_end - x_start, y1 - y0 + 1),
+        ))
     }
 }
 
@@ -30,12 +79,17 @@ impl Default for DisplayRotation {
 /// - Drawing (With the help of DrawTarget/Embedded Graphics)
 /// - Rotations
 /// - Clearing
+/// - Tracking the changed region to update only that part of the display, see [flush_dirty](Display::flush_dirty)
 pub trait Display: DrawTarget<Color = BinaryColor> {
     /// Clears the buffer of the display with the chosen background color
     fn clear_buffer(&mut self, background_color: Color) {
         for elem in self.get_mut_buffer().iter_mut() {
             *elem = background_color.get_byte_value();
         }
+        let Size { width, height } = self.buffer_size();
+        let mut dirty = self.dirty_region();
+        dirty.add_all(width, height);
+        self.set_dirty_region(dirty);
     }
 
     /// Returns the buffer
@@ -44,12 +98,101 @@ pub trait Display: DrawTarget<Color = BinaryColor> {
     /// Returns a mutable buffer
     fn get_mut_buffer(&mut self) -> &mut [u8];
 
+    /// Unrotated size of the buffer in pixels
+    ///
+    /// Defaults to the size of the [bounding box](Dimensions::bounding_box), displays whose
+    /// size follows the rotation return the unrotated one.
+    fn buffer_size(&self) -> Size {
+        self.bounding_box().size
+    }
+
     /// Sets the rotation of the display
     fn set_rotation(&mut self, rotation: DisplayRotation);
 
     /// Get the current rotation of the display
     fn rotation(&self) -> DisplayRotation;
 
+    /// Returns the region changed by drawing since the last [flush_dirty](Display::flush_dirty)
+    ///
+    /// Changes made directly to [get_mut_buffer](Display::get_mut_buffer) aren't tracked.
+    /// Defaults to the full buffer for displays which don't track changes, so
+    /// [flush_dirty](Display::flush_dirty) always sends everything.
+    fn dirty_region(&self) -> DirtyRegion {
+        let Size { width, height } = self.buffer_size();
+        let mut dirty = DirtyRegion::new();
+        dirty.add_all(width, height);
+        dirty
+    }
+
+    /// Sets the tracked changed region, e.g. to [DirtyRegion::new] after sending the buffer
+    ///
+    /// Does nothing by default.
+    fn set_dirty_region(&mut self, _region: DirtyRegion) {}
+
+    /// Sends the changed region with `update` and resets it afterwards
+    ///
+    /// `update` gets the data, `x`, `y`, `width` and `height` of the window as taken by
+    /// `update_partial_frame` and `update_partial_new_frame`. The window is copied into `scratch`
+    /// if it fits, otherwise (e.g. for an empty `scratch`) the full rows of the window are sent
+    /// directly from the buffer.
+    ///
+    /// Returns `Ok(false)` without calling `update` if nothing changed.
+    ///
+    /// ```rust, no_run
+    /// # use epd_waveshare::{epd2in9::*, prelude::*};
+    /// # fn update<SPI, CS, BUSY, DC, RST, DELAY>(
+    /// #     epd: &mut Epd2in9<SPI, CS, BUSY, DC, RST, DELAY>, spi: &mut SPI, delay: &mut DELAY,
+    /// #     display: &mut Display2in9,
+    /// # ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+    /// # where
+    /// #     SPI: embedded_hal::blocking::spi::Write<u8>,
+    /// #     BUSY: embedded_hal::digital::v2::InputPin,
+    /// #     CS: embedded_hal::digital::v2::OutputPin,
+    /// #     DC: embedded_hal::digital::v2::OutputPin,
+    /// #     RST: embedded_hal::digital::v2::OutputPin,
+    /// #     DELAY: embedded_hal::blocking::delay::DelayMs<u8>,
+    /// # {
+    /// let mut scratch = [0; 1024];
+    /// if display.flush_dirty(&mut scratch, |buffer, x, y, width, height| {
+    ///     epd.update_partial_frame(spi, buffer, x, y, width, height, delay)
+    /// })? {
+    ///     epd.display_frame(spi, delay)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn flush_dirty<E, F>(&mut self, scratch: &mut [u8], update: F) -> Result<bool, E>
+    where
+        F: FnOnce(&[u8], u32, u32, u32, u32) -> Result<(), E>,
+    {
+        let width = self.buffer_size().width;
+        let window = match self.dirty_region().window(width) {
+            Some(window) => window,
+            None => return Ok(false),
+        };
+        let (x, y) = (window.top_left.x as u32, window.top_left.y as u32);
+        let (window_width, height) = (window.size.width, window.size.height);
+
+        let row_len = width.div_ceil(8) as usize;
+        let rows = &self.buffer()[y as usize * row_len..(y + height) as usize * row_len];
+        let window_row_len = window_width.div_ceil(8) as usize;
+        let len = window_row_len * height as usize;
+        if len <= scratch.len() {
+            let first = x as usize / 8;
+            for (target, row) in scratch[..len]
+                .chunks_mut(window_row_len)
+                .zip(rows.chunks(row_len))
+            {
+                target.copy_from_slice(&row[first..first + window_row_len]);
+            }
+            update(&scratch[..len], x, y, window_width, height)?;
+        } else {
+            update(rows, 0, y, width, height)?;
+        }
+        self.set_dirty_region(DirtyRegion::new());
+        Ok(true)
+    }
+
     /// Helperfunction for the Embedded Graphics draw trait
     ///
     /// Becomes uneccesary when const_generics become stablised
@@ -60,7 +203,6 @@ pub trait Display: DrawTarget<Color = BinaryColor> {
         pixel: Pixel<BinaryColor>,
     ) -> Result<(), Self::Error> {
         let rotation = self.rotation();
-        let buffer = self.get_mut_buffer();
 
         let Pixel(point, color) = pixel;
         if outside_display(point, width, height, rotation) {
@@ -72,6 +214,8 @@ pub trait Display: DrawTarget<Color = BinaryColor> {
         let index = index as usize;
 
         // "Draw" the Pixel on that bit
+        let buffer = self.get_mut_buffer();
+        let old = buffer[index];
         match color {
             // Black
             BinaryColor::On => {
@@ -82,6 +226,13 @@ pub trait Display: DrawTarget<Color = BinaryColor> {
                 buffer[index] |= bit;
             }
         }
+
+        if buffer[index] != old {
+            let (nx, ny) = find_rotation(point.x as u32, point.y as u32, width, height, rotation);
+            let mut dirty = self.dirty_region();
+            dirty.add(nx, ny);
+            self.set_dirty_region(dirty);
+        }
         Ok(())
     }
 }
@@ -92,6 +243,8 @@ pub trait Display: DrawTarget<Color = BinaryColor> {
 /// - Drawing (With the help of DrawTarget/Embedded Graphics)
 /// - Rotations
 /// - Clearing
+///
+/// Unlike [Display], changed regions aren't tracked, the whole buffer is sent on every update.
 pub trait TriDisplay: DrawTarget<Color = TriColor> {
     /// Clears the buffer of the display with the chosen background color
     fn clear_buffer(&mut self, background_color: TriColor) {
@@ -106,6 +259,11 @@ pub trait TriDisplay: DrawTarget<Color = TriColor> {
     /// Returns a mutable buffer
     fn get_mut_buffer(&mut self) -> &mut [u8];
 
+    /// Unrotated size of the buffer in pixels, see [Display::buffer_size]
+    fn buffer_size(&self) -> Size {
+        self.bounding_box().size
+    }
+
     /// Sets the rotation of the display
     fn set_rotation(&mut self, rotation: DisplayRotation);
 
@@ -175,6 +333,8 @@ pub trait TriDisplay: DrawTarget<Color = TriColor> {
 /// - Drawing (With the help of DrawTarget/Embedded Graphics)
 /// - Rotations
 /// - Clearing
+///
+/// Unlike [Display], changed regions aren't tracked, the whole buffer is sent on every update.
 pub trait OctDisplay: DrawTarget<Color = OctColor> {
     /// Clears the buffer of the display with the chosen background color
     fn clear_buffer(&mut self, background_color: OctColor) {
@@ -189,6 +349,11 @@ pub trait OctDisplay: DrawTarget<Color = OctColor> {
     /// Returns a mutable buffer
     fn get_mut_buffer(&mut self) -> &mut [u8];
 
+    /// Unrotated size of the buffer in pixels, see [Display::buffer_size]
+    fn buffer_size(&self) -> Size {
+        self.bounding_box().size
+    }
+
     /// Sets the rotation of the display
     fn set_rotation(&mut self, rotation: DisplayRotation);
 
@@ -228,6 +393,120 @@ pub trait OctDisplay: DrawTarget<Color = OctColor> {
     }
 }
 
+/// Necessary traits for all displays to implement for drawing
+///
+/// Adds support for:
+/// - Drawing (With the help of DrawTarget/Embedded Graphics)
+/// - Rotations
+/// - Clearing
+///
+/// The buffer stores 2 bits per pixel (4 pixels per byte, MSB first),
+/// see [gray_buffer_len](crate::gray_buffer_len)
+///
+/// Unlike [Display], changed regions aren't tracked, the whole buffer is sent on every update.
+pub trait QuadDisplay: DrawTarget<Color = QuadColor> {
+    /// Clears the buffer of the display with the chosen background color
+    fn clear_buffer(&mut self, background_color: QuadColor) {
+        for elem in self.get_mut_buffer().iter_mut() {
+            *elem = background_color.get_byte_value();
+        }
+    }
+
+    /// Returns the buffer
+    fn buffer(&self) -> &[u8];
+
+    /// Returns a mutable buffer
+    fn get_mut_buffer(&mut self) -> &mut [u8];
+
+    /// Unrotated size of the buffer in pixels, see [Display::buffer_size]
+    fn buffer_size(&self) -> Size {
+        self.bounding_box().size
+    }
+
+    /// Sets the rotation of the display
+    fn set_rotation(&mut self, rotation: DisplayRotation);
+
+    /// Get the current rotation of the display
+    fn rotation(&self) -> DisplayRotation;
+
+    /// Helperfunction for the Embedded Graphics draw trait
+    ///
+    /// Becomes uneccesary when const_generics become stablised
+    fn draw_helper(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixel: Pixel<QuadColor>,
+    ) -> Result<(), Self::Error> {
+        let rotation = self.rotation();
+        let buffer = self.get_mut_buffer();
+
+        let Pixel(point, color) = pixel;
+        if outside_display(point, width, height, rotation) {
+            return Ok(());
+        }
+
+        // Give us index inside the buffer and the shift of the 2 bits inside that u8
+        let (index, shift) =
+            find_gray_position(point.x as u32, point.y as u32, width, height, rotation);
+        let index = index as usize;
+
+        // "Draw" the Pixel on these two bits
+        buffer[index] = (buffer[index] & !(0x03 << shift)) | (color.get_bits() << shift);
+        Ok(())
+    }
+}
+
+/// Pixel formats of the display buffers of the drivers
+///
+/// Implemented for [BinaryColor] ([Display]), [TriColor] ([TriDisplay]), [OctColor]
+/// ([OctDisplay]) and [QuadColor] ([QuadDisplay]).
+pub trait ColorFormat: PixelColor {
+    /// Bits of a pixel in one plane
+    const BITS_PER_PIXEL: usize;
+    /// Number of planes following each other in the buffer
+    const PLANES: usize;
+
+    /// Byte a new buffer is filled with: all pixels white
+    fn white_byte() -> u8;
+}
+
+impl ColorFormat for BinaryColor {
+    const BITS_PER_PIXEL: usize = 1;
+    const PLANES: usize = 1;
+
+    fn white_byte() -> u8 {
+        Color::White.get_byte_value()
+    }
+}
+
+impl ColorFormat for TriColor {
+    const BITS_PER_PIXEL: usize = 1;
+    const PLANES: usize = 2;
+
+    fn white_byte() -> u8 {
+        TriColor::White.get_byte_value()
+    }
+}
+
+impl ColorFormat for OctColor {
+    const BITS_PER_PIXEL: usize = 4;
+    const PLANES: usize = 1;
+
+    fn white_byte() -> u8 {
+        OctColor::colors_byte(OctColor::White, OctColor::White)
+    }
+}
+
+impl ColorFormat for QuadColor {
+    const BITS_PER_PIXEL: usize = 2;
+    const PLANES: usize = 1;
+
+    fn white_byte() -> u8 {
+        QuadColor::White.get_byte_value()
+    }
+}
+
 /// A variable Display without a predefined buffer
 ///
 /// The buffer can be created as following:
@@ -259,6 +538,7 @@ pub struct VarDisplay<'a> {
     height: u32,
     rotation: DisplayRotation,
     buffer: &'a mut [u8], //buffer: Box<u8>//[u8; 15000]
+    dirty: DirtyRegion,
 }
 
 impl<'a> VarDisplay<'a> {
@@ -273,6 +553,7 @@ impl<'a> VarDisplay<'a> {
             height,
             rotation: DisplayRotation::default(),
             buffer,
+            dirty: DirtyRegion::new(),
         }
     }
 }
@@ -304,7 +585,7 @@ impl<'a> Display for VarDisplay<'a> {
     }
 
     fn get_mut_buffer(&mut self) -> &mut [u8] {
-        &mut self.buffer
+        self.buffer
     }
 
     fn set_rotation(&mut self, rotation: DisplayRotation) {
@@ -314,6 +595,14 @@ impl<'a> Display for VarDisplay<'a> {
     fn rotation(&self) -> DisplayRotation {
         self.rotation
     }
+
+    fn dirty_region(&self) -> DirtyRegion {
+        self.dirty
+    }
+
+    fn set_dirty_region(&mut self, region: DirtyRegion) {
+        self.dirty = region;
+    }
 }
 
 // Checks if a pos is outside the defined display
@@ -337,7 +626,13 @@ fn outside_display(p: Point, width: u32, height: u32, rotation: DisplayRotation)
     false
 }
 
-fn find_rotation(x: u32, y: u32, width: u32, height: u32, rotation: DisplayRotation) -> (u32, u32) {
+pub(crate) fn find_rotation(
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    rotation: DisplayRotation,
+) -> (u32, u32) {
     let nx;
     let ny;
     match rotation {
@@ -363,7 +658,7 @@ fn find_rotation(x: u32, y: u32, width: u32, height: u32, rotation: DisplayRotat
 
 #[rustfmt::skip]
 //returns index position in the u8-slice and the bit-position inside that u8
-fn find_oct_position(x: u32, y: u32, width: u32, height: u32, rotation: DisplayRotation) -> (u32, bool) {
+pub(crate) fn find_oct_position(x: u32, y: u32, width: u32, height: u32, rotation: DisplayRotation) -> (u32, bool) {
     let (nx, ny) = find_rotation(x, y, width, height, rotation);
     (
         /* what byte address is this? */
@@ -373,24 +668,38 @@ fn find_oct_position(x: u32, y: u32, width: u32, height: u32, rotation: DisplayR
     )
 }
 
+#[rustfmt::skip]
+//returns index position in the u8-slice and the shift of the 2 bits inside that u8
+pub(crate) fn find_gray_position(x: u32, y: u32, width: u32, height: u32, rotation: DisplayRotation) -> (u32, u8) {
+    let (nx, ny) = find_rotation(x, y, width, height, rotation);
+    (
+        nx / 4 + width.div_ceil(4) * ny,
+        (6 - 2 * (nx % 4)) as u8,
+    )
+}
+
 #[rustfmt::skip]
 //returns index position in the u8-slice and the bit-position inside that u8
-fn find_position(x: u32, y: u32, width: u32, height: u32, rotation: DisplayRotation) -> (u32, u8) {
+pub(crate) fn find_position(x: u32, y: u32, width: u32, height: u32, rotation: DisplayRotation) -> (u32, u8) {
     let (nx, ny) = find_rotation(x, y, width, height, rotation);
     (
-        nx / 8 + ((width + 7) / 8) * ny,
+        nx / 8 + width.div_ceil(8) * ny,
         0x80 >> (nx % 8),
     )
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{buffer_len, find_position, outside_display, Display, DisplayRotation, VarDisplay};
+    use super::{
+        buffer_len, find_gray_position, find_position, outside_display, DirtyRegion, Display,
+        DisplayRotation, VarDisplay,
+    };
     use crate::color::Black;
     use crate::color::Color;
     use embedded_graphics::{
+        pixelcolor::BinaryColor,
         prelude::*,
-        primitives::{Line, PrimitiveStyle},
+        primitives::{Line, PrimitiveStyle, Rectangle},
     };
 
     #[test]
@@ -415,8 +724,8 @@ mod tests {
     #[test]
     fn rotation_overflow() {
         use crate::epd4in2::{HEIGHT, WIDTH};
-        let width = WIDTH as u32;
-        let height = HEIGHT as u32;
+        let width = WIDTH;
+        let height = HEIGHT;
         test_rotation_overflow(width, height, DisplayRotation::Rotate0);
         test_rotation_overflow(width, height, DisplayRotation::Rotate90);
         test_rotation_overflow(width, height, DisplayRotation::Rotate180);
@@ -427,7 +736,7 @@ mod tests {
         let max_value = width / 8 * height;
         for x in 0..(width + height) {
             //limit x because it runs too long
-            for y in 0..(u32::max_value()) {
+            for y in 0..(u32::MAX) {
                 if outside_display(Point::new(x as i32, y as i32), width, height, rotation2) {
                     break;
                 } else {
@@ -486,4 +795,181 @@ mod tests {
             assert_eq!(byte, DEFAULT_BACKGROUND_COLOR.get_byte_value());
         }
     }
+
+    #[test]
+    fn gray_position() {
+        assert_eq!(
+            find_gray_position(0, 0, 104, 212, DisplayRotation::Rotate0),
+            (0, 6)
+        );
+        assert_eq!(
+            find_gray_position(3, 0, 104, 212, DisplayRotation::Rotate0),
+            (0, 0)
+        );
+        assert_eq!(
+            find_gray_position(4, 1, 104, 212, DisplayRotation::Rotate0),
+            (27, 6)
+        );
+        assert_eq!(
+            find_gray_position(0, 0, 104, 212, DisplayRotation::Rotate180),
+            (5511, 0)
+        );
+    }
+
+    #[test]
+    fn dirty_region() {
+        let (width, height) = (122, 250);
+        let mut buffer = [Color::White.get_byte_value(); buffer_len(122, 250)];
+        let mut display = VarDisplay::new(width, height, &mut buffer);
+        assert_eq!(display.dirty_region(), DirtyRegion::new());
+
+        // drawing the background color doesn't change anything
+        let _ = Pixel(Point::new(5, 5), BinaryColor::Off).draw(&mut display);
+        assert!(display.dirty_region().is_empty());
+
+        let _ = Line::new(Point::new(10, 20), Point::new(17, 22))
+            .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+            .draw(&mut display);
+        assert_eq!(
+            display.dirty_region().window(width),
+            Some(Rectangle::new(Point::new(8, 20), Size::new(16, 3)))
+        );
+
+        // rotated points are tracked in buffer coordinates, the window ends at the unaligned edge
+        display.set_rotation(DisplayRotation::Rotate90);
+        let _ = Pixel(Point::new(30, 0), BinaryColor::On).draw(&mut display);
+        assert_eq!(
+            display.dirty_region().window(width),
+            Some(Rectangle::new(Point::new(8, 20), Size::new(114, 11)))
+        );
+
+        display.set_dirty_region(DirtyRegion::new());
+        display.clear_buffer(Color::White);
+        assert_eq!(
+            display.dirty_region().window(width),
+            Some(Rectangle::new(Point::zero(), Size::new(width, height)))
+        );
+    }
+
+    #[test]
+    fn flush_dirty() {
+        let (width, height) = (32, 8);
+        let mut buffer = [Color::White.get_byte_value(); buffer_len(32, 8)];
+        let mut display = VarDisplay::new(width, height, &mut buffer);
+        let mut scratch = [0; 4];
+
+        let nothing = display.flush_dirty(&mut scratch, |_, _, _, _, _| -> Result<(), ()> {
+            panic!("nothing to update")
+        });
+        assert_eq!(nothing, Ok(false));
+
+        let _ = Line::new(Point::new(8, 2), Point::new(15, 2))
+            .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+            .draw(&mut display);
+        let _ = Pixel(Point::new(16, 3), BinaryColor::On).draw(&mut display);
+        let flushed = display.flush_dirty(&mut scratch, |data, x, y, width, height| {
+            assert_eq!((x, y, width, height), (8, 2, 16, 2));
+            assert_eq!(data, [0x00, 0xff, 0xff, 0x7f]);
+            Ok::<(), ()>(())
+        });
+        assert_eq!(flushed, Ok(true));
+        assert!(display.dirty_region().is_empty());
+
+        // full rows are sent directly if the window doesn't fit into the scratch buffer
+        let _ = Line::new(Point::new(0, 1), Point::new(31, 1))
+            .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+            .draw(&mut display);
+        let _ = display.flush_dirty(&mut [], |data, x, y, width, height| {
+            assert_eq!((x, y, width, height), (0, 1, 32, 1));
+            assert_eq!(data, [0x00; 4]);
+            Ok::<(), ()>(())
+        });
+
+        // the region is kept if the update fails
+        let _ = Pixel(Point::new(0, 0), BinaryColor::On).draw(&mut display);
+        assert_eq!(
+            display.flush_dirty(&mut [], |_, _, _, _, _| Err(())),
+            Err(())
+        );
+        assert!(!display.dirty_region().is_empty());
+    }
+
+    #[test]
+    fn flush_dirty_without_tracking() {
+        struct Untracked([u8; 6]);
+
+        impl OriginDimensions for Untracked {
+            fn size(&self) -> Size {
+                Size::new(16, 3)
+            }
+        }
+
+        impl DrawTarget for Untracked {
+            type Color = BinaryColor;
+            type Error = core::convert::Infallible;
+
+            fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+            where
+                I: IntoIterator<Item = Pixel<BinaryColor>>,
+            {
+                for pixel in pixels {
+                    self.draw_helper(16, 3, pixel)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl Display for Untracked {
+            fn buffer(&self) -> &[u8] {
+                &self.0
+            }
+
+            fn get_mut_buffer(&mut self) -> &mut [u8] {
+                &mut self.0
+            }
+
+            fn set_rotation(&mut self, _rotation: DisplayRotation) {}
+
+            fn rotation(&self) -> DisplayRotation {
+                DisplayRotation::Rotate0
+            }
+        }
+
+        // The whole buffer is sent every time
+        let mut display = Untracked([0xff; 6]);
+        let _ = Pixel(Point::new(9, 1), BinaryColor::On).draw(&mut display);
+        for _ in 0..2 {
+            let flushed = display.flush_dirty(&mut [0; 6], |data, x, y, width, height| {
+                assert_eq!((x, y, width, height), (0, 0, 16, 3));
+                assert_eq!(data, [0xff, 0xff, 0xff, 0xbf, 0xff, 0xff]);
+                Ok::<(), ()>(())
+            });
+            assert_eq!(flushed, Ok(true));
+        }
+    }
+
+    #[test]
+    fn flush_dirty_rotated() {
+        use crate::epd2in13_GDEQ0213B74::{Display2in13, HEIGHT, WIDTH};
+
+        // the size of this buffer follows the rotation, the window is in buffer coordinates
+        let mut display = Display2in13::default();
+        display.set_rotation(DisplayRotation::Rotate90);
+        assert_eq!(display.size(), Size::new(HEIGHT, WIDTH));
+        let _ = Pixel(Point::new(200, 0), BinaryColor::On).draw(&mut display);
+        let flushed = display.flush_dirty(&mut [0; 8], |data, x, y, width, height| {
+            assert_eq!((x, y, width, height), (120, 200, 2, 1));
+            assert_eq!(data, [0xbf]);
+            Ok::<(), ()>(())
+        });
+        assert_eq!(flushed, Ok(true));
+
+        let _ = Pixel(Point::new(200, 0), BinaryColor::On).draw(&mut display);
+        display.clear_buffer(Color::White);
+        let _ = display.flush_dirty(&mut [], |data, x, y, width, height| {
+            assert_eq!((x, y, width, height), (0, 0, WIDTH, HEIGHT));
+            assert_eq!(data.len(), buffer_len(WIDTH as usize, HEIGHT as usize));
+            Ok::<(), ()>(())
+        });
+    }
 }
@@ -1,13 +1,29 @@
-use crate::traits::Command;
+use crate::error::EpdError;
+use crate::sequence::Step;
+use crate::traits::{Command, DriverError};
+use crate::DEFAULT_BUSY_TIMEOUT_MS;
 use core::marker::PhantomData;
 use embedded_hal::{
     blocking::{delay::*, spi::Write},
     digital::v2::*,
 };
 
+/// Linux has a default limit of 4096 bytes per spi transfer
+/// see https://raspberrypi.stackexchange.com/questions/65595/spi-transfer-fails-with-buffer-size-greater-than-4096
+const MAX_TRANSFER_LEN: usize = if cfg!(target_os = "linux") {
+    4096
+} else {
+    usize::MAX
+};
+
+/// Size of the stack buffer [data_x_times](DisplayInterface::data_x_times) streams from
+const REPEAT_CHUNK_LEN: usize = 256;
+
 /// The Connection Interface of all (?) Waveshare EPD-Devices
 ///
-pub(crate) struct DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY> {
+/// Data is streamed in bulk transfers unless `SINGLE_BYTE_WRITE` is set, which sends it
+/// one byte at a time with CS toggled for every byte, as some controllers were reported to need (#82).
+pub(crate) struct DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, const SINGLE_BYTE_WRITE: bool> {
     /// SPI
     _spi: PhantomData<SPI>,
     /// DELAY
@@ -20,25 +36,22 @@ pub(crate) struct DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY> {
     dc: DC,
     /// Pin for Resetting
     rst: RST,
+    /// Maximum time in ms to wait for the busy pin, `None` waits forever
+    busy_timeout: Option<u32>,
 }
 
-impl<SPI, CS, BUSY, DC, RST, DELAY> DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY>
-where
-    SPI: Write<u8>,
-    CS: OutputPin,
-    BUSY: InputPin,
-    DC: OutputPin,
-    RST: OutputPin,
-    DELAY: DelayMs<u8>,
+impl<SPI, CS, BUSY, DC, RST, DELAY, const SINGLE_BYTE_WRITE: bool>
+    DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>
 {
     pub fn new(cs: CS, busy: BUSY, dc: DC, rst: RST) -> Self {
         DisplayInterface {
-            _spi: PhantomData::default(),
-            _delay: PhantomData::default(),
+            _spi: PhantomData,
+            _delay: PhantomData,
             cs,
             busy,
             dc,
             rst,
+            busy_timeout: Some(DEFAULT_BUSY_TIMEOUT_MS),
         }
     }
 
@@ -46,30 +59,94 @@ where
         (self.cs, self.busy, self.dc, self.rst)
     }
 
+    /// Sets the maximum time in ms [wait_until_idle](DisplayInterface::wait_until_idle)
+    /// waits for the busy pin, `None` waits forever
+    pub(crate) fn set_busy_timeout(&mut self, timeout: Option<u32>) {
+        self.busy_timeout = timeout;
+    }
+
+    /// Returns the maximum time in ms to wait for the busy pin
+    pub(crate) fn busy_timeout(&self) -> Option<u32> {
+        self.busy_timeout
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, const SINGLE_BYTE_WRITE: bool>
+    DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Executes a command sequence, see [Step]
+    pub(crate) fn run(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        is_busy_low: bool,
+        steps: &[Step<'_>],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        for step in steps {
+            match *step {
+                Step::Reset(duration) => self.reset(delay, duration)?,
+                Step::Command(address) => self.command_byte(spi, address)?,
+                Step::Data(data) => self.data(spi, data)?,
+                Step::DataRepeat(val, repetitions) => self.data_x_times(spi, val, repetitions)?,
+                Step::WaitUntilIdle => self.wait_until_idle(delay, is_busy_low)?,
+                Step::WaitUntilBusy => self.wait_until_idle(delay, !is_busy_low)?,
+                Step::Delay(ms) => delay.delay_ms(ms),
+            }
+        }
+        Ok(())
+    }
+
     /// Basic function for sending [Commands](Command).
     ///
     /// Enables direct interaction with the device with the help of [data()](DisplayInterface::data())
-    pub(crate) fn cmd<T: Command>(&mut self, spi: &mut SPI, command: T) -> Result<(), SPI::Error> {
+    pub(crate) fn cmd<T: Command>(
+        &mut self,
+        spi: &mut SPI,
+        command: T,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.command_byte(spi, command.address())
+    }
+
+    fn command_byte(
+        &mut self,
+        spi: &mut SPI,
+        address: u8,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        trace!("command 0x{:x}", address);
         // low for commands
-        let _ = self.dc.set_low();
+        self.dc.set_low().map_err(EpdError::DcPin)?;
 
         // Transfer the command over spi
-        self.write(spi, &[command.address()])
+        self.write(spi, &[address])
     }
 
     /// Basic function for sending an array of u8-values of data over spi
     ///
     /// Enables direct interaction with the device with the help of [command()](Epd4in2::command())
-    pub(crate) fn data(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+    pub(crate) fn data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         // high for data
-        let _ = self.dc.set_high();
+        self.dc.set_high().map_err(EpdError::DcPin)?;
 
-        for val in data.iter().copied() {
-            // Transfer data one u8 at a time over spi
-            self.write(spi, &[val])?;
+        if SINGLE_BYTE_WRITE {
+            for val in data.iter().copied() {
+                // Transfer data one u8 at a time over spi
+                self.write(spi, &[val])?;
+            }
+            Ok(())
+        } else {
+            self.write(spi, data)
         }
-
-        Ok(())
     }
 
     /// Basic function for sending [Commands](Command) and the data belonging to it.
@@ -80,7 +157,7 @@ where
         spi: &mut SPI,
         command: T,
         data: &[u8],
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         self.cmd(spi, command)?;
         self.data(spi, data)
     }
@@ -93,34 +170,48 @@ where
         spi: &mut SPI,
         val: u8,
         repetitions: u32,
-    ) -> Result<(), SPI::Error> {
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         // high for data
-        let _ = self.dc.set_high();
-        // Transfer data (u8) over spi
-        for _ in 0..repetitions {
-            self.write(spi, &[val])?;
+        self.dc.set_high().map_err(EpdError::DcPin)?;
+
+        if SINGLE_BYTE_WRITE {
+            // Transfer data (u8) over spi
+            for _ in 0..repetitions {
+                self.write(spi, &[val])?;
+            }
+            return Ok(());
+        }
+
+        // Stream the repeated byte out of a small buffer within a single CS cycle
+        let chunk = [val; REPEAT_CHUNK_LEN];
+        self.cs.set_low().map_err(EpdError::CsPin)?;
+        let mut remaining = repetitions as usize;
+        while remaining > 0 {
+            let len = remaining.min(REPEAT_CHUNK_LEN);
+            spi.write(&chunk[..len]).map_err(EpdError::Spi)?;
+            remaining -= len;
         }
+        self.cs.set_high().map_err(EpdError::CsPin)?;
         Ok(())
     }
 
     // spi write helper/abstraction function
-    fn write(&mut self, spi: &mut SPI, data: &[u8]) -> Result<(), SPI::Error> {
+    fn write(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         // activate spi with cs low
-        let _ = self.cs.set_low();
+        self.cs.set_low().map_err(EpdError::CsPin)?;
 
         // transfer spi data
-        // Be careful!! Linux has a default limit of 4096 bytes per spi transfer
-        // see https://raspberrypi.stackexchange.com/questions/65595/spi-transfer-fails-with-buffer-size-greater-than-4096
-        if cfg!(target_os = "linux") {
-            for data_chunk in data.chunks(4096) {
-                spi.write(data_chunk)?;
-            }
-        } else {
-            spi.write(data)?;
+        // Be careful!! Linux has a default limit of 4096 bytes per spi transfer, see MAX_TRANSFER_LEN
+        for data_chunk in data.chunks(MAX_TRANSFER_LEN) {
+            spi.write(data_chunk).map_err(EpdError::Spi)?;
         }
 
         // deactivate spi with cs high
-        let _ = self.cs.set_high();
+        self.cs.set_high().map_err(EpdError::CsPin)?;
 
         Ok(())
     }
@@ -138,14 +229,32 @@ where
     ///
     /// Most likely there was a mistake with the 2in9 busy connection
     /// //TODO: use the #cfg feature to make this compile the right way for the certain types
-    pub(crate) fn wait_until_idle(&mut self, is_busy_low: bool) {
+    ///
+    /// Returns [EpdError::BusyTimeout] if the device is still busy after the busy timeout.
+    pub(crate) fn wait_until_idle(
+        &mut self,
+        delay: &mut DELAY,
+        is_busy_low: bool,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
         // //tested: worked without the delay for all tested devices
         // //self.delay_ms(1);
-        while self.is_busy(is_busy_low) {
-            // //tested: REMOVAL of DELAY: it's only waiting for the signal anyway and should continue work asap
-            // //old: shorten the time? it was 100 in the beginning
-            // //self.delay_ms(5);
+        let mut waited_ms = 0;
+        while self.is_busy(is_busy_low)? {
+            if let Some(timeout) = self.busy_timeout {
+                if waited_ms >= timeout {
+                    warn!("still busy after {} ms", waited_ms);
+                    return Err(EpdError::BusyTimeout);
+                }
+            }
+            // Poll in 1ms steps, it's only waiting for the signal anyway and should continue work asap
+            delay.delay_ms(1);
+            waited_ms += 1;
+        }
+        if waited_ms > 0 {
+            // includes the refresh time when waiting after a display update
+            debug!("busy for {} ms", waited_ms);
         }
+        Ok(())
     }
 
     /// Checks if device is still busy
@@ -161,9 +270,15 @@ where
     ///
     /// Most likely there was a mistake with the 2in9 busy connection
     /// //TODO: use the #cfg feature to make this compile the right way for the certain types
-    pub(crate) fn is_busy(&self, is_busy_low: bool) -> bool {
-        (is_busy_low && self.busy.is_low().unwrap_or(false))
-            || (!is_busy_low && self.busy.is_high().unwrap_or(false))
+    pub(crate) fn is_busy(
+        &self,
+        is_busy_low: bool,
+    ) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        if is_busy_low {
+            self.busy.is_low().map_err(EpdError::BusyPin)
+        } else {
+            self.busy.is_high().map_err(EpdError::BusyPin)
+        }
     }
 
     /// Resets the device.
@@ -173,15 +288,484 @@ where
     /// The timing of keeping the reset pin low seems to be important and different per device.
     /// Most displays seem to require keeping it low for 10ms, but the 7in5_v2 only seems to reset
     /// properly with 2ms
-    pub(crate) fn reset(&mut self, delay: &mut DELAY, duration: u8) {
-        let _ = self.rst.set_high();
+    pub(crate) fn reset(
+        &mut self,
+        delay: &mut DELAY,
+        duration: u8,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.rst.set_high().map_err(EpdError::RstPin)?;
         delay.delay_ms(10);
 
-        let _ = self.rst.set_low();
+        self.rst.set_low().map_err(EpdError::RstPin)?;
         delay.delay_ms(duration);
-        let _ = self.rst.set_high();
+        self.rst.set_high().map_err(EpdError::RstPin)?;
         //TODO: the upstream libraries always sleep for 200ms here
         // 10ms works fine with just for the 7in5_v2 but this needs to be validated for other devices
         delay.delay_ms(200);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI, CS, BUSY, DC, RST, DELAY, const SINGLE_BYTE_WRITE: bool>
+    DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>
+where
+    SPI: embedded_hal_async::spi::SpiBus<u8>,
+    CS: embedded_hal_1::digital::OutputPin,
+    BUSY: embedded_hal_1::digital::InputPin + embedded_hal_async::digital::Wait,
+    DC: embedded_hal_1::digital::OutputPin,
+    RST: embedded_hal_1::digital::OutputPin,
+    DELAY: embedded_hal_async::delay::DelayNs,
+{
+    /// Async counterpart of [run](DisplayInterface::run), executing the same [Step]s
+    pub(crate) async fn run_async(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        is_busy_low: bool,
+        steps: &[Step<'_>],
+    ) -> Result<(), crate::asynch::AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        for step in steps {
+            match *step {
+                Step::Reset(duration) => {
+                    self.rst.set_high().map_err(EpdError::RstPin)?;
+                    delay.delay_ms(10).await;
+                    self.rst.set_low().map_err(EpdError::RstPin)?;
+                    delay.delay_ms(duration.into()).await;
+                    self.rst.set_high().map_err(EpdError::RstPin)?;
+                    delay.delay_ms(200).await;
+                }
+                Step::Command(address) => {
+                    trace!("command 0x{:x}", address);
+                    self.dc.set_low().map_err(EpdError::DcPin)?;
+                    self.write_async(spi, &[address]).await?;
+                }
+                Step::Data(data) => {
+                    self.dc.set_high().map_err(EpdError::DcPin)?;
+                    if SINGLE_BYTE_WRITE {
+                        for val in data.iter().copied() {
+                            self.write_async(spi, &[val]).await?;
+                        }
+                    } else {
+                        self.write_async(spi, data).await?;
+                    }
+                }
+                Step::DataRepeat(val, repetitions) => {
+                    self.dc.set_high().map_err(EpdError::DcPin)?;
+                    if SINGLE_BYTE_WRITE {
+                        for _ in 0..repetitions {
+                            self.write_async(spi, &[val]).await?;
+                        }
+                    } else {
+                        self.write_repeated_async(spi, val, repetitions).await?;
+                    }
+                }
+                Step::WaitUntilIdle => self.wait_until_idle_async(delay, is_busy_low).await?,
+                Step::WaitUntilBusy => self.wait_until_idle_async(delay, !is_busy_low).await?,
+                Step::Delay(ms) => delay.delay_ms(ms.into()).await,
+            }
+        }
+        Ok(())
+    }
+
+    async fn write_async(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), crate::asynch::AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.cs.set_low().map_err(EpdError::CsPin)?;
+        for data_chunk in data.chunks(MAX_TRANSFER_LEN) {
+            spi.write(data_chunk).await.map_err(EpdError::Spi)?;
+        }
+        // the bus may still be shifting out data when write returns
+        spi.flush().await.map_err(EpdError::Spi)?;
+        self.cs.set_high().map_err(EpdError::CsPin)?;
+        Ok(())
+    }
+
+    /// Async counterpart of the bulk path of [data_x_times](DisplayInterface::data_x_times)
+    async fn write_repeated_async(
+        &mut self,
+        spi: &mut SPI,
+        val: u8,
+        repetitions: u32,
+    ) -> Result<(), crate::asynch::AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let chunk = [val; REPEAT_CHUNK_LEN];
+        self.cs.set_low().map_err(EpdError::CsPin)?;
+        let mut remaining = repetitions as usize;
+        while remaining > 0 {
+            let len = remaining.min(REPEAT_CHUNK_LEN);
+            spi.write(&chunk[..len]).await.map_err(EpdError::Spi)?;
+            remaining -= len;
+        }
+        spi.flush().await.map_err(EpdError::Spi)?;
+        self.cs.set_high().map_err(EpdError::CsPin)?;
+        Ok(())
+    }
+
+    /// Awaits the edge of the busy pin which signals that the device is idle
+    ///
+    /// Returns [EpdError::BusyTimeout] if the device is still busy after the busy timeout.
+    async fn wait_until_idle_async(
+        &mut self,
+        delay: &mut DELAY,
+        is_busy_low: bool,
+    ) -> Result<(), crate::asynch::AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        use core::future::{poll_fn, Future};
+        use core::pin::pin;
+        use core::task::Poll;
+
+        let busy_timeout = self.busy_timeout;
+        let busy = &mut self.busy;
+        let mut idle = pin!(async {
+            if is_busy_low {
+                busy.wait_for_high().await
+            } else {
+                busy.wait_for_low().await
+            }
+        });
+        debug!("waiting for busy pin");
+        let timeout = match busy_timeout {
+            Some(timeout) => timeout,
+            None => return idle.await.map_err(EpdError::BusyPin),
+        };
+        let mut elapsed = pin!(delay.delay_ms(timeout));
+
+        poll_fn(|cx| {
+            if let Poll::Ready(result) = idle.as_mut().poll(cx) {
+                Poll::Ready(result.map_err(EpdError::BusyPin))
+            } else if elapsed.as_mut().poll(cx).is_ready() {
+                warn!("still busy after {} ms", timeout);
+                Poll::Ready(Err(EpdError::BusyTimeout))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    /// Async counterpart of [is_busy](DisplayInterface::is_busy)
+    pub(crate) fn is_busy_now(
+        &mut self,
+        is_busy_low: bool,
+    ) -> Result<bool, crate::asynch::AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        if is_busy_low {
+            self.busy.is_low().map_err(EpdError::BusyPin)
+        } else {
+            self.busy.is_high().map_err(EpdError::BusyPin)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use embedded_hal_mock::{
+        delay::MockNoop,
+        pin::{Mock as PinMock, State, Transaction},
+        spi::{Mock as SpiMock, Transaction as SpiTransaction},
+    };
+
+    type Interface<const SINGLE_BYTE_WRITE: bool> =
+        DisplayInterface<SpiMock, PinMock, PinMock, PinMock, PinMock, MockNoop, SINGLE_BYTE_WRITE>;
+
+    #[test]
+    fn wait_until_idle_times_out() {
+        let busy = PinMock::new(&core::array::from_fn::<_, 4, _>(|_| {
+            Transaction::get(State::Low)
+        }));
+        let mut interface: Interface<false> = DisplayInterface::new(
+            PinMock::new(&[]),
+            busy,
+            PinMock::new(&[]),
+            PinMock::new(&[]),
+        );
+        interface.set_busy_timeout(Some(3));
+
+        assert_eq!(
+            interface.wait_until_idle(&mut MockNoop::new(), true),
+            Err(EpdError::BusyTimeout)
+        );
+        interface.busy.done();
+    }
+
+    #[test]
+    fn wait_until_idle_returns_when_idle() {
+        let busy = PinMock::new(&[
+            Transaction::get(State::Low),
+            Transaction::get(State::Low),
+            Transaction::get(State::High),
+        ]);
+        let mut interface: Interface<false> = DisplayInterface::new(
+            PinMock::new(&[]),
+            busy,
+            PinMock::new(&[]),
+            PinMock::new(&[]),
+        );
+        interface.set_busy_timeout(Some(3));
+
+        assert_eq!(
+            interface.wait_until_idle(&mut MockNoop::new(), true),
+            Ok(())
+        );
+        interface.busy.done();
+    }
+
+    fn data_interface<const SINGLE_BYTE_WRITE: bool>(
+        cs_cycles: usize,
+    ) -> Interface<SINGLE_BYTE_WRITE> {
+        let mut cs = std::vec::Vec::new();
+        for _ in 0..cs_cycles {
+            cs.push(Transaction::set(State::Low));
+            cs.push(Transaction::set(State::High));
+        }
+        DisplayInterface::new(
+            PinMock::new(&cs),
+            PinMock::new(&[]),
+            PinMock::new(&[Transaction::set(State::High)]),
+            PinMock::new(&[]),
+        )
+    }
+
+    fn done<const SINGLE_BYTE_WRITE: bool>(
+        mut interface: Interface<SINGLE_BYTE_WRITE>,
+        mut spi: SpiMock,
+    ) {
+        interface.cs.done();
+        interface.dc.done();
+        spi.done();
+    }
+
+    #[test]
+    fn data_bulk_transfer() {
+        let mut interface = data_interface::<false>(1);
+        let mut spi = SpiMock::new(&[SpiTransaction::write(std::vec![1, 2, 3])]);
+
+        interface.data(&mut spi, &[1, 2, 3]).unwrap();
+        done(interface, spi);
+    }
+
+    #[test]
+    fn data_single_byte_write() {
+        let mut interface = data_interface::<true>(3);
+        let mut spi = SpiMock::new(&[
+            SpiTransaction::write(std::vec![1]),
+            SpiTransaction::write(std::vec![2]),
+            SpiTransaction::write(std::vec![3]),
+        ]);
+
+        interface.data(&mut spi, &[1, 2, 3]).unwrap();
+        done(interface, spi);
+    }
+
+    #[test]
+    fn data_x_times_bulk_transfer() {
+        let mut interface = data_interface::<false>(1);
+        let mut spi = SpiMock::new(&[
+            SpiTransaction::write(std::vec![0xFF; REPEAT_CHUNK_LEN]),
+            SpiTransaction::write(std::vec![0xFF; 44]),
+        ]);
+
+        interface
+            .data_x_times(&mut spi, 0xFF, REPEAT_CHUNK_LEN as u32 + 44)
+            .unwrap();
+        done(interface, spi);
+    }
+
+    /// Output pin with an error type of its own, e.g. of an IO expander
+    struct ExpanderPin;
+
+    #[derive(Debug, PartialEq)]
+    struct ExpanderError;
+
+    impl OutputPin for ExpanderPin {
+        type Error = ExpanderError;
+
+        fn set_low(&mut self) -> Result<(), ExpanderError> {
+            Err(ExpanderError)
+        }
+
+        fn set_high(&mut self) -> Result<(), ExpanderError> {
+            Err(ExpanderError)
+        }
+    }
+
+    #[test]
+    fn pins_with_own_error_types() {
+        let mut interface: DisplayInterface<_, _, _, _, _, MockNoop, false> = DisplayInterface::new(
+            PinMock::new(&[]),
+            PinMock::new(&[]),
+            ExpanderPin,
+            PinMock::new(&[]),
+        );
+        let mut spi = SpiMock::new(&[]);
+
+        assert_eq!(
+            interface.data(&mut spi, &[1]),
+            Err(EpdError::DcPin(ExpanderError))
+        );
+        interface.cs.done();
+        spi.done();
+    }
+}
+
+#[cfg(all(test, feature = "async"))]
+mod async_tests {
+    extern crate std;
+
+    use super::*;
+    use core::convert::Infallible;
+    use core::future::{pending, Future};
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use embedded_hal_1::digital::{ErrorType as PinErrorType, InputPin, OutputPin};
+    use embedded_hal_1::spi::ErrorType as SpiErrorType;
+    use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiBus};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    /// Records all bytes written to the bus
+    #[derive(Default)]
+    struct Spi(std::vec::Vec<u8>);
+
+    impl SpiErrorType for Spi {
+        type Error = Infallible;
+    }
+
+    impl SpiBus<u8> for Spi {
+        async fn read(&mut self, _words: &mut [u8]) -> Result<(), Infallible> {
+            Ok(())
+        }
+        async fn write(&mut self, words: &[u8]) -> Result<(), Infallible> {
+            self.0.extend_from_slice(words);
+            Ok(())
+        }
+        async fn transfer(&mut self, _read: &mut [u8], _write: &[u8]) -> Result<(), Infallible> {
+            Ok(())
+        }
+        async fn transfer_in_place(&mut self, _words: &mut [u8]) -> Result<(), Infallible> {
+            Ok(())
+        }
+        async fn flush(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    /// Output pin, or busy pin which is idle (low) unless `stuck` is set
+    #[derive(Default)]
+    struct Pin {
+        stuck: bool,
+    }
+
+    impl PinErrorType for Pin {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for Pin {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    impl InputPin for Pin {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(self.stuck)
+        }
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            Ok(!self.stuck)
+        }
+    }
+
+    impl Wait for Pin {
+        async fn wait_for_high(&mut self) -> Result<(), Infallible> {
+            pending().await
+        }
+        async fn wait_for_low(&mut self) -> Result<(), Infallible> {
+            if self.stuck {
+                pending().await
+            }
+            Ok(())
+        }
+        async fn wait_for_rising_edge(&mut self) -> Result<(), Infallible> {
+            pending().await
+        }
+        async fn wait_for_falling_edge(&mut self) -> Result<(), Infallible> {
+            pending().await
+        }
+        async fn wait_for_any_edge(&mut self) -> Result<(), Infallible> {
+            pending().await
+        }
+    }
+
+    /// Delay which returns immediately
+    struct Delay;
+
+    impl DelayNs for Delay {
+        async fn delay_ns(&mut self, _ns: u32) {}
+    }
+
+    type Interface = DisplayInterface<Spi, Pin, Pin, Pin, Pin, Delay, false>;
+
+    /// Waker which does nothing, the futures are polled until they are ready
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    /// Polls a future which never has to wait for a wake up
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    fn interface(stuck: bool) -> Interface {
+        DisplayInterface::new(
+            Pin::default(),
+            Pin { stuck },
+            Pin::default(),
+            Pin::default(),
+        )
+    }
+
+    #[test]
+    fn run_async_writes_steps() {
+        let mut interface = interface(false);
+        let mut spi = Spi::default();
+        let steps = [
+            Step::Reset(10),
+            Step::Command(0x10),
+            Step::Data(&[0x01, 0x02]),
+            Step::WaitUntilIdle,
+            Step::Command(0x13),
+            Step::DataRepeat(0xFF, 3),
+            Step::Delay(5),
+        ];
+
+        block_on(interface.run_async(&mut spi, &mut Delay, false, &steps)).unwrap();
+        assert_eq!(spi.0, [0x10, 0x01, 0x02, 0x13, 0xFF, 0xFF, 0xFF]);
+    }
+
+    #[test]
+    fn wait_until_idle_async_times_out() {
+        let mut interface = interface(true);
+        interface.set_busy_timeout(Some(3));
+
+        assert_eq!(
+            block_on(interface.wait_until_idle_async(&mut Delay, false)),
+            Err(EpdError::BusyTimeout)
+        );
+        assert_eq!(interface.is_busy_now(false), Ok(true));
     }
 }
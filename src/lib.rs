@@ -2,9 +2,17 @@
 //!
 //! - Built using [`embedded-hal`] traits.
 //! - Graphics support is added through [`embedded-graphics`]
+//! - An async API on top of [`embedded-hal-async`] is available with the `async` feature, see [asynch]
+//! - Commands, busy waits and refresh times are logged through [`defmt`] or [`log`] with the feature
+//!   of the same name, nothing is logged without either of them
+//! - Display buffers can be exported as PBM/PGM/PPM or PNG images for snapshot tests with the
+//!   `std` feature, see the `snapshot` module
 //!
 //! [`embedded-graphics`]: https://docs.rs/embedded-graphics/
 //! [`embedded-hal`]: https://docs.rs/embedded-hal
+//! [`embedded-hal-async`]: https://docs.rs/embedded-hal-async
+//! [`defmt`]: https://docs.rs/defmt
+//! [`log`]: https://docs.rs/log
 //!
 
 //!
@@ -12,7 +20,7 @@
 //!
 //!```rust, no_run
 //!# use embedded_hal_mock::*;
-//!# fn main() -> Result<(), MockError> {
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
 //!use embedded_graphics::{
 //!    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 //!};
@@ -63,47 +71,96 @@
 #![no_std]
 #![deny(missing_docs)]
 
+#[macro_use]
+mod logging;
+
 #[cfg(feature = "graphics")]
 pub mod graphics;
 
+#[cfg(feature = "graphics")]
+mod display_buffer;
+
+#[cfg(feature = "graphics")]
+pub mod dither;
+
+#[cfg(feature = "graphics")]
+pub mod paged;
+
 mod traits;
 
 pub mod color;
 
+pub mod error;
+
+pub mod lut;
+
+pub mod ghosting;
+
+pub mod session;
+
+pub mod power;
+
+pub mod any_epd;
+
 /// Interface for the physical connection between display and the controlling device
 mod interface;
 
+mod sequence;
+
+#[cfg(feature = "async")]
+pub mod asynch;
+
+#[cfg(any(test, feature = "std"))]
+extern crate std;
+#[cfg(any(test, feature = "simulator"))]
+pub mod simulator;
+#[cfg(any(test, feature = "std"))]
+pub mod snapshot;
+#[cfg(any(test, feature = "std"))]
+pub mod trace;
+
 pub mod epd1in54;
 pub mod epd1in54b;
 pub mod epd1in54c;
 pub mod epd2in13_v2;
 pub mod epd2in13bc;
+pub mod epd2in13d;
 pub mod epd2in7b;
 pub mod epd2in9;
 pub mod epd2in9_v2;
 pub mod epd2in9bc;
+pub mod epd2in9d;
 pub mod epd4in2;
 pub mod epd5in65f;
 pub mod epd7in5;
 pub mod epd7in5_hd;
 pub mod epd7in5_v2;
 
-pub mod epd2in13_GDEW0213T5D;
+#[allow(non_snake_case)]
 pub mod epd2in13_GDEQ0213B74;
+#[allow(non_snake_case)]
+pub mod epd2in13_GDEW0213T5D;
 
 pub(crate) mod type_a;
 
+pub mod ssd1680;
+
+pub mod uc8151;
+
 /// Includes everything important besides the chosen Display
 pub mod prelude {
-    pub use crate::color::{Color, OctColor, TriColor};
+    pub use crate::color::{Color, OctColor, QuadColor, TriColor};
+    pub use crate::error::EpdError;
     pub use crate::traits::{
-        QuickRefresh, RefreshLut, WaveshareDisplay, WaveshareThreeColorDisplay,
+        Deinit, DriverError, ExternalTemperature, FullRefresh, GrayscaleDisplay, LutSelection,
+        PanelInfo, PartialUpdate, QuickRefresh, RefreshLut, StreamingColorFrame, StreamingFrame,
+        TemperatureBand, WaveshareDisplay, WaveshareThreeColorDisplay,
     };
 
-    pub use crate::SPI_MODE;
+    pub use crate::{DEFAULT_BUSY_TIMEOUT_MS, SPI_MODE};
 
     #[cfg(feature = "graphics")]
-    pub use crate::graphics::{Display, DisplayRotation, OctDisplay, TriDisplay};
+    pub use crate::graphics::{Display, DisplayRotation, OctDisplay, QuadDisplay, TriDisplay};
 }
 
 /// Computes the needed buffer length. Takes care of rounding up in case width
@@ -116,7 +173,14 @@ pub mod prelude {
 /// \[XXXXX210\]\[76543210\]...\[76543210\] | height
 /// \[XXXXX210\]\[76543210\]...\[76543210\] v
 pub const fn buffer_len(width: usize, height: usize) -> usize {
-    (width + 7) / 8 * height
+    width.div_ceil(8) * height
+}
+
+/// Computes the needed buffer length for displays storing 2 bits per pixel
+/// (e.g. 4 grayscales). Takes care of rounding up in case width is not
+/// divisible by 4.
+pub const fn gray_buffer_len(width: usize, height: usize) -> usize {
+    width.div_ceil(4) * height
 }
 
 use embedded_hal::spi::{Mode, Phase, Polarity};
@@ -127,3 +191,10 @@ pub const SPI_MODE: Mode = Mode {
     phase: Phase::CaptureOnFirstTransition,
     polarity: Polarity::IdleLow,
 };
+
+/// Default maximum time in ms the drivers wait for the busy pin before returning
+/// [EpdError::BusyTimeout](error::EpdError::BusyTimeout)
+///
+/// Long enough for the slowest (multi color) refreshes at low temperatures.
+/// Can be changed with [WaveshareDisplay::set_busy_timeout](prelude::WaveshareDisplay::set_busy_timeout)
+pub const DEFAULT_BUSY_TIMEOUT_MS: u32 = 60_000;
@@ -0,0 +1,35 @@
+//! Logging facade used by the drivers
+//!
+//! The macros forward to [defmt](https://docs.rs/defmt) with the `defmt` feature, to
+//! [log](https://docs.rs/log) with the `log` feature and compile to nothing otherwise.
+//! Format strings therefore need to stick to the syntax both understand: `{}`, `{:x}` and `{:?}`.
+//! defmt wins if both features are enabled.
+
+#![allow(unused_macros)]
+
+macro_rules! log_at {
+    ($level:ident, $s:literal $(, $x:expr)* $(,)?) => {{
+        #[cfg(feature = "defmt")]
+        ::defmt::$level!($s $(, $x)*);
+        #[cfg(all(feature = "log", not(feature = "defmt")))]
+        ::log::$level!($s $(, $x)*);
+        #[cfg(not(any(feature = "defmt", feature = "log")))]
+        let _ = ($(&$x,)*);
+    }};
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { log_at!(trace, $($arg)*) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { log_at!(debug, $($arg)*) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { log_at!(info, $($arg)*) };
+}
+
+macro_rules! warn {
+    ($($arg:tt)*) => { log_at!(warn, $($arg)*) };
+}
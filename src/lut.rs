@@ -0,0 +1,695 @@
+//! Decoding and safety checks of waveforms (LUTs)
+//!
+//! A waveform tells the controller which voltage to apply to a pixel for how many frames,
+//! depending on its old and new color. A pixel which is driven more into one direction than
+//! into the other accumulates a DC bias, which causes ghosting and permanently damages the
+//! panel over time.
+//!
+//! [Lut] decodes the LUT formats used by the drivers of this crate into [Group]s of phases
+//! and [analyzes](Lut::analyze) the net DC bias of every transition and the refresh duration.
+//! [validate](Lut::validate) refuses waveforms which leave a bias on pixels that keep their
+//! color or on pixels which change their color and back.
+//!
+//! The bias is measured in frames of a drive voltage relative to VCOM: +1 for every frame of a
+//! positive, -1 for every frame of a negative voltage. The magnitude of the voltages (e.g. VSH1
+//! vs VSH2) isn't taken into account.
+//!
+//! ```rust
+//! use epd_waveshare::lut::{Lut, LutLimits, Waveform};
+//!
+//! // 70 byte SSD1675 LUT, driving pixels turning white up and pixels turning black down
+//! let mut bytes = [0u8; 70];
+//! bytes[7] = 0x40; // black to white: group 0, phase A to VSH
+//! bytes[14] = 0x80; // white to black: group 0, phase A to VSL
+//! bytes[35] = 4; // group 0: 4 frames in phase A
+//! let lut = Lut::ssd1675(&bytes).unwrap();
+//!
+//! let analysis = lut.analyze();
+//! assert_eq!(analysis.bias(Waveform::BlackToWhite), 4);
+//! assert_eq!(analysis.bias(Waveform::WhiteToBlack), -4);
+//! assert_eq!(analysis.duration_ms(50), 80);
+//! assert!(lut.validate(&LutLimits::default()).is_ok());
+//!
+//! // One more frame down for pixels turning black leaves a bias of 1 frame on pixels
+//! // turning black and white again
+//! bytes[14] = 0xA0;
+//! bytes[36] = 1;
+//! let lut = Lut::ssd1675(&bytes).unwrap();
+//! assert_eq!(lut.analyze().cycle_bias(), -1);
+//! assert!(lut.validate(&LutLimits { max_bias: 0, ..LutLimits::default() }).is_err());
+//! ```
+
+use core::fmt;
+
+use crate::uc8151::{Level, Uc8151Lut};
+
+/// Length of the LUT of the [type_a](LutFormat::TypeA) controllers
+const TYPE_A_LEN: usize = 30;
+/// Length of the LUT of the SSD1675
+const SSD1675_LEN: usize = 70;
+/// Length of the LUT of the SSD1680
+const SSD1680_LEN: usize = 153;
+
+/// Phase of the LUT of the [type_a](LutFormat::TypeA) controllers
+const TYPE_A_PHASES: usize = 20;
+
+/// One waveform of a LUT: a pixel transition or the VCOM waveform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    /// Pixels staying black
+    BlackToBlack,
+    /// Pixels turning white
+    BlackToWhite,
+    /// Pixels turning black
+    WhiteToBlack,
+    /// Pixels staying white
+    WhiteToWhite,
+    /// Voltage of the common electrode
+    Vcom,
+}
+
+impl Waveform {
+    /// All waveforms, pixel transitions first
+    pub const ALL: [Waveform; 5] = [
+        Waveform::BlackToBlack,
+        Waveform::BlackToWhite,
+        Waveform::WhiteToBlack,
+        Waveform::WhiteToWhite,
+        Waveform::Vcom,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            Waveform::BlackToBlack => 0,
+            Waveform::BlackToWhite => 1,
+            Waveform::WhiteToBlack => 2,
+            Waveform::WhiteToWhite => 3,
+            Waveform::Vcom => 4,
+        }
+    }
+}
+
+/// Format of a LUT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LutFormat {
+    /// 30 bytes: 20 phases with 4 bit frame counts, constant VCOM
+    /// (IL3820 and SSD1608, see the `type_a` displays)
+    TypeA,
+    /// 5 registers with 7 groups of 4 phases each ([Uc8151Lut])
+    Uc8151,
+    /// 70 bytes: 7 groups of 4 phases (SSD1675, used by the Epd 2in13 (V2) and the
+    /// full refresh of the GDEQ0213B74)
+    Ssd1675,
+    /// 153 bytes: 12 groups of 4 phases with state repeats (SSD1680, used by the Epd 2in9 V2
+    /// and the partial refresh of the GDEQ0213B74)
+    Ssd1680,
+}
+
+/// Voltage applied during a [Phase]
+///
+/// For the VCOM waveform the voltages are added to the DC VCOM level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Voltage {
+    /// VSS/GND, DC VCOM for the VCOM waveform
+    Ground,
+    /// VSH/VSH1/VDH
+    High,
+    /// VSH2/VDHR, the second positive source voltage
+    High2,
+    /// VSL/VDL
+    Low,
+    /// Not driven
+    Floating,
+}
+
+impl Voltage {
+    /// Direction of the voltage for the DC bias
+    fn sign(self) -> i32 {
+        match self {
+            Voltage::High | Voltage::High2 => 1,
+            Voltage::Low => -1,
+            Voltage::Ground | Voltage::Floating => 0,
+        }
+    }
+
+    /// Voltage select bits of the SSD16xx controllers
+    fn from_ssd16xx(bits: u8, vcom: bool) -> Self {
+        match bits & 0b11 {
+            0b00 => Voltage::Ground,
+            0b01 => Voltage::High,
+            0b10 => Voltage::Low,
+            _ if vcom => Voltage::Floating,
+            _ => Voltage::High2,
+        }
+    }
+
+    fn from_uc8151(level: Level, vcom: bool) -> Self {
+        match level {
+            Level::Ground => Voltage::Ground,
+            Level::High => Voltage::High,
+            Level::Low => Voltage::Low,
+            Level::Floating if vcom => Voltage::Floating,
+            Level::Floating => Voltage::High2,
+        }
+    }
+}
+
+/// A voltage held for a number of frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Phase {
+    /// Applied voltage
+    pub voltage: Voltage,
+    /// Duration in frames
+    pub frames: u8,
+}
+
+/// Four phases A to D of a waveform, which are repeated as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Group {
+    /// Phases A to D
+    pub phases: [Phase; 4],
+    /// How often phases A and B, and phases C and D run in a row
+    ///
+    /// Always 1 except for the SSD1680.
+    pub state_repeat: [u16; 2],
+    /// How often the whole group runs
+    pub repeat: u16,
+}
+
+impl Group {
+    /// Frames of one run of the group and its signed voltage frames
+    fn frames_and_bias(&self) -> (u32, i32) {
+        let mut frames = 0;
+        let mut bias = 0;
+        for (i, phase) in self.phases.iter().enumerate() {
+            let frames_of_phase = u32::from(phase.frames) * u32::from(self.state_repeat[i / 2]);
+            frames += frames_of_phase;
+            bias += phase.voltage.sign() * frames_of_phase as i32;
+        }
+        let repeat = u32::from(self.repeat);
+        (frames * repeat, bias * repeat as i32)
+    }
+}
+
+/// Errors of decoding or validating a LUT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LutError {
+    /// The LUT doesn't have the length of its format
+    Length {
+        /// Length of the format
+        expected: usize,
+        /// Length of the given LUT
+        actual: usize,
+    },
+    /// Pixels keeping their color are left with a DC bias
+    DcBias {
+        /// [Waveform::BlackToBlack] or [Waveform::WhiteToWhite]
+        waveform: Waveform,
+        /// Net bias in frames
+        bias: i32,
+    },
+    /// Pixels turning black and white again are left with a DC bias
+    CycleBias {
+        /// Net bias of [Waveform::BlackToWhite] and [Waveform::WhiteToBlack] in frames
+        bias: i32,
+    },
+    /// The refresh takes more frames than allowed
+    Duration {
+        /// Frames of the refresh
+        frames: u32,
+    },
+}
+
+impl fmt::Display for LutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LutError::Length { expected, actual } => {
+                write!(
+                    f,
+                    "LUT has {} bytes, but {} bytes are needed",
+                    actual, expected
+                )
+            }
+            LutError::DcBias { waveform, bias } => {
+                write!(
+                    f,
+                    "{:?} waveform has a DC bias of {} frames",
+                    waveform, bias
+                )
+            }
+            LutError::CycleBias { bias } => write!(
+                f,
+                "Black to white and white to black waveforms have a DC bias of {} frames",
+                bias
+            ),
+            LutError::Duration { frames } => write!(f, "LUT takes too long ({} frames)", frames),
+        }
+    }
+}
+
+/// Limits checked by [Lut::validate]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LutLimits {
+    /// Largest net bias in frames of pixels keeping their color and of pixels turning black
+    /// and white again
+    pub max_bias: u32,
+    /// Largest number of frames of a refresh
+    pub max_frames: u32,
+}
+
+impl Default for LutLimits {
+    /// Allows a bias of 2 frames, which some partial waveforms of the panel manufacturers
+    /// leave on unchanged pixels, and refreshes of any length
+    fn default() -> Self {
+        LutLimits {
+            max_bias: 2,
+            max_frames: u32::MAX,
+        }
+    }
+}
+
+/// Result of [Lut::analyze]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LutAnalysis {
+    frames: u32,
+    bias: [i32; 5],
+}
+
+impl LutAnalysis {
+    /// Number of frames of a refresh, the longest of all waveforms
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Net DC bias of a pixel transition relative to VCOM in frames
+    ///
+    /// For [Waveform::Vcom] this is the bias of the VCOM waveform itself.
+    pub fn bias(&self, waveform: Waveform) -> i32 {
+        match waveform {
+            Waveform::Vcom => self.bias[Waveform::Vcom.index()],
+            _ => self.bias[waveform.index()] - self.bias[Waveform::Vcom.index()],
+        }
+    }
+
+    /// Net DC bias of a pixel turning white and black again (or the other way round)
+    pub fn cycle_bias(&self) -> i32 {
+        self.bias(Waveform::BlackToWhite) + self.bias(Waveform::WhiteToBlack)
+    }
+
+    /// Estimated duration of a refresh at the given frame rate in ms
+    pub fn duration_ms(&self, frame_rate_hz: u32) -> u32 {
+        (u64::from(self.frames) * 1000 / u64::from(frame_rate_hz.max(1))) as u32
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Data<'a> {
+    Bytes(LutFormat, &'a [u8]),
+    Uc8151(&'a Uc8151Lut),
+}
+
+/// A LUT in one of the [LutFormat]s
+#[derive(Debug, Clone, Copy)]
+pub struct Lut<'a> {
+    data: Data<'a>,
+}
+
+impl<'a> Lut<'a> {
+    fn bytes(format: LutFormat, bytes: &'a [u8], expected: usize) -> Result<Self, LutError> {
+        if bytes.len() == expected {
+            Ok(Lut {
+                data: Data::Bytes(format, bytes),
+            })
+        } else {
+            Err(LutError::Length {
+                expected,
+                actual: bytes.len(),
+            })
+        }
+    }
+
+    /// 30 byte LUT of the `type_a` displays
+    pub fn type_a(bytes: &'a [u8]) -> Result<Self, LutError> {
+        Self::bytes(LutFormat::TypeA, bytes, TYPE_A_LEN)
+    }
+
+    /// 70 byte LUT of the SSD1675
+    pub fn ssd1675(bytes: &'a [u8]) -> Result<Self, LutError> {
+        Self::bytes(LutFormat::Ssd1675, bytes, SSD1675_LEN)
+    }
+
+    /// 153 byte LUT of the SSD1680
+    pub fn ssd1680(bytes: &'a [u8]) -> Result<Self, LutError> {
+        Self::bytes(LutFormat::Ssd1680, bytes, SSD1680_LEN)
+    }
+
+    /// Waveform registers of the UC8151
+    pub fn uc8151(lut: &'a Uc8151Lut) -> Self {
+        Lut {
+            data: Data::Uc8151(lut),
+        }
+    }
+
+    /// Format of the LUT
+    pub fn format(&self) -> LutFormat {
+        match self.data {
+            Data::Bytes(format, _) => format,
+            Data::Uc8151(_) => LutFormat::Uc8151,
+        }
+    }
+
+    /// Number of groups of every waveform
+    pub fn groups(&self) -> usize {
+        match self.format() {
+            LutFormat::TypeA => TYPE_A_PHASES / 4,
+            LutFormat::Uc8151 => Uc8151Lut::GROUPS,
+            LutFormat::Ssd1675 => 7,
+            LutFormat::Ssd1680 => 12,
+        }
+    }
+
+    /// Decodes a group of a waveform
+    ///
+    /// The [type_a](LutFormat::TypeA) LUTs have no groups, their phases are returned four by
+    /// four. Their VCOM waveform is constant.
+    ///
+    /// # Panics
+    ///
+    /// If `group` isn't below [groups](Lut::groups)
+    pub fn group(&self, waveform: Waveform, group: usize) -> Group {
+        assert!(group < self.groups(), "LUT group out of range");
+        let vcom = waveform == Waveform::Vcom;
+        let mut phases = [Phase {
+            voltage: Voltage::Ground,
+            frames: 0,
+        }; 4];
+        let mut state_repeat = [1; 2];
+        let repeat;
+        match self.data {
+            Data::Bytes(LutFormat::TypeA, bytes) => {
+                for (i, phase) in phases.iter_mut().enumerate() {
+                    let index = group * 4 + i;
+                    if !vcom {
+                        let shift = 6 - 2 * waveform.index();
+                        phase.voltage = Voltage::from_ssd16xx(bytes[index] >> shift, false);
+                    }
+                    let timing = bytes[TYPE_A_PHASES + index / 2];
+                    phase.frames = if index % 2 == 0 {
+                        timing & 0x0F
+                    } else {
+                        timing >> 4
+                    };
+                }
+                repeat = 1;
+            }
+            Data::Bytes(format, bytes) => {
+                let groups = self.groups();
+                let select = bytes[waveform.index() * groups + group];
+                for (i, phase) in phases.iter_mut().enumerate() {
+                    phase.voltage = Voltage::from_ssd16xx(select >> (6 - 2 * i), vcom);
+                }
+                let timing = &bytes[5 * groups..];
+                if format == LutFormat::Ssd1680 {
+                    // TP[nA], TP[nB], SR[nAB], TP[nC], TP[nD], SR[nCD], RP[n]
+                    let timing = &timing[group * 7..(group + 1) * 7];
+                    for (phase, &frames) in phases
+                        .iter_mut()
+                        .zip(&[timing[0], timing[1], timing[3], timing[4]])
+                    {
+                        phase.frames = frames;
+                    }
+                    state_repeat = [u16::from(timing[2]) + 1, u16::from(timing[5]) + 1];
+                    repeat = u16::from(timing[6]) + 1;
+                } else {
+                    // TP[nA], TP[nB], TP[nC], TP[nD], RP[n]
+                    let timing = &timing[group * 5..(group + 1) * 5];
+                    for (phase, &frames) in phases.iter_mut().zip(timing) {
+                        phase.frames = frames;
+                    }
+                    repeat = u16::from(timing[4]) + 1;
+                }
+            }
+            Data::Uc8151(lut) => {
+                let bytes = &lut.waveform(waveform)[group * 6..(group + 1) * 6];
+                for (i, phase) in phases.iter_mut().enumerate() {
+                    let level = Level::from_bits(bytes[0] >> (6 - 2 * i));
+                    phase.voltage = Voltage::from_uc8151(level, vcom);
+                    phase.frames = bytes[1 + i];
+                }
+                repeat = u16::from(bytes[5]);
+            }
+        }
+        Group {
+            phases,
+            state_repeat,
+            repeat,
+        }
+    }
+
+    /// Computes the DC bias of every transition and the number of frames of a refresh
+    pub fn analyze(&self) -> LutAnalysis {
+        let mut analysis = LutAnalysis {
+            frames: 0,
+            bias: [0; 5],
+        };
+        for &waveform in &Waveform::ALL {
+            let mut frames = 0;
+            for group in 0..self.groups() {
+                let (group_frames, bias) = self.group(waveform, group).frames_and_bias();
+                frames += group_frames;
+                analysis.bias[waveform.index()] += bias;
+            }
+            analysis.frames = analysis.frames.max(frames);
+        }
+        analysis
+    }
+
+    /// Analyzes the LUT and refuses it if it leaves a DC bias or takes longer than `limits`
+    ///
+    /// Checked are the pixels keeping their color and the pixels turning black and white
+    /// again. A single color change always leaves a bias, which the opposite change removes.
+    pub fn validate(&self, limits: &LutLimits) -> Result<LutAnalysis, LutError> {
+        let analysis = self.analyze();
+        for &waveform in &[Waveform::BlackToBlack, Waveform::WhiteToWhite] {
+            let bias = analysis.bias(waveform);
+            if bias.unsigned_abs() > limits.max_bias {
+                return Err(LutError::DcBias { waveform, bias });
+            }
+        }
+        let bias = analysis.cycle_bias();
+        if bias.unsigned_abs() > limits.max_bias {
+            return Err(LutError::CycleBias { bias });
+        }
+        if analysis.frames > limits.max_frames {
+            return Err(LutError::Duration {
+                frames: analysis.frames,
+            });
+        }
+        Ok(analysis)
+    }
+}
+
+/// Scales the frames of the `phases` of every group in `bytes` by `num / den`
+///
+/// Estimates the waveforms of the cold and hot [TemperatureBand](crate::traits::TemperatureBand)s
+/// from the one of the normal band, as the manufacturers don't publish them. The `groups`
+/// groups are `group_len` bytes long and start at `first`, `phases` are the indices of the
+/// frame counts in a group. Phases keep at least one frame and are limited to 255 frames.
+pub(crate) const fn scale_phases<const N: usize>(
+    mut bytes: [u8; N],
+    first: usize,
+    groups: usize,
+    group_len: usize,
+    phases: [usize; 4],
+    num: u16,
+    den: u16,
+) -> [u8; N] {
+    let mut group = 0;
+    while group < groups {
+        let mut phase = 0;
+        while phase < phases.len() {
+            let index = first + group * group_len + phases[phase];
+            let frames = bytes[index] as u16;
+            if frames > 0 {
+                let scaled = (frames * num + den / 2) / den;
+                bytes[index] = if scaled == 0 {
+                    1
+                } else if scaled > 0xFF {
+                    0xFF
+                } else {
+                    scaled as u8
+                };
+            }
+            phase += 1;
+        }
+        group += 1;
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::RefreshLut;
+
+    fn check(lut: Lut<'_>) -> LutAnalysis {
+        match lut.validate(&LutLimits::default()) {
+            Ok(analysis) => analysis,
+            Err(e) => panic!("{:?} LUT refused: {:?}", lut.format(), e),
+        }
+    }
+
+    #[test]
+    fn builtin_luts_are_balanced() {
+        use crate::epd2in13_GDEQ0213B74::constants as gdeq;
+        use crate::epd2in13_v2::constants as v2;
+        use crate::type_a::constants as type_a;
+
+        check(Lut::type_a(&type_a::LUT_FULL_UPDATE).unwrap());
+        check(Lut::type_a(&type_a::LUT_PARTIAL_UPDATE).unwrap());
+        check(Lut::ssd1675(&v2::LUT_FULL_UPDATE).unwrap());
+        check(Lut::ssd1675(&v2::LUT_PARTIAL_UPDATE).unwrap());
+        check(Lut::ssd1680(&gdeq::LUT_PARTIAL_UPDATE).unwrap());
+        check(Lut::ssd1680(&crate::epd2in9_v2::LUT_PARTIAL_2IN9).unwrap());
+        check(Lut::uc8151(&Uc8151Lut::builtin(RefreshLut::Full)));
+        check(Lut::uc8151(&Uc8151Lut::builtin(RefreshLut::Quick)));
+    }
+
+    #[test]
+    fn estimated_temperature_luts() {
+        use crate::epd2in13_GDEQ0213B74::constants as gdeq;
+        use crate::uc8151::constants as uc8151;
+
+        let normal = check(Lut::ssd1680(&gdeq::LUT_PARTIAL_UPDATE).unwrap());
+        let cold = check(Lut::ssd1680(&gdeq::LUT_PARTIAL_UPDATE_COLD).unwrap());
+        let hot = check(Lut::ssd1680(&gdeq::LUT_PARTIAL_UPDATE_HOT).unwrap());
+        assert!(hot.frames() < normal.frames() && normal.frames() < cold.frames());
+
+        let normal = check(Lut::uc8151(&uc8151::FULL_LUT));
+        let cold = check(Lut::uc8151(&uc8151::FULL_LUT_COLD));
+        let hot = check(Lut::uc8151(&uc8151::FULL_LUT_HOT));
+        assert!(hot.frames() < normal.frames() && normal.frames() < cold.frames());
+
+        let normal = check(Lut::uc8151(&uc8151::DIRECT_LUT));
+        let cold = check(Lut::uc8151(&uc8151::DIRECT_LUT_COLD));
+        let hot = check(Lut::uc8151(&uc8151::DIRECT_LUT_HOT));
+        assert!(hot.frames() < normal.frames() && normal.frames() < cold.frames());
+    }
+
+    #[test]
+    fn decode_type_a() {
+        use crate::type_a::constants::LUT_PARTIAL_UPDATE;
+
+        let lut = Lut::type_a(&LUT_PARTIAL_UPDATE).unwrap();
+        let group = lut.group(Waveform::BlackToWhite, 0);
+        assert_eq!(
+            group.phases,
+            [
+                Phase {
+                    voltage: Voltage::High,
+                    frames: 3
+                },
+                Phase {
+                    voltage: Voltage::High,
+                    frames: 1
+                },
+                Phase {
+                    voltage: Voltage::High,
+                    frames: 4
+                },
+                Phase {
+                    voltage: Voltage::Ground,
+                    frames: 1
+                },
+            ]
+        );
+        assert_eq!(group.repeat, 1);
+
+        let analysis = lut.analyze();
+        assert_eq!(analysis.frames(), 20);
+        assert_eq!(analysis.bias(Waveform::BlackToWhite), 16);
+        assert_eq!(analysis.bias(Waveform::WhiteToBlack), -16);
+        assert_eq!(analysis.cycle_bias(), 0);
+        assert_eq!(analysis.bias(Waveform::WhiteToWhite), 0);
+    }
+
+    #[test]
+    fn decode_ssd1680() {
+        let lut = Lut::ssd1680(&crate::epd2in9_v2::LUT_PARTIAL_2IN9).unwrap();
+        let group = lut.group(Waveform::BlackToWhite, 0);
+        assert_eq!(group.phases[0].voltage, Voltage::Low);
+        assert_eq!(group.phases[0].frames, 0x0A);
+        assert_eq!(group.state_repeat, [1, 1]);
+        assert_eq!(group.repeat, 3);
+        assert_eq!(
+            lut.group(Waveform::BlackToBlack, 1).phases[0].voltage,
+            Voltage::High
+        );
+
+        let mut bytes = crate::epd2in9_v2::LUT_PARTIAL_2IN9;
+        // state repeat of phases A and B, repeat of the group
+        bytes[62] = 2;
+        bytes[66] = 1;
+        let analysis = Lut::ssd1680(&bytes).unwrap().analyze();
+        assert_eq!(analysis.bias(Waveform::BlackToWhite), -(0x0A * 3 * 2) - 1);
+    }
+
+    #[test]
+    fn vcom_bias() {
+        let mut bytes = [0u8; 70];
+        // VCOM and white to white: group 0, phase A to VSH for 5 frames
+        bytes[28] = 0x40;
+        bytes[21] = 0x40;
+        bytes[35] = 5;
+        let analysis = Lut::ssd1675(&bytes).unwrap().analyze();
+        assert_eq!(analysis.bias(Waveform::Vcom), 5);
+        assert_eq!(analysis.bias(Waveform::WhiteToWhite), 0);
+        assert_eq!(analysis.bias(Waveform::BlackToBlack), -5);
+        assert_eq!(analysis.duration_ms(100), 50);
+        assert_eq!(
+            Lut::ssd1675(&bytes)
+                .unwrap()
+                .validate(&LutLimits::default()),
+            Err(LutError::DcBias {
+                waveform: Waveform::BlackToBlack,
+                bias: -5
+            })
+        );
+    }
+
+    #[test]
+    fn refuse_unsafe() {
+        use crate::uc8151::Level::*;
+
+        let unbalanced = Uc8151Lut::new()
+            .with_timing(0, [10, 10, 0, 0], 1)
+            .with_levels(Waveform::BlackToWhite, 0, [Low, High, Ground, Ground])
+            .with_levels(Waveform::WhiteToBlack, 0, [High, High, Ground, Ground]);
+        assert_eq!(
+            Lut::uc8151(&unbalanced).validate(&LutLimits::default()),
+            Err(LutError::CycleBias { bias: 20 })
+        );
+
+        let balanced =
+            unbalanced.with_levels(Waveform::WhiteToBlack, 0, [High, Low, Ground, Ground]);
+        let limits = LutLimits {
+            max_bias: 0,
+            max_frames: 19,
+        };
+        assert_eq!(
+            Lut::uc8151(&balanced).validate(&limits),
+            Err(LutError::Duration { frames: 20 })
+        );
+        assert!(Lut::uc8151(&balanced)
+            .validate(&LutLimits::default())
+            .is_ok());
+
+        assert_eq!(
+            Lut::ssd1680(&[0; 70]).err(),
+            Some(LutError::Length {
+                expected: 153,
+                actual: 70
+            })
+        );
+    }
+}
@@ -0,0 +1,408 @@
+//! Drawing in horizontal bands for displays whose frame doesn't fit into memory
+//!
+//! A [PagedDisplay] only holds a band of rows. [render](PagedDisplay::render) calls the
+//! drawing closure once per band and streams each band to the controller with
+//! [StreamingFrame], so a 7.5" frame can be drawn with a buffer of a few rows. The closure
+//! needs to draw the same content on every call, pixels outside of the current band are
+//! dropped.
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
+//!use embedded_graphics::{
+//!    pixelcolor::BinaryColor, prelude::*, primitives::{Line, PrimitiveStyle},
+//!};
+//!use epd_waveshare::{epd7in5_v2::*, paged::PagedDisplay, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!let mut epd = Epd7in5::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!
+//!// 16 rows of 800 pixels instead of 48000 bytes for the frame
+//!let mut band = [0; 16 * WIDTH as usize / 8];
+//!let mut display = PagedDisplay::new(&mut band, WIDTH, HEIGHT, BinaryColor::Off);
+//!display.render(&mut epd, &mut spi, &mut delay, |display| {
+//!    let _ = Line::new(Point::new(0, 0), Point::new(799, 479))
+//!        .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))
+//!        .draw(display);
+//!})?;
+//!epd.display_frame(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_graphics_core::{pixelcolor::BinaryColor, prelude::*};
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::color::{OctColor, TriColor};
+use crate::error::EpdError;
+use crate::graphics::{find_rotation, DisplayRotation};
+use crate::traits::{DriverError, StreamingColorFrame, StreamingFrame};
+
+/// Colors which can be drawn with a [PagedDisplay]
+pub trait PagedColor: PixelColor {
+    /// Bits per pixel in a plane, a divisor of 8
+    const BITS: u32;
+
+    /// Bits of the color in `plane`
+    ///
+    /// Tri-color displays have a b/w plane (`0`) and a chromatic plane (`1`).
+    fn plane_bits(self, plane: usize) -> u8;
+}
+
+impl PagedColor for BinaryColor {
+    const BITS: u32 = 1;
+
+    fn plane_bits(self, _plane: usize) -> u8 {
+        match self {
+            BinaryColor::On => 0,
+            BinaryColor::Off => 1,
+        }
+    }
+}
+
+impl PagedColor for TriColor {
+    const BITS: u32 = 1;
+
+    fn plane_bits(self, plane: usize) -> u8 {
+        match (self, plane) {
+            (TriColor::Black, 0) | (TriColor::Chromatic, 1) => 0,
+            _ => 1,
+        }
+    }
+}
+
+impl PagedColor for OctColor {
+    const BITS: u32 = 4;
+
+    fn plane_bits(self, _plane: usize) -> u8 {
+        self.get_nibble()
+    }
+}
+
+/// Draw target for one band of rows of a frame
+///
+/// See the [module documentation](crate::paged).
+pub struct PagedDisplay<'a, C> {
+    buffer: &'a mut [u8],
+    width: u32,
+    height: u32,
+    rotation: DisplayRotation,
+    background: C,
+    /// First row of the current band
+    first_row: u32,
+    /// Rows of the current band
+    rows: u32,
+    plane: usize,
+}
+
+impl<'a, C: PagedColor> PagedDisplay<'a, C> {
+    /// Paged display for a `width` x `height` pixels frame cleared with `background`
+    ///
+    /// The band holds as many full rows as fit into `buffer`.
+    pub fn new(buffer: &'a mut [u8], width: u32, height: u32, background: C) -> Self {
+        PagedDisplay {
+            buffer,
+            width,
+            height,
+            rotation: DisplayRotation::default(),
+            background,
+            first_row: 0,
+            rows: 0,
+            plane: 0,
+        }
+    }
+
+    /// Sets the rotation of the display
+    pub fn set_rotation(&mut self, rotation: DisplayRotation) {
+        self.rotation = rotation;
+    }
+
+    /// Get the current rotation of the display
+    pub fn rotation(&self) -> DisplayRotation {
+        self.rotation
+    }
+
+    /// Rows of the frame in one band
+    pub fn band_rows(&self) -> u32 {
+        (self.buffer.len() / self.row_bytes()) as u32
+    }
+
+    fn row_bytes(&self) -> usize {
+        (self.width * C::BITS).div_ceil(8) as usize
+    }
+
+    /// Draws and writes all bands of `plane` after the frame was begun
+    fn render_plane<SPI, CS, BUSY, DC, RST, DELAY, EPD, F>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        plane: usize,
+        draw: &mut F,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        EPD: StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>,
+        F: FnMut(&mut Self),
+    {
+        let row_bytes = self.row_bytes();
+        let band_rows = self.band_rows();
+        if band_rows == 0 {
+            return Err(EpdError::BufferSize {
+                expected: row_bytes,
+                actual: self.buffer.len(),
+            });
+        }
+
+        let mut fill = 0;
+        for _ in 0..8 / C::BITS {
+            fill = fill << C::BITS | self.background.plane_bits(plane);
+        }
+
+        self.plane = plane;
+        self.first_row = 0;
+        while self.first_row < self.height {
+            self.rows = band_rows.min(self.height - self.first_row);
+            let len = self.rows as usize * row_bytes;
+            self.buffer[..len].fill(fill);
+            draw(self);
+            epd.write_frame_data(spi, &self.buffer[..len])?;
+            self.first_row += self.rows;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! render_single_plane {
+    ($color:ty) => {
+        impl<'a> PagedDisplay<'a, $color> {
+            /// Writes the frame band by band, calling `draw` for every band
+            ///
+            /// The frame is written like with `update_frame`, it still needs to be displayed.
+            /// `draw` is called again for every band of a
+            /// [base frame](StreamingFrame::begin_base_frame). Returns [EpdError::BufferSize] if
+            /// the buffer doesn't hold a single row.
+            pub fn render<SPI, CS, BUSY, DC, RST, DELAY, EPD, F>(
+                &mut self,
+                epd: &mut EPD,
+                spi: &mut SPI,
+                delay: &mut DELAY,
+                mut draw: F,
+            ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+            where
+                SPI: Write<u8>,
+                CS: OutputPin,
+                BUSY: InputPin,
+                DC: OutputPin,
+                RST: OutputPin,
+                DELAY: DelayMs<u8>,
+                EPD: StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>,
+                F: FnMut(&mut Self),
+            {
+                epd.begin_frame(spi, delay)?;
+                self.render_plane(epd, spi, 0, &mut draw)?;
+                epd.end_frame(spi, delay)?;
+                if epd.begin_base_frame(spi, delay)? {
+                    self.render_plane(epd, spi, 0, &mut draw)?;
+                    epd.end_frame(spi, delay)?;
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+render_single_plane!(BinaryColor);
+render_single_plane!(OctColor);
+
+impl<'a> PagedDisplay<'a, TriColor> {
+    /// Writes the b/w and the chromatic frame band by band, calling `draw` twice for every band
+    ///
+    /// The frames are written like with `update_color_frame`, they still need to be displayed.
+    /// Returns [EpdError::BufferSize] if the buffer doesn't hold a single row.
+    pub fn render<SPI, CS, BUSY, DC, RST, DELAY, EPD, F>(
+        &mut self,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        mut draw: F,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        EPD: StreamingColorFrame<SPI, CS, BUSY, DC, RST, DELAY>,
+        F: FnMut(&mut Self),
+    {
+        epd.begin_frame(spi, delay)?;
+        self.render_plane(epd, spi, 0, &mut draw)?;
+        epd.end_frame(spi, delay)?;
+        epd.begin_chromatic_frame(spi, delay)?;
+        self.render_plane(epd, spi, 1, &mut draw)?;
+        epd.end_frame(spi, delay)
+    }
+}
+
+impl<C: PagedColor> DrawTarget for PagedDisplay<'_, C> {
+    type Color = C;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let size = self.size();
+        let row_bytes = self.row_bytes();
+        for Pixel(point, color) in pixels {
+            if point.x < 0
+                || point.y < 0
+                || point.x as u32 >= size.width
+                || point.y as u32 >= size.height
+            {
+                continue;
+            }
+            let (x, y) = find_rotation(
+                point.x as u32,
+                point.y as u32,
+                self.width,
+                self.height,
+                self.rotation,
+            );
+            if y < self.first_row || y >= self.first_row + self.rows {
+                continue;
+            }
+
+            let bit = x * C::BITS;
+            let index = (y - self.first_row) as usize * row_bytes + bit as usize / 8;
+            let shift = 8 - C::BITS - bit % 8;
+            let mask = ((1u16 << C::BITS) - 1) as u8;
+            self.buffer[index] = self.buffer[index] & !(mask << shift)
+                | (color.plane_bits(self.plane) & mask) << shift;
+        }
+        Ok(())
+    }
+}
+
+impl<C> OriginDimensions for PagedDisplay<'_, C> {
+    fn size(&self) -> Size {
+        match self.rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
+                Size::new(self.width, self.height)
+            }
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                Size::new(self.height, self.width)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{Delay, Pin, Plane, SimulatedPanel, Spi};
+    use crate::traits::WaveshareDisplay;
+    use embedded_graphics::primitives::{Primitive, PrimitiveStyle, Rectangle};
+
+    #[test]
+    fn band_pixels() {
+        // 12 x 4 pixels, bands of 2 rows with 2 bytes each
+        let mut buffer = [0xFF; 5];
+        let mut display = PagedDisplay::new(&mut buffer, 12, 4, BinaryColor::Off);
+        assert_eq!(display.band_rows(), 2);
+        display.first_row = 2;
+        display.rows = 2;
+        for point in [Point::new(0, 0), Point::new(1, 2), Point::new(11, 3)] {
+            let _ = Pixel(point, BinaryColor::On).draw(&mut display);
+        }
+        assert_eq!(buffer[..4], [0xBF, 0xFF, 0xFF, 0xEF]);
+
+        let mut buffer = [0x11; 2];
+        let mut display = PagedDisplay::new(&mut buffer, 4, 1, OctColor::White);
+        display.rows = 1;
+        display.set_rotation(DisplayRotation::Rotate180);
+        let _ = Pixel(Point::new(0, 0), OctColor::Red).draw(&mut display);
+        assert_eq!(buffer, [0x11, 0x14]);
+
+        let mut buffer = [0xFF; 1];
+        let mut display = PagedDisplay::new(&mut buffer, 8, 1, TriColor::White);
+        display.rows = 1;
+        display.plane = 1;
+        let _ = Pixel(Point::new(0, 0), TriColor::Black).draw(&mut display);
+        let _ = Pixel(Point::new(1, 0), TriColor::Chromatic).draw(&mut display);
+        assert_eq!(buffer, [0xBF]);
+    }
+
+    /// Draws a black block with bands of 5 rows on a simulated panel, returns how often the
+    /// block was drawn
+    fn render_block<EPD>(panel: &SimulatedPanel) -> u32
+    where
+        EPD: WaveshareDisplay<Spi, Pin, Pin, Pin, Pin, Delay>
+            + StreamingFrame<Spi, Pin, Pin, Pin, Pin, Delay>,
+    {
+        let (mut epd, mut spi, mut delay) = panel.connect::<EPD>();
+        let (width, height) = (epd.width(), epd.height());
+        let mut band = std::vec![0; 5 * width.div_ceil(8) as usize];
+        let mut display = PagedDisplay::new(&mut band, width, height, BinaryColor::Off);
+        let mut calls = 0;
+        display
+            .render(&mut epd, &mut spi, &mut delay, |display| {
+                calls += 1;
+                let _ = Rectangle::new(Point::new(16, 3), Size::new(24, 9))
+                    .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+                    .draw(display);
+            })
+            .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+
+        assert!(panel.image().is_black_window(16, 3, 24, 9));
+        assert_eq!(panel.violations(), []);
+        calls
+    }
+
+    #[test]
+    fn render_epd4in2() {
+        use crate::epd4in2::{Epd4in2, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::il0398(WIDTH, HEIGHT);
+        let calls = render_block::<Epd4in2<_, _, _, _, _, _>>(&panel);
+        assert_eq!(calls, HEIGHT.div_ceil(5));
+    }
+
+    #[test]
+    fn render_gdeq0213b74() {
+        use crate::epd2in13_GDEQ0213B74::{Epd2in13, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        // The frame is written into both RAMs in full refresh mode
+        let calls = render_block::<Epd2in13<_, _, _, _, _, _>>(&panel);
+        assert_eq!(calls, 2 * HEIGHT.div_ceil(5));
+        assert_eq!(panel.ram(Plane::Old), panel.ram(Plane::New));
+    }
+
+    #[test]
+    fn render_t5d() {
+        use crate::epd2in13_GDEW0213T5D::{Epd2in13_T5D, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let calls = render_block::<Epd2in13_T5D<_, _, _, _, _, _>>(&panel);
+        assert_eq!(calls, HEIGHT.div_ceil(5));
+    }
+}
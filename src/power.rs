@@ -0,0 +1,227 @@
+//! Sleep and wake up tracked in the type of the driver
+//!
+//! After [WaveshareDisplay::sleep] most controllers ignore every command until the next
+//! hardware reset, frames sent in between are silently lost. Wrapping a driver in [Awake]
+//! makes this a compile error: [Awake::sleep] consumes the awake driver and returns an
+//! [Asleep] one, which can only be woken up again or give its pins back.
+//!
+//! [Awake] dereferences to the driver, so all other functions are called as before.
+//!
+//! Example:
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
+//!use epd_waveshare::{epd4in2::*, power::Awake, prelude::*};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!let mut epd = Awake::new(Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?);
+//!epd.clear_frame(&mut spi, &mut delay)?;
+//!epd.display_frame(&mut spi, &mut delay)?;
+//!
+//!let epd = epd.sleep(&mut spi, &mut delay)?;
+//!// epd.display_frame(&mut spi, &mut delay)?; doesn't compile
+//!
+//!let epd = epd.wake_up(&mut spi, &mut delay)?;
+//!let (cs_pin, busy_in, dc, rst) = epd.sleep(&mut spi, &mut delay)?.deinit();
+//!# Ok(())
+//!# }
+//!```
+//!
+//! The sleeping driver has no frame functions:
+//!```rust, compile_fail
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
+//!# use epd_waveshare::{epd4in2::*, power::Awake, prelude::*};
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!let epd = Awake::new(Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?);
+//!let mut epd = epd.sleep(&mut spi, &mut delay)?;
+//!epd.display_frame(&mut spi, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::error::EpdError;
+use crate::traits::{Deinit, DriverError, WaveshareDisplay};
+
+/// A driver whose display is powered up and accepts frames
+///
+/// Dereferences to the driver. Its own [WaveshareDisplay::sleep] stays reachable through the
+/// fully qualified syntax, which bypasses the tracking; use [Awake::sleep] instead.
+pub struct Awake<EPD> {
+    epd: EPD,
+}
+
+/// A driver whose display is in deep sleep
+///
+/// Frames sent now would be lost, so the only ways out are [wake_up](Asleep::wake_up) and
+/// [deinit](Asleep::deinit).
+pub struct Asleep<EPD> {
+    epd: EPD,
+}
+
+/// A failed [Awake::sleep] or [Asleep::wake_up]
+///
+/// The display may be in either state afterwards. The driver is returned as [Asleep] as waking
+/// it up resets the controller, which recovers from both.
+pub struct PowerError<EPD, E> {
+    /// The driver, to retry [wake_up](Asleep::wake_up) or [deinit](Asleep::deinit) it
+    pub epd: Asleep<EPD>,
+    /// The error of the driver, a [DriverError]
+    pub error: E,
+}
+
+impl<EPD> Awake<EPD> {
+    /// Wraps a driver whose display is awake, e.g. one just returned by [WaveshareDisplay::new]
+    pub fn new(epd: EPD) -> Self {
+        Awake { epd }
+    }
+
+    /// Returns the driver, giving up the tracking
+    pub fn into_inner(self) -> EPD {
+        self.epd
+    }
+
+    /// Sends the display into deep sleep, see [WaveshareDisplay::sleep]
+    #[allow(clippy::type_complexity)]
+    pub fn sleep<SPI, CS, BUSY, DC, RST, DELAY>(
+        mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Asleep<EPD>, PowerError<EPD, DriverError<SPI, CS, BUSY, DC, RST>>>
+    where
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+    {
+        let result = self.epd.sleep(spi, delay);
+        let epd = Asleep { epd: self.epd };
+        match result {
+            Ok(()) => Ok(epd),
+            Err(error) => Err(PowerError { epd, error }),
+        }
+    }
+}
+
+impl<EPD> Deref for Awake<EPD> {
+    type Target = EPD;
+
+    fn deref(&self) -> &EPD {
+        &self.epd
+    }
+}
+
+impl<EPD> DerefMut for Awake<EPD> {
+    fn deref_mut(&mut self) -> &mut EPD {
+        &mut self.epd
+    }
+}
+
+impl<EPD> Asleep<EPD> {
+    /// Resets the controller and initialises it again, see [WaveshareDisplay::wake_up]
+    ///
+    /// The RAM of most controllers is lost in deep sleep, send the frame again before
+    /// displaying it.
+    #[allow(clippy::type_complexity)]
+    pub fn wake_up<SPI, CS, BUSY, DC, RST, DELAY>(
+        mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<Awake<EPD>, PowerError<EPD, DriverError<SPI, CS, BUSY, DC, RST>>>
+    where
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>,
+    {
+        match self.epd.wake_up(spi, delay) {
+            Ok(()) => Ok(Awake { epd: self.epd }),
+            Err(error) => Err(PowerError { epd: self, error }),
+        }
+    }
+
+    /// Consumes the driver and returns the CS, BUSY, DC and RST pins, the display keeps sleeping
+    pub fn deinit<CS, BUSY, DC, RST>(self) -> (CS, BUSY, DC, RST)
+    where
+        EPD: Deinit<CS, BUSY, DC, RST>,
+    {
+        self.epd.deinit()
+    }
+}
+
+impl<EPD, SpiE, BusyE, CsE, DcE, RstE> From<PowerError<EPD, EpdError<SpiE, BusyE, CsE, DcE, RstE>>>
+    for EpdError<SpiE, BusyE, CsE, DcE, RstE>
+{
+    fn from(error: PowerError<EPD, EpdError<SpiE, BusyE, CsE, DcE, RstE>>) -> Self {
+        error.error
+    }
+}
+
+impl<EPD, E: fmt::Debug> fmt::Debug for PowerError<EPD, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PowerError")
+            .field("error", &self.error)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_len;
+    use crate::simulator::SimulatedPanel;
+
+    #[test]
+    fn sleep_and_wake_up() {
+        use crate::epd2in13_GDEW0213T5D::{Epd2in13_T5D, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let (epd, mut spi, mut delay) = panel.connect::<Epd2in13_T5D<_, _, _, _, _, _>>();
+        let epd = Awake::new(epd);
+
+        let epd = epd.sleep(&mut spi, &mut delay).unwrap();
+        assert!(panel.is_asleep());
+
+        let mut epd = epd.wake_up(&mut spi, &mut delay).unwrap();
+        assert!(!panel.is_asleep());
+        let mut buffer = std::vec![0xFF; buffer_len(WIDTH as usize, HEIGHT as usize)];
+        buffer[0] = 0x00;
+        epd.update_and_display_frame(&mut spi, &buffer, &mut delay)
+            .unwrap();
+        assert!(panel.image().is_black_window(0, 0, 8, 1));
+
+        let _pins = epd.sleep(&mut spi, &mut delay).unwrap().deinit();
+        assert!(panel.is_asleep());
+        assert_eq!(panel.refreshes(), 1);
+        assert_eq!(panel.violations(), []);
+    }
+}
@@ -0,0 +1,71 @@
+//! Command sequences shared by the blocking and the async drivers
+//!
+//! Drivers describe what they send as a list of [Step]s, which is then executed by the
+//! blocking [DisplayInterface](crate::interface::DisplayInterface) or its async counterpart.
+//! That way both flavors always send exactly the same bytes.
+
+use crate::traits::Command;
+
+/// One step of a command sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step<'a> {
+    /// Reset the device, keeping the reset pin low for the given ms
+    Reset(u8),
+    /// Send a command (DC low)
+    Command(u8),
+    /// Send data (DC high)
+    Data(&'a [u8]),
+    /// Send the same data byte the given number of times
+    DataRepeat(u8, u32),
+    /// Wait until the busy pin signals that the device is idle
+    WaitUntilIdle,
+    /// Wait until the busy pin signals that the device is busy
+    WaitUntilBusy,
+    /// Wait for the given ms
+    Delay(u8),
+}
+
+impl<'a> Step<'a> {
+    /// Shortcut for [Step::Command] with the address of `command`
+    pub(crate) fn cmd<T: Command>(command: T) -> Self {
+        Step::Command(command.address())
+    }
+}
+
+/// Command sequence with steps only some displays or modes send, see [push](Steps::push)
+///
+/// Holds up to `N` steps, pushing more is a bug of the driver and panics.
+pub(crate) struct Steps<'a, const N: usize> {
+    steps: [Step<'a>; N],
+    len: usize,
+}
+
+impl<'a, const N: usize> Steps<'a, N> {
+    pub(crate) fn new() -> Self {
+        Steps {
+            steps: [Step::Delay(0); N],
+            len: 0,
+        }
+    }
+
+    /// Appends a step
+    pub(crate) fn push(&mut self, step: Step<'a>) {
+        self.steps[self.len] = step;
+        self.len += 1;
+    }
+
+    /// Appends all `steps`
+    pub(crate) fn extend(&mut self, steps: &[Step<'a>]) {
+        for step in steps {
+            self.push(*step);
+        }
+    }
+}
+
+impl<'a, const N: usize> core::ops::Deref for Steps<'a, N> {
+    type Target = [Step<'a>];
+
+    fn deref(&self) -> &[Step<'a>] {
+        &self.steps[..self.len]
+    }
+}
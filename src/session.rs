@@ -0,0 +1,406 @@
+//! Quick refreshes which keep track of the displayed frame
+//!
+//! The [QuickRefresh] methods need the frame on the screen as old frame for every refresh,
+//! which is easy to get wrong. [QuickRefreshSession] keeps a copy of the displayed frame,
+//! compares every new frame with it and sends old and new data of the changed window.
+//!
+//! Both buffers are provided by the caller since there is no allocator:
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
+//!use epd_waveshare::{buffer_len, epd4in2::*, prelude::*, session::QuickRefreshSession};
+//!#
+//!# let expectations = [];
+//!# let mut spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let mut delay = delay::MockNoop::new();
+//!
+//!const FRAME_BYTES: usize = buffer_len(WIDTH as usize, HEIGHT as usize);
+//!let (mut displayed, mut scratch) = ([0xFF; FRAME_BYTES], [0; FRAME_BYTES]);
+//!let mut frame = [0xFF; FRAME_BYTES];
+//!
+//!let epd = Epd4in2::new(&mut spi, cs_pin, busy_in, dc, rst, &mut delay)?;
+//!let mut session = QuickRefreshSession::new(epd, &mut displayed, &mut scratch);
+//!session.full_refresh(&mut spi, &frame, &mut delay)?;
+//!
+//!// Only the first 8 × 2 pixels change
+//!frame[0] = 0x00;
+//!frame[WIDTH as usize / 8] = 0x00;
+//!session.refresh(&mut spi, &frame, &mut delay)?;
+//!# Ok(())
+//!# }
+//!```
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::buffer_len;
+use crate::error::EpdError;
+#[cfg(doc)]
+use crate::traits::LutSelection;
+use crate::traits::{DriverError, FullRefresh, QuickRefresh, WaveshareDisplay};
+
+/// Window in pixels, `x` and `width` are multiples of 8 besides at the right edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Window {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Window {
+    fn full(width: u32, height: u32) -> Self {
+        Window {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }
+    }
+
+    /// Bounding box of both windows
+    fn union(self, other: Window) -> Self {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Window {
+            x,
+            y,
+            width: (self.x + self.width).max(other.x + other.width) - x,
+            height: (self.y + self.height).max(other.y + other.height) - y,
+        }
+    }
+}
+
+/// Part of the controller's old frame which doesn't match the displayed frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stale {
+    Nothing,
+    Window(Window),
+    Frame,
+}
+
+/// Quick refreshes of whole frames, sending only the changed window
+///
+/// The session owns the driver and a copy of the displayed frame. The driver needs to be set
+/// to [RefreshLut::Quick](crate::traits::RefreshLut::Quick) with
+/// [set_lut](LutSelection::set_lut) (or the driver specific method) before or
+/// [full_refresh](QuickRefreshSession::full_refresh) needs to be used first.
+///
+/// The old data of the window sent with a refresh is still in the controller afterwards, the
+/// next refresh sends the window again along with the changed one. After
+/// [new](QuickRefreshSession::new) and after using the driver directly
+/// ([reset](QuickRefreshSession::reset)) the first refresh sends the whole frames.
+pub struct QuickRefreshSession<'a, EPD> {
+    epd: EPD,
+    /// Frame on the screen
+    displayed: &'a mut [u8],
+    /// Window data sent to the controller
+    scratch: &'a mut [u8],
+    stale: Stale,
+}
+
+impl<'a, EPD> QuickRefreshSession<'a, EPD> {
+    /// Starts a session with `displayed` as frame on the screen
+    ///
+    /// `displayed` and `scratch` need to be as long as a frame of the display.
+    pub fn new(epd: EPD, displayed: &'a mut [u8], scratch: &'a mut [u8]) -> Self {
+        QuickRefreshSession {
+            epd,
+            displayed,
+            scratch,
+            stale: Stale::Frame,
+        }
+    }
+
+    /// The frame on the screen
+    pub fn displayed(&self) -> &[u8] {
+        self.displayed
+    }
+
+    /// The driver
+    pub fn epd(&self) -> &EPD {
+        &self.epd
+    }
+
+    /// The driver, the session needs to be [reset](QuickRefreshSession::reset) after refreshing
+    /// or writing frames with it
+    pub fn epd_mut(&mut self) -> &mut EPD {
+        &mut self.epd
+    }
+
+    /// Ends the session and returns the driver
+    pub fn into_inner(self) -> EPD {
+        self.epd
+    }
+
+    /// Sets the frame on the screen, the next refresh sends the whole frames
+    ///
+    /// Returns [EpdError::BufferSize] if `frame` is not as long as a frame.
+    pub fn reset<SpiE, BusyE, CsE, DcE, RstE>(
+        &mut self,
+        frame: &[u8],
+    ) -> Result<(), EpdError<SpiE, BusyE, CsE, DcE, RstE>> {
+        EpdError::check_buffer_size(self.displayed.len(), frame.len())?;
+        self.displayed.copy_from_slice(frame);
+        self.stale = Stale::Frame;
+        Ok(())
+    }
+
+    /// Refreshes `frame` with [FullRefresh::full_refresh_frame]
+    ///
+    /// `frame` is written as old frame as well, the next refresh only sends the changes.
+    pub fn full_refresh<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        frame: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        EPD: FullRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+    {
+        self.check_buffers(frame, self.epd.width(), self.epd.height())?;
+        self.epd.full_refresh_frame(spi, frame, delay)?;
+        self.displayed.copy_from_slice(frame);
+        self.stale = Stale::Nothing;
+        Ok(())
+    }
+
+    /// Refreshes the changed window of `frame` quickly
+    ///
+    /// Returns `false` without refreshing if `frame` is the displayed frame.
+    pub fn refresh<SPI, CS, BUSY, DC, RST, DELAY>(
+        &mut self,
+        spi: &mut SPI,
+        frame: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        SPI: Write<u8>,
+        CS: OutputPin,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayMs<u8>,
+        EPD: WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+            + QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>,
+    {
+        let (width, height) = (self.epd.width(), self.epd.height());
+        self.check_buffers(frame, width, height)?;
+
+        let window = match (self.stale, changed_window(self.displayed, frame, width)) {
+            (_, None) => return Ok(false),
+            (Stale::Nothing, Some(changed)) => changed,
+            (Stale::Frame, Some(_)) => Window::full(width, height),
+            (Stale::Window(stale), Some(changed)) => stale.union(changed),
+        };
+
+        if window == Window::full(width, height) {
+            self.epd.update_old_frame(spi, self.displayed, delay)?;
+            self.epd.update_new_frame(spi, frame, delay)?;
+        } else {
+            let Window {
+                x,
+                y,
+                width: w,
+                height: h,
+            } = window;
+            let len = copy_window(self.displayed, width, window, self.scratch);
+            self.epd
+                .update_partial_old_frame(spi, &self.scratch[..len], x, y, w, h, delay)?;
+            let len = copy_window(frame, width, window, self.scratch);
+            self.epd
+                .update_partial_new_frame(spi, &self.scratch[..len], x, y, w, h, delay)?;
+        }
+        self.epd.display_new_frame(spi, delay)?;
+
+        self.displayed.copy_from_slice(frame);
+        self.stale = Stale::Window(window);
+        Ok(true)
+    }
+
+    fn check_buffers<SpiE, BusyE, CsE, DcE, RstE>(
+        &self,
+        frame: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<(), EpdError<SpiE, BusyE, CsE, DcE, RstE>> {
+        let len = buffer_len(width as usize, height as usize);
+        EpdError::check_buffer_size(len, frame.len())?;
+        EpdError::check_buffer_size(len, self.displayed.len())?;
+        EpdError::check_buffer_size(len, self.scratch.len())
+    }
+}
+
+/// Byte aligned bounding box of the differences between the frames `old` and `new` of a
+/// `width` pixels wide display
+fn changed_window(old: &[u8], new: &[u8], width: u32) -> Option<Window> {
+    let row_bytes = width.div_ceil(8) as usize;
+    let mut bounds: Option<(usize, usize, usize, usize)> = None;
+    for (y, (old, new)) in old
+        .chunks_exact(row_bytes)
+        .zip(new.chunks_exact(row_bytes))
+        .enumerate()
+    {
+        let first = old.iter().zip(new).position(|(o, n)| o != n);
+        let last = old.iter().zip(new).rposition(|(o, n)| o != n);
+        if let (Some(first), Some(last)) = (first, last) {
+            bounds = Some(match bounds {
+                Some((x0, y0, x1, _)) => (x0.min(first), y0, x1.max(last), y),
+                None => (first, y, last, y),
+            });
+        }
+    }
+    bounds.map(|(x0, y0, x1, y1)| {
+        let x = x0 as u32 * 8;
+        Window {
+            x,
+            y: y0 as u32,
+            width: ((x1 as u32 + 1) * 8).min(width) - x,
+            height: (y1 - y0) as u32 + 1,
+        }
+    })
+}
+
+/// Copies `window` of `frame` to the start of `target`, returns the number of bytes
+fn copy_window(frame: &[u8], width: u32, window: Window, target: &mut [u8]) -> usize {
+    let row_bytes = width.div_ceil(8) as usize;
+    let x = window.x as usize / 8;
+    let window_bytes = window.width.div_ceil(8) as usize;
+    let rows = frame
+        .chunks_exact(row_bytes)
+        .skip(window.y as usize)
+        .take(window.height as usize);
+    for (row, target) in rows.zip(target.chunks_exact_mut(window_bytes)) {
+        target.copy_from_slice(&row[x..x + window_bytes]);
+    }
+    window_bytes * window.height as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_len;
+    use crate::color::TriColor;
+    use crate::simulator::{Delay, Pin, Plane, SimulatedPanel, Spi};
+
+    #[test]
+    fn window_of_changes() {
+        // 20 x 3 pixels, 3 bytes per row
+        let old = [0xFF; 9];
+        assert_eq!(changed_window(&old, &old, 20), None);
+
+        let mut new = old;
+        new[4] = 0x00;
+        assert_eq!(
+            changed_window(&old, &new, 20),
+            Some(Window {
+                x: 8,
+                y: 1,
+                width: 8,
+                height: 1
+            })
+        );
+        new[8] = 0x0F;
+        let window = changed_window(&old, &new, 20).unwrap();
+        assert_eq!(
+            window,
+            Window {
+                x: 8,
+                y: 1,
+                width: 12,
+                height: 2
+            }
+        );
+
+        let mut target = [0; 9];
+        assert_eq!(copy_window(&new, 20, window, &mut target), 4);
+        assert_eq!(target[..4], [0x00, 0xFF, 0xFF, 0x0F]);
+
+        assert_eq!(
+            window.union(Window {
+                x: 0,
+                y: 0,
+                width: 8,
+                height: 1
+            }),
+            Window::full(20, 3)
+        );
+    }
+
+    /// Moves a black block with a session on a simulated panel
+    fn move_block<EPD>(panel: &SimulatedPanel)
+    where
+        EPD: FullRefresh<Spi, Pin, Pin, Pin, Pin, Delay>,
+    {
+        let (epd, mut spi, mut delay) = panel.connect::<EPD>();
+        let (width, height) = (epd.width(), epd.height());
+        let len = buffer_len(width as usize, height as usize);
+        let (mut displayed, mut scratch) = (std::vec![0; len], std::vec![0; len]);
+        let mut session = QuickRefreshSession::new(epd, &mut displayed, &mut scratch);
+
+        let white = std::vec![0xFF; len];
+        session.full_refresh(&mut spi, &white, &mut delay).unwrap();
+        assert!(panel.image().pixels().iter().all(|&p| p == TriColor::White));
+
+        let frame_with_block = |x: u32, y: u32| {
+            let mut frame = white.clone();
+            for row in y..y + 4 {
+                let start = row as usize * width.div_ceil(8) as usize + x as usize / 8;
+                frame[start..start + 2].fill(0x00);
+            }
+            frame
+        };
+
+        // Only the window of the block is sent
+        let sent = panel.bytes_sent();
+        let frame = frame_with_block(16, 20);
+        assert!(session.refresh(&mut spi, &frame, &mut delay).unwrap());
+        assert!(panel.bytes_sent() - sent < len as u64);
+        assert!(panel.image().is_black_window(16, 20, 16, 4));
+        assert_eq!(session.displayed(), frame);
+        // The old data of the window was white
+        assert!(panel.ram(Plane::Old).iter().all(|&byte| byte == 0xFF));
+
+        // The old block is cleared
+        let frame = frame_with_block(40, 60);
+        assert!(session.refresh(&mut spi, &frame, &mut delay).unwrap());
+        assert!(panel.image().is_black_window(40, 60, 16, 4));
+
+        let refreshes = panel.refreshes();
+        assert!(!session.refresh(&mut spi, &frame, &mut delay).unwrap());
+        assert_eq!(panel.refreshes(), refreshes);
+        assert_eq!(panel.violations(), []);
+    }
+
+    #[test]
+    fn move_block_epd4in2() {
+        use crate::epd4in2::{Epd4in2, HEIGHT, WIDTH};
+        move_block::<Epd4in2<_, _, _, _, _, _>>(&SimulatedPanel::il0398(WIDTH, HEIGHT));
+    }
+
+    #[test]
+    fn move_block_epd2in9_v2() {
+        use crate::epd2in9_v2::{Epd2in9, HEIGHT, WIDTH};
+        move_block::<Epd2in9<_, _, _, _, _, _>>(&SimulatedPanel::ssd1680(WIDTH, HEIGHT));
+    }
+
+    #[test]
+    fn move_block_t5d() {
+        use crate::epd2in13_GDEW0213T5D::{Epd2in13_T5D, HEIGHT, WIDTH};
+        move_block::<Epd2in13_T5D<_, _, _, _, _, _>>(&SimulatedPanel::uc8151(WIDTH, HEIGHT));
+    }
+}
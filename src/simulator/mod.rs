@@ -0,0 +1,744 @@
+//! Simulated panels to test the drivers without hardware
+//!
+//! A [SimulatedPanel] hands out an SPI bus, pins and a delay which implement the
+//! `embedded_hal` traits the drivers need. Everything sent over them is decoded like the
+//! display controller would do it: the controller RAM is written according to the RAM windows,
+//! address counters and data entry mode, the busy pin stays asserted for a while after power
+//! and refresh commands (time only passes through the simulated [Delay]) and
+//! a refresh renders the RAM into an [Image].
+//!
+//! Supported controllers:
+//!
+//! - UC8151 ([epd2in13_GDEW0213T5D](crate::epd2in13_GDEW0213T5D) and the other [uc8151](crate::uc8151)
+//!   presets)
+//! - IL0398 ([epd4in2](crate::epd4in2))
+//! - SSD1680 ([epd2in13_GDEQ0213B74](crate::epd2in13_GDEQ0213B74), [epd2in13_v2](crate::epd2in13_v2),
+//!   [epd2in9_v2](crate::epd2in9_v2) and other [ssd1680](crate::ssd1680) presets)
+//!
+//! LUTs, voltages and temperature settings are accepted but don't influence the rendered image.
+//!
+//! Only available with the `simulator` feature.
+//!
+//! # Example
+//!
+//!```rust
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<core::convert::Infallible, core::convert::Infallible>> {
+//!use epd_waveshare::{color::TriColor, epd4in2::*, prelude::*, simulator::SimulatedPanel};
+//!
+//!let panel = SimulatedPanel::il0398(WIDTH, HEIGHT);
+//!let (mut spi, mut delay) = (panel.spi(), panel.delay());
+//!let mut epd = Epd4in2::new(&mut spi, panel.cs(), panel.busy(), panel.dc(), panel.rst(), &mut delay)?;
+//!
+//!let mut buffer = [0xFF; WIDTH as usize / 8 * HEIGHT as usize];
+//!buffer[0] = 0x7F;
+//!epd.update_and_display_frame(&mut spi, &buffer, &mut delay)?;
+//!epd.sleep(&mut spi, &mut delay)?;
+//!
+//!let image = panel.image();
+//!assert_eq!(image.pixel(0, 0), TriColor::Black);
+//!assert_eq!(image.pixel(1, 0), TriColor::White);
+//!assert!(panel.violations().is_empty());
+//!# Ok(())
+//!# }
+//!```
+
+pub(crate) mod ssd1680;
+mod uc8151;
+
+use crate::color::TriColor;
+use core::cell::RefCell;
+use core::convert::Infallible;
+use embedded_hal::{
+    blocking::{
+        delay::{DelayMs, DelayUs},
+        spi::Write,
+    },
+    digital::v2::{InputPin, OutputPin},
+};
+use std::boxed::Box;
+use std::rc::Rc;
+use std::vec::Vec;
+
+/// Temperature measured by the sensor of the simulated controllers in °C
+const SENSOR_CELSIUS: i8 = 25;
+
+/// Something the simulated controller didn't expect from the driver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// A byte was sent while CS was high
+    ByteWithoutCs(u8),
+    /// A command was sent while the busy pin was asserted, it was ignored
+    CommandWhileBusy(u8),
+    /// A data byte was sent while the busy pin was asserted, it was ignored
+    DataWhileBusy(u8),
+    /// A command was sent in deep sleep, it was ignored
+    CommandWhileAsleep(u8),
+    /// A data byte was sent before any command
+    DataWithoutCommand(u8),
+    /// A RAM write ran outside of the RAM
+    RamOverflow,
+    /// More data was sent than the window of the data transmission holds, the address counter
+    /// wrapped around and overwrote its start, e.g. parameters sent without their command
+    WindowOverrun,
+    /// A refresh was started while the booster was powered off, nothing was rendered
+    RefreshWhilePoweredOff,
+}
+
+/// The two RAM planes of a controller
+///
+/// | Controller      | `Old`                          | `New`                          |
+/// | :---            | :---                           | :---                           |
+/// | UC8151, IL0398  | DTM1 (black/white in KWR mode) | DTM2 (red in KWR mode)         |
+/// | SSD1680         | RED RAM (0x26)                 | BW RAM (0x24)                  |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Plane {
+    /// Old data or the first color plane
+    Old,
+    /// New data or the second color plane
+    New,
+}
+
+/// Image on the simulated panel after the last refresh
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<TriColor>,
+}
+
+impl Image {
+    fn new(width: u32, height: u32) -> Self {
+        Image {
+            width,
+            height,
+            pixels: std::vec![TriColor::White; (width * height) as usize],
+        }
+    }
+
+    /// Width in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Color of the pixel at `x`, `y`
+    pub fn pixel(&self, x: u32, y: u32) -> TriColor {
+        assert!(x < self.width && y < self.height);
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// All pixels, row by row
+    pub fn pixels(&self) -> &[TriColor] {
+        &self.pixels
+    }
+
+    fn set_pixel(&mut self, x: u32, y: u32, color: TriColor) {
+        if x < self.width && y < self.height {
+            self.pixels[(y * self.width + x) as usize] = color;
+        }
+    }
+}
+
+#[cfg(test)]
+impl Image {
+    /// Whether the pixels of the window are black and all others white
+    pub(crate) fn is_black_window(&self, x: u32, y: u32, width: u32, height: u32) -> bool {
+        (0..self.height).all(|py| {
+            (0..self.width).all(|px| {
+                let inside = (x..x + width).contains(&px) && (y..y + height).contains(&py);
+                let expected = if inside {
+                    TriColor::Black
+                } else {
+                    TriColor::White
+                };
+                self.pixel(px, py) == expected
+            })
+        })
+    }
+}
+
+/// Controller RAM with one bit per pixel and two planes, stored row by row
+#[derive(Debug, Clone)]
+struct Ram {
+    bytes_per_row: u32,
+    rows: u32,
+    planes: [Vec<u8>; 2],
+}
+
+impl Ram {
+    fn new(width: u32, height: u32) -> Self {
+        let bytes_per_row = width.div_ceil(8);
+        let len = (bytes_per_row * height) as usize;
+        Ram {
+            bytes_per_row,
+            rows: height,
+            planes: [std::vec![0xFF; len], std::vec![0xFF; len]],
+        }
+    }
+
+    fn plane(&self, plane: Plane) -> &[u8] {
+        match plane {
+            Plane::Old => &self.planes[0],
+            Plane::New => &self.planes[1],
+        }
+    }
+
+    /// Writes `byte` at column `x_byte` of `row`, returns false if that is outside of the RAM
+    fn write(&mut self, plane: Plane, x_byte: u32, row: u32, byte: u8) -> bool {
+        if x_byte >= self.bytes_per_row || row >= self.rows {
+            return false;
+        }
+        let index = (row * self.bytes_per_row + x_byte) as usize;
+        match plane {
+            Plane::Old => self.planes[0][index] = byte,
+            Plane::New => self.planes[1][index] = byte,
+        }
+        true
+    }
+
+    /// Bits of both planes for pixel `x`, `y` as `(old, new)`
+    fn bits(&self, x: u32, y: u32) -> (bool, bool) {
+        let index = (y * self.bytes_per_row + x / 8) as usize;
+        let mask = 0x80 >> (x % 8);
+        (
+            self.planes[0][index] & mask != 0,
+            self.planes[1][index] & mask != 0,
+        )
+    }
+}
+
+/// Command set of a display controller
+trait Controller {
+    /// Hardware reset, also wakes the controller from deep sleep
+    fn reset(&mut self);
+
+    /// Starts a new command, returns how long the controller is busy afterwards in ms
+    fn command(&mut self, command: u8, violations: &mut Vec<Violation>) -> u32;
+
+    /// Handles a data byte of the current command, returns how long the controller is busy afterwards in ms
+    fn data(&mut self, command: u8, index: usize, byte: u8, violations: &mut Vec<Violation>)
+        -> u32;
+
+    fn is_asleep(&self) -> bool;
+
+    fn ram(&self) -> &Ram;
+
+    fn image(&self) -> &Image;
+
+    fn refreshes(&self) -> u32;
+
+    /// Content of the waveform register written with `command`, `None` while refreshes use
+    /// the waveforms of the OTP memory
+    fn lut(&self, _command: u8) -> Option<&[u8]> {
+        None
+    }
+
+    /// Temperature the waveforms are selected with, `None` while it is unknown
+    fn temperature(&self) -> Option<i8> {
+        None
+    }
+}
+
+struct State {
+    controller: Box<dyn Controller>,
+    busy_is_low: bool,
+    cs_low: bool,
+    dc_high: bool,
+    rst_low: bool,
+    command: Option<u8>,
+    data_index: usize,
+    now_us: u64,
+    busy_until_us: u64,
+    bytes: u64,
+    violations: Vec<Violation>,
+}
+
+impl State {
+    fn is_busy(&self) -> bool {
+        self.now_us < self.busy_until_us
+    }
+
+    fn set_busy(&mut self, ms: u32) {
+        if ms > 0 {
+            self.busy_until_us = self.now_us + u64::from(ms) * 1000;
+        }
+    }
+
+    fn byte(&mut self, byte: u8) {
+        self.bytes += 1;
+        if !self.cs_low {
+            self.violations.push(Violation::ByteWithoutCs(byte));
+            return;
+        }
+        if !self.dc_high {
+            if self.controller.is_asleep() {
+                self.violations.push(Violation::CommandWhileAsleep(byte));
+                return;
+            }
+            if self.is_busy() {
+                self.violations.push(Violation::CommandWhileBusy(byte));
+                return;
+            }
+            self.command = Some(byte);
+            self.data_index = 0;
+            let busy = self.controller.command(byte, &mut self.violations);
+            self.set_busy(busy);
+        } else {
+            if self.controller.is_asleep() {
+                return;
+            }
+            if self.is_busy() {
+                self.violations.push(Violation::DataWhileBusy(byte));
+                return;
+            }
+            match self.command {
+                Some(command) => {
+                    let index = self.data_index;
+                    self.data_index += 1;
+                    let busy = self
+                        .controller
+                        .data(command, index, byte, &mut self.violations);
+                    self.set_busy(busy);
+                }
+                None => self.violations.push(Violation::DataWithoutCommand(byte)),
+            }
+        }
+    }
+}
+
+/// A simulated display, see the [module documentation](self)
+///
+/// All handles share the state of the panel, so it can be inspected while the driver owns the pins.
+#[derive(Clone)]
+pub struct SimulatedPanel {
+    state: Rc<RefCell<State>>,
+}
+
+impl SimulatedPanel {
+    fn with_controller(controller: Box<dyn Controller>, busy_is_low: bool) -> Self {
+        SimulatedPanel {
+            state: Rc::new(RefCell::new(State {
+                controller,
+                busy_is_low,
+                cs_low: false,
+                dc_high: false,
+                rst_low: false,
+                command: None,
+                data_index: 0,
+                now_us: 0,
+                busy_until_us: 0,
+                bytes: 0,
+                violations: Vec::new(),
+            })),
+        }
+    }
+
+    /// Panel with an UC8151 controller, e.g. the GDEW0213T5D
+    pub fn uc8151(width: u32, height: u32) -> Self {
+        Self::with_controller(Box::new(uc8151::Uc8151::new(width, height)), true)
+    }
+
+    /// Panel with an IL0398 controller, e.g. the 4.2" display
+    ///
+    /// The IL0398 shares the command set of the UC8151 but takes 16 bit coordinates.
+    pub fn il0398(width: u32, height: u32) -> Self {
+        Self::with_controller(Box::new(uc8151::Uc8151::new(width, height)), true)
+    }
+
+    /// Black/white panel with an SSD1680 controller, e.g. the GDEQ0213B74 or the 2.13" V2
+    pub fn ssd1680(width: u32, height: u32) -> Self {
+        Self::with_controller(Box::new(ssd1680::Ssd1680::new(width, height, false)), false)
+    }
+
+    /// Black/white/red panel with an SSD1680 controller, set bits in the RED RAM render as red
+    pub fn ssd1680_red(width: u32, height: u32) -> Self {
+        Self::with_controller(Box::new(ssd1680::Ssd1680::new(width, height, true)), false)
+    }
+
+    /// SPI bus to the panel
+    pub fn spi(&self) -> Spi {
+        Spi {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Chip select pin
+    pub fn cs(&self) -> Pin {
+        self.pin(PinRole::Cs)
+    }
+
+    /// Busy pin
+    pub fn busy(&self) -> Pin {
+        self.pin(PinRole::Busy)
+    }
+
+    /// Data/command pin
+    pub fn dc(&self) -> Pin {
+        self.pin(PinRole::Dc)
+    }
+
+    /// Reset pin
+    pub fn rst(&self) -> Pin {
+        self.pin(PinRole::Rst)
+    }
+
+    /// Delay which advances the simulated time
+    pub fn delay(&self) -> Delay {
+        Delay {
+            state: self.state.clone(),
+        }
+    }
+
+    fn pin(&self, role: PinRole) -> Pin {
+        Pin {
+            state: self.state.clone(),
+            role,
+        }
+    }
+
+    /// Image rendered by the last refresh, white before the first one
+    pub fn image(&self) -> Image {
+        self.state.borrow().controller.image().clone()
+    }
+
+    /// Content of a RAM plane, row by row with `width / 8` (rounded up) bytes per row
+    pub fn ram(&self, plane: Plane) -> Vec<u8> {
+        self.state.borrow().controller.ram().plane(plane).to_vec()
+    }
+
+    /// Number of refreshes so far
+    pub fn refreshes(&self) -> u32 {
+        self.state.borrow().controller.refreshes()
+    }
+
+    /// Content of the waveform (LUT) register written with `command`
+    ///
+    /// `None` while the refreshes use the waveforms of the OTP memory or if the simulated
+    /// controller doesn't keep track of its waveforms.
+    pub fn lut(&self, command: u8) -> Option<Vec<u8>> {
+        self.state
+            .borrow()
+            .controller
+            .lut(command)
+            .map(|lut| lut.to_vec())
+    }
+
+    /// Temperature in °C the controller selects its waveforms with
+    ///
+    /// Either the one set by the driver or the 25 °C of the simulated sensor. `None` while the
+    /// SSD1680 hasn't loaded or got a temperature yet.
+    pub fn temperature(&self) -> Option<i8> {
+        self.state.borrow().controller.temperature()
+    }
+
+    /// Whether the controller is in deep sleep
+    pub fn is_asleep(&self) -> bool {
+        self.state.borrow().controller.is_asleep()
+    }
+
+    /// Whether the controller is busy right now
+    pub fn is_busy(&self) -> bool {
+        self.state.borrow().is_busy()
+    }
+
+    /// Simulated time passed through the [Delay] in ms
+    pub fn elapsed_ms(&self) -> u64 {
+        self.state.borrow().now_us / 1000
+    }
+
+    /// Number of bytes sent over the SPI bus so far
+    pub fn bytes_sent(&self) -> u64 {
+        self.state.borrow().bytes
+    }
+
+    /// Everything the controller didn't expect so far
+    pub fn violations(&self) -> Vec<Violation> {
+        self.state.borrow().violations.clone()
+    }
+}
+
+#[cfg(test)]
+impl SimulatedPanel {
+    /// Creates a driver on the panel, returns it with the SPI bus and delay of the panel
+    pub(crate) fn connect<EPD>(&self) -> (EPD, Spi, Delay)
+    where
+        EPD: crate::traits::WaveshareDisplay<Spi, Pin, Pin, Pin, Pin, Delay>,
+    {
+        let (mut spi, mut delay) = (self.spi(), self.delay());
+        let epd = EPD::new(
+            &mut spi,
+            self.cs(),
+            self.busy(),
+            self.dc(),
+            self.rst(),
+            &mut delay,
+        )
+        .unwrap();
+        (epd, spi, delay)
+    }
+
+    /// Like [connect](SimulatedPanel::connect), with everything sent recorded by `recorder`
+    pub(crate) fn connect_traced<EPD>(
+        &self,
+        recorder: &crate::trace::Recorder,
+    ) -> (
+        EPD,
+        crate::trace::TracedSpi<Spi>,
+        crate::trace::TracedDelay<Delay>,
+    )
+    where
+        EPD: crate::traits::WaveshareDisplay<
+            crate::trace::TracedSpi<Spi>,
+            crate::trace::TracedPin<Pin>,
+            crate::trace::TracedPin<Pin>,
+            crate::trace::TracedPin<Pin>,
+            crate::trace::TracedPin<Pin>,
+            crate::trace::TracedDelay<Delay>,
+        >,
+    {
+        let (mut spi, mut delay) = (recorder.spi(self.spi()), recorder.delay(self.delay()));
+        let epd = EPD::new(
+            &mut spi,
+            recorder.cs(self.cs()),
+            recorder.busy(self.busy()),
+            recorder.dc(self.dc()),
+            recorder.rst(self.rst()),
+            &mut delay,
+        )
+        .unwrap();
+        (epd, spi, delay)
+    }
+}
+
+/// SPI bus of a [SimulatedPanel]
+pub struct Spi {
+    state: Rc<RefCell<State>>,
+}
+
+impl Write<u8> for Spi {
+    type Error = Infallible;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        for &byte in words {
+            state.byte(byte);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PinRole {
+    Cs,
+    Busy,
+    Dc,
+    Rst,
+}
+
+/// Pin of a [SimulatedPanel]
+pub struct Pin {
+    state: Rc<RefCell<State>>,
+    role: PinRole,
+}
+
+impl OutputPin for Pin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        match self.role {
+            PinRole::Cs => state.cs_low = true,
+            PinRole::Dc => state.dc_high = false,
+            PinRole::Rst => state.rst_low = true,
+            PinRole::Busy => {}
+        }
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        match self.role {
+            PinRole::Cs => state.cs_low = false,
+            PinRole::Dc => state.dc_high = true,
+            PinRole::Rst => {
+                if state.rst_low {
+                    state.controller.reset();
+                    state.command = None;
+                    state.busy_until_us = state.now_us;
+                }
+                state.rst_low = false;
+            }
+            PinRole::Busy => {}
+        }
+        Ok(())
+    }
+}
+
+impl InputPin for Pin {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        self.is_low().map(|low| !low)
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        let state = self.state.borrow();
+        Ok(match self.role {
+            PinRole::Busy => state.is_busy() == state.busy_is_low,
+            PinRole::Cs => state.cs_low,
+            PinRole::Dc => !state.dc_high,
+            PinRole::Rst => state.rst_low,
+        })
+    }
+}
+
+/// Delay of a [SimulatedPanel], only advances the simulated time
+pub struct Delay {
+    state: Rc<RefCell<State>>,
+}
+
+impl DelayUs<u32> for Delay {
+    fn delay_us(&mut self, us: u32) {
+        self.state.borrow_mut().now_us += u64::from(us);
+    }
+}
+
+impl DelayMs<u8> for Delay {
+    fn delay_ms(&mut self, ms: u8) {
+        self.delay_us(u32::from(ms) * 1000);
+    }
+}
+
+impl DelayMs<u16> for Delay {
+    fn delay_ms(&mut self, ms: u16) {
+        self.delay_us(u32::from(ms) * 1000);
+    }
+}
+
+impl DelayMs<u32> for Delay {
+    fn delay_ms(&mut self, ms: u32) {
+        self.state.borrow_mut().now_us += u64::from(ms) * 1000;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_len;
+    use crate::prelude::*;
+
+    #[test]
+    fn uc8151_full_and_partial_frame() {
+        use crate::epd2in13_GDEW0213T5D::{Epd2in13_T5D, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13_T5D<_, _, _, _, _, _>>();
+
+        let mut buffer = std::vec![0xFF; buffer_len(WIDTH as usize, HEIGHT as usize)];
+        buffer[0] = 0x0F;
+        epd.update_and_display_frame(&mut spi, &buffer, &mut delay)
+            .unwrap();
+        assert!(panel.is_busy());
+        assert!(panel.image().is_black_window(0, 0, 4, 1));
+
+        epd.update_frame(&mut spi, &[0xFF; 104 / 8 * 212], &mut delay)
+            .unwrap();
+        epd.update_partial_frame(&mut spi, &[0x00; 2 * 4], 8, 10, 16, 4, &mut delay)
+            .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+        assert!(panel.image().is_black_window(8, 10, 16, 4));
+
+        epd.sleep(&mut spi, &mut delay).unwrap();
+        assert!(panel.is_asleep());
+        assert_eq!(panel.refreshes(), 2);
+        assert_eq!(panel.violations(), []);
+    }
+
+    #[test]
+    fn il0398_partial_refresh() {
+        use crate::epd4in2::{Epd4in2, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::il0398(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd4in2<_, _, _, _, _, _>>();
+
+        epd.clear_frame(&mut spi, &mut delay).unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+        epd.update_partial_frame(&mut spi, &[0x00; 3 * 5], 16, 40, 24, 5, &mut delay)
+            .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+
+        assert!(panel.image().is_black_window(16, 40, 24, 5));
+        assert_eq!(panel.violations(), []);
+    }
+
+    #[test]
+    fn ssd1680_full_and_partial_frame() {
+        use crate::epd2in13_GDEQ0213B74::{Epd2in13, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13<_, _, _, _, _, _>>();
+
+        let mut buffer = std::vec![0xFF; buffer_len(WIDTH as usize, HEIGHT as usize)];
+        buffer[16] = 0x00;
+        epd.update_and_display_frame(&mut spi, &buffer, &mut delay)
+            .unwrap();
+        assert!(panel.image().is_black_window(0, 1, 8, 1));
+        assert_eq!(panel.ram(Plane::Old), buffer);
+
+        epd.clear_frame(&mut spi, &mut delay).unwrap();
+        epd.update_partial_frame(&mut spi, &[0x00; 2 * 3], 32, 100, 16, 3, &mut delay)
+            .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+        assert!(panel.image().is_black_window(32, 100, 16, 3));
+        assert_eq!(panel.violations(), []);
+    }
+
+    #[test]
+    fn ssd1680_display_waits_for_refresh() {
+        use crate::epd2in13_v2::{Epd2in13, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13<_, _, _, _, _, _>>();
+
+        let start = panel.elapsed_ms();
+        epd.update_and_display_frame(
+            &mut spi,
+            &[0x00; buffer_len(WIDTH as usize, HEIGHT as usize)],
+            &mut delay,
+        )
+        .unwrap();
+        assert!(panel.elapsed_ms() - start >= u64::from(ssd1680::FULL_REFRESH_MS));
+        assert!(panel.image().pixels().iter().all(|&p| p == TriColor::Black));
+
+        epd.sleep(&mut spi, &mut delay).unwrap();
+        assert_eq!(panel.violations(), []);
+    }
+
+    #[test]
+    fn reports_violations() {
+        let panel = SimulatedPanel::uc8151(8, 8);
+        let (mut spi, mut cs, mut dc) = (panel.spi(), panel.cs(), panel.dc());
+
+        spi.write(&[0x04]).unwrap();
+        cs.set_low().unwrap();
+        dc.set_high().unwrap();
+        spi.write(&[0xAA]).unwrap();
+        dc.set_low().unwrap();
+        spi.write(&[0x12, 0x04, 0x12]).unwrap();
+
+        assert_eq!(
+            panel.violations(),
+            [
+                Violation::ByteWithoutCs(0x04),
+                Violation::DataWithoutCommand(0xAA),
+                Violation::RefreshWhilePoweredOff,
+                Violation::CommandWhileBusy(0x12),
+            ]
+        );
+        assert_eq!(panel.refreshes(), 0);
+    }
+}
@@ -0,0 +1,286 @@
+//! Command set of the SSD1680
+
+use super::{Controller, Image, Plane, Ram, Violation, SENSOR_CELSIUS};
+use crate::color::TriColor;
+use std::vec::Vec;
+
+const DEEP_SLEEP_MODE: u8 = 0x10;
+const DATA_ENTRY_MODE_SETTING: u8 = 0x11;
+const SW_RESET: u8 = 0x12;
+const WRITE_TEMPERATURE_REGISTER: u8 = 0x1A;
+const MASTER_ACTIVATION: u8 = 0x20;
+const DISPLAY_UPDATE_CONTROL_1: u8 = 0x21;
+const DISPLAY_UPDATE_CONTROL_2: u8 = 0x22;
+const WRITE_RAM: u8 = 0x24;
+const WRITE_RAM_RED: u8 = 0x26;
+const WRITE_LUT_REGISTER: u8 = 0x32;
+const SET_RAM_X_ADDRESS_START_END_POSITION: u8 = 0x44;
+const SET_RAM_Y_ADDRESS_START_END_POSITION: u8 = 0x45;
+const SET_RAM_X_ADDRESS_COUNTER: u8 = 0x4E;
+const SET_RAM_Y_ADDRESS_COUNTER: u8 = 0x4F;
+
+/// Approximate busy times in ms
+const SW_RESET_MS: u32 = 10;
+const ACTIVATION_MS: u32 = 20;
+pub(crate) const FULL_REFRESH_MS: u32 = 2000;
+const PARTIAL_REFRESH_MS: u32 = 300;
+
+/// Display update control 2: display with the loaded waveform
+const DISPLAY: u8 = 0x04;
+/// Display update control 2: display mode 2 (partial refresh)
+const DISPLAY_MODE_2: u8 = 0x08;
+/// Display update control 2: load the waveform of the temperature from the OTP
+const LOAD_LUT: u8 = 0x10;
+/// Display update control 2: read the temperature sensor
+const LOAD_TEMPERATURE: u8 = 0x20;
+
+/// Registers which are restored by a hardware or software reset
+#[derive(Debug, Clone, Copy)]
+struct Registers {
+    data_entry_mode: u8,
+    x_start: u32,
+    x_end: u32,
+    y_start: u32,
+    y_end: u32,
+    x: u32,
+    y: u32,
+    update_control_1: u8,
+    update_control_2: u8,
+}
+
+impl Registers {
+    fn new(ram: &Ram) -> Self {
+        Registers {
+            data_entry_mode: 0x03,
+            x_start: 0,
+            x_end: ram.bytes_per_row - 1,
+            y_start: 0,
+            y_end: ram.rows - 1,
+            x: 0,
+            y: 0,
+            update_control_1: 0x00,
+            update_control_2: 0xFF,
+        }
+    }
+}
+
+pub(super) struct Ssd1680 {
+    ram: Ram,
+    image: Image,
+    refreshes: u32,
+    asleep: bool,
+    /// Render set bits of the RED RAM as red
+    red: bool,
+    registers: Registers,
+    /// RAM plane of a running RAM write
+    writing: Option<Plane>,
+    overflowed: bool,
+    /// LUT register, `None` after loading the waveform from the OTP
+    lut: Option<Vec<u8>>,
+    /// Temperature register
+    temperature: Option<i8>,
+}
+
+/// Moves `value` one step towards `end`, wraps around to `start` after reaching `end`
+///
+/// Returns whether it wrapped.
+fn step(value: &mut u32, increment: bool, start: u32, end: u32, size: u32) -> bool {
+    if *value == end {
+        *value = start;
+        true
+    } else {
+        *value = if increment {
+            (*value + 1) % size
+        } else {
+            (*value + size - 1) % size
+        };
+        false
+    }
+}
+
+/// Applies a RAM option of display update control 1 to a bit
+fn ram_option(option: u8, bit: bool) -> bool {
+    match option {
+        0x4 => false,
+        0x8 => !bit,
+        _ => bit,
+    }
+}
+
+impl Ssd1680 {
+    pub(super) fn new(width: u32, height: u32, red: bool) -> Self {
+        let ram = Ram::new(width, height);
+        let registers = Registers::new(&ram);
+        Ssd1680 {
+            ram,
+            image: Image::new(width, height),
+            refreshes: 0,
+            asleep: false,
+            red,
+            registers,
+            writing: None,
+            overflowed: false,
+            lut: None,
+            temperature: None,
+        }
+    }
+
+    fn write_ram(&mut self, plane: Plane, byte: u8, violations: &mut Vec<Violation>) {
+        let r = &mut self.registers;
+        if !self.ram.write(plane, r.x, r.y, byte) && !self.overflowed {
+            self.overflowed = true;
+            violations.push(Violation::RamOverflow);
+        }
+
+        let x_increment = r.data_entry_mode & 0x01 != 0;
+        let y_increment = r.data_entry_mode & 0x02 != 0;
+        let (columns, rows) = (
+            self.ram.bytes_per_row.max(r.x + 1),
+            self.ram.rows.max(r.y + 1),
+        );
+        if r.data_entry_mode & 0x04 == 0 {
+            // X direction first
+            if step(&mut r.x, x_increment, r.x_start, r.x_end, columns) {
+                step(&mut r.y, y_increment, r.y_start, r.y_end, rows);
+            }
+        } else if step(&mut r.y, y_increment, r.y_start, r.y_end, rows) {
+            step(&mut r.x, x_increment, r.x_start, r.x_end, columns);
+        }
+    }
+
+    fn refresh(&mut self) {
+        let bw_option = self.registers.update_control_1 & 0x0F;
+        let red_option = self.registers.update_control_1 >> 4;
+        for y in 0..self.image.height() {
+            for x in 0..self.image.width() {
+                let (red, bw) = self.ram.bits(x, y);
+                let color = if self.red && ram_option(red_option, red) {
+                    TriColor::Chromatic
+                } else if ram_option(bw_option, bw) {
+                    TriColor::White
+                } else {
+                    TriColor::Black
+                };
+                self.image.set_pixel(x, y, color);
+            }
+        }
+        self.refreshes += 1;
+    }
+}
+
+impl Controller for Ssd1680 {
+    fn reset(&mut self) {
+        self.asleep = false;
+        self.registers = Registers::new(&self.ram);
+        self.writing = None;
+        self.lut = None;
+        self.temperature = None;
+    }
+
+    fn command(&mut self, command: u8, _violations: &mut Vec<Violation>) -> u32 {
+        self.writing = None;
+        match command {
+            SW_RESET => {
+                self.registers = Registers::new(&self.ram);
+                SW_RESET_MS
+            }
+            MASTER_ACTIVATION => {
+                let control = self.registers.update_control_2;
+                if control & LOAD_TEMPERATURE != 0 {
+                    self.temperature = Some(SENSOR_CELSIUS);
+                }
+                if control & LOAD_LUT != 0 {
+                    self.lut = None;
+                }
+                if control & DISPLAY == 0 {
+                    ACTIVATION_MS
+                } else {
+                    self.refresh();
+                    if control & DISPLAY_MODE_2 != 0 {
+                        PARTIAL_REFRESH_MS
+                    } else {
+                        FULL_REFRESH_MS
+                    }
+                }
+            }
+            WRITE_RAM => {
+                self.writing = Some(Plane::New);
+                self.overflowed = false;
+                0
+            }
+            WRITE_RAM_RED => {
+                self.writing = Some(Plane::Old);
+                self.overflowed = false;
+                0
+            }
+            WRITE_LUT_REGISTER => {
+                self.lut = Some(Vec::new());
+                0
+            }
+            _ => 0,
+        }
+    }
+
+    fn data(
+        &mut self,
+        command: u8,
+        index: usize,
+        byte: u8,
+        violations: &mut Vec<Violation>,
+    ) -> u32 {
+        if let Some(plane) = self.writing {
+            self.write_ram(plane, byte, violations);
+            return 0;
+        }
+        if let (WRITE_LUT_REGISTER, Some(lut)) = (command, &mut self.lut) {
+            lut.push(byte);
+            return 0;
+        }
+        let r = &mut self.registers;
+        match (command, index) {
+            (DEEP_SLEEP_MODE, 0) => self.asleep = byte & 0x03 != 0,
+            (DATA_ENTRY_MODE_SETTING, 0) => r.data_entry_mode = byte & 0x07,
+            (WRITE_TEMPERATURE_REGISTER, 0) => self.temperature = Some(byte as i8),
+            (DISPLAY_UPDATE_CONTROL_1, 0) => r.update_control_1 = byte,
+            (DISPLAY_UPDATE_CONTROL_2, 0) => r.update_control_2 = byte,
+            (SET_RAM_X_ADDRESS_START_END_POSITION, 0) => r.x_start = u32::from(byte & 0x3F),
+            (SET_RAM_X_ADDRESS_START_END_POSITION, 1) => r.x_end = u32::from(byte & 0x3F),
+            (SET_RAM_Y_ADDRESS_START_END_POSITION, 0) => r.y_start = u32::from(byte),
+            (SET_RAM_Y_ADDRESS_START_END_POSITION, 1) => r.y_start |= u32::from(byte & 0x01) << 8,
+            (SET_RAM_Y_ADDRESS_START_END_POSITION, 2) => r.y_end = u32::from(byte),
+            (SET_RAM_Y_ADDRESS_START_END_POSITION, 3) => r.y_end |= u32::from(byte & 0x01) << 8,
+            (SET_RAM_X_ADDRESS_COUNTER, 0) => r.x = u32::from(byte & 0x3F),
+            (SET_RAM_Y_ADDRESS_COUNTER, 0) => r.y = u32::from(byte),
+            (SET_RAM_Y_ADDRESS_COUNTER, 1) => r.y |= u32::from(byte & 0x01) << 8,
+            _ => {}
+        }
+        0
+    }
+
+    fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    fn ram(&self) -> &Ram {
+        &self.ram
+    }
+
+    fn image(&self) -> &Image {
+        &self.image
+    }
+
+    fn refreshes(&self) -> u32 {
+        self.refreshes
+    }
+
+    fn lut(&self, command: u8) -> Option<&[u8]> {
+        match command {
+            WRITE_LUT_REGISTER => self.lut.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn temperature(&self) -> Option<i8> {
+        self.temperature
+    }
+}
@@ -0,0 +1,313 @@
+//! Command set of the UC8151 and the IL0398
+
+use super::{Controller, Image, Plane, Ram, Violation, SENSOR_CELSIUS};
+use crate::color::TriColor;
+use std::vec::Vec;
+
+const PANEL_SETTING: u8 = 0x00;
+const POWER_OFF: u8 = 0x02;
+const POWER_ON: u8 = 0x04;
+const DEEP_SLEEP: u8 = 0x07;
+const DATA_START_TRANSMISSION_1: u8 = 0x10;
+const DISPLAY_REFRESH: u8 = 0x12;
+const DATA_START_TRANSMISSION_2: u8 = 0x13;
+const LUT_FOR_VCOM: u8 = 0x20;
+const LUT_BLACK_TO_BLACK: u8 = 0x24;
+const VCOM_AND_DATA_INTERVAL_SETTING: u8 = 0x50;
+const PARTIAL_WINDOW: u8 = 0x90;
+const PARTIAL_IN: u8 = 0x91;
+const PARTIAL_OUT: u8 = 0x92;
+const CASCADE_SETTING: u8 = 0xE0;
+const FORCE_TEMPERATURE: u8 = 0xE5;
+
+/// Approximate busy times in ms
+const POWER_ON_MS: u32 = 80;
+const POWER_OFF_MS: u32 = 20;
+const FULL_REFRESH_MS: u32 = 2000;
+const PARTIAL_REFRESH_MS: u32 = 400;
+
+/// RAM window in bytes (x) and rows (y), both inclusive
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    x_start: u32,
+    x_end: u32,
+    y_start: u32,
+    y_end: u32,
+}
+
+pub(super) struct Uc8151 {
+    ram: Ram,
+    image: Image,
+    refreshes: u32,
+    powered: bool,
+    asleep: bool,
+    /// Black/white mode (KW) instead of black/white/red (KWR)
+    black_white: bool,
+    /// REG_EN: waveforms from the LUT registers instead of the OTP memory
+    lut_from_registers: bool,
+    /// LUT registers from VCOM to black to black
+    luts: [Vec<u8>; 5],
+    /// TSFIX: use the forced temperature instead of the sensor
+    fixed_temperature: bool,
+    forced_temperature: i8,
+    /// DDX\[0\] cleared: black/white data is inverted
+    inverted: bool,
+    partial: bool,
+    partial_window: Window,
+    params: [u8; 9],
+    /// RAM plane and address counter of a running data transmission
+    transmission: Option<(Plane, u32, u32)>,
+    /// Bytes left in the window of the running data transmission
+    remaining: u32,
+    overflowed: bool,
+}
+
+impl Uc8151 {
+    pub(super) fn new(width: u32, height: u32) -> Self {
+        let ram = Ram::new(width, height);
+        let partial_window = Window {
+            x_start: 0,
+            x_end: ram.bytes_per_row - 1,
+            y_start: 0,
+            y_end: height - 1,
+        };
+        Uc8151 {
+            ram,
+            image: Image::new(width, height),
+            refreshes: 0,
+            powered: false,
+            asleep: false,
+            black_white: false,
+            lut_from_registers: false,
+            luts: Default::default(),
+            fixed_temperature: false,
+            forced_temperature: 0,
+            inverted: false,
+            partial: false,
+            partial_window,
+            params: [0; 9],
+            transmission: None,
+            remaining: 0,
+            overflowed: false,
+        }
+    }
+
+    fn window(&self) -> Window {
+        if self.partial {
+            self.partial_window
+        } else {
+            Window {
+                x_start: 0,
+                x_end: self.ram.bytes_per_row - 1,
+                y_start: 0,
+                y_end: self.ram.rows - 1,
+            }
+        }
+    }
+
+    fn start_transmission(&mut self, plane: Plane) {
+        let window = self.window();
+        self.transmission = Some((plane, window.x_start, window.y_start));
+        self.remaining = (window.x_end - window.x_start + 1) * (window.y_end - window.y_start + 1);
+        self.overflowed = false;
+    }
+
+    fn transmit(&mut self, byte: u8, violations: &mut Vec<Violation>) {
+        let window = self.window();
+        if let Some((plane, x, y)) = self.transmission {
+            if self.remaining == 0 && !self.overflowed {
+                self.overflowed = true;
+                violations.push(Violation::WindowOverrun);
+            }
+            self.remaining = self.remaining.saturating_sub(1);
+            if !self.ram.write(plane, x, y, byte) && !self.overflowed {
+                self.overflowed = true;
+                violations.push(Violation::RamOverflow);
+            }
+            let (x, y) = if x >= window.x_end {
+                let y = if y >= window.y_end {
+                    window.y_start
+                } else {
+                    y + 1
+                };
+                (window.x_start, y)
+            } else {
+                (x + 1, y)
+            };
+            self.transmission = Some((plane, x, y));
+        }
+    }
+
+    fn set_partial_window(&mut self, len: usize) {
+        let p = &self.params;
+        let window = match len {
+            // UC8151: HRST, HRED, VRST, VRED with 8 bit x and 9 bit y
+            7 => Window {
+                x_start: u32::from(p[0]) / 8,
+                x_end: u32::from(p[1]) / 8,
+                y_start: u32::from(p[2] & 0x01) << 8 | u32::from(p[3]),
+                y_end: u32::from(p[4] & 0x01) << 8 | u32::from(p[5]),
+            },
+            // IL0398: 16 bit coordinates, also sent by the GDEW0213T5D driver
+            9 => Window {
+                x_start: (u32::from(p[0]) << 8 | u32::from(p[1])) / 8,
+                x_end: (u32::from(p[2]) << 8 | u32::from(p[3])) / 8,
+                y_start: u32::from(p[4]) << 8 | u32::from(p[5]),
+                y_end: u32::from(p[6]) << 8 | u32::from(p[7]),
+            },
+            _ => return,
+        };
+        self.partial_window = window;
+    }
+
+    fn refresh(&mut self) {
+        let window = self.window();
+        let x_end = ((window.x_end + 1) * 8).min(self.image.width());
+        let y_end = (window.y_end + 1).min(self.image.height());
+        for y in window.y_start..y_end {
+            for x in window.x_start * 8..x_end {
+                let (old, new) = self.ram.bits(x, y);
+                let color = if self.black_white {
+                    if new != self.inverted {
+                        TriColor::White
+                    } else {
+                        TriColor::Black
+                    }
+                } else if !new {
+                    TriColor::Chromatic
+                } else if old {
+                    TriColor::White
+                } else {
+                    TriColor::Black
+                };
+                self.image.set_pixel(x, y, color);
+            }
+        }
+        self.refreshes += 1;
+    }
+}
+
+impl Controller for Uc8151 {
+    fn reset(&mut self) {
+        self.powered = false;
+        self.asleep = false;
+        self.black_white = false;
+        self.lut_from_registers = false;
+        self.luts = Default::default();
+        self.fixed_temperature = false;
+        self.inverted = false;
+        self.partial = false;
+        self.transmission = None;
+    }
+
+    fn command(&mut self, command: u8, violations: &mut Vec<Violation>) -> u32 {
+        self.transmission = None;
+        match command {
+            POWER_OFF => {
+                self.powered = false;
+                POWER_OFF_MS
+            }
+            POWER_ON => {
+                self.powered = true;
+                POWER_ON_MS
+            }
+            DATA_START_TRANSMISSION_1 => {
+                self.start_transmission(Plane::Old);
+                0
+            }
+            DATA_START_TRANSMISSION_2 => {
+                self.start_transmission(Plane::New);
+                0
+            }
+            DISPLAY_REFRESH => {
+                if !self.powered {
+                    violations.push(Violation::RefreshWhilePoweredOff);
+                    return 0;
+                }
+                self.refresh();
+                if self.partial {
+                    PARTIAL_REFRESH_MS
+                } else {
+                    FULL_REFRESH_MS
+                }
+            }
+            LUT_FOR_VCOM..=LUT_BLACK_TO_BLACK => {
+                self.luts[usize::from(command - LUT_FOR_VCOM)].clear();
+                0
+            }
+            PARTIAL_IN => {
+                self.partial = true;
+                0
+            }
+            PARTIAL_OUT => {
+                self.partial = false;
+                0
+            }
+            _ => 0,
+        }
+    }
+
+    fn data(
+        &mut self,
+        command: u8,
+        index: usize,
+        byte: u8,
+        violations: &mut Vec<Violation>,
+    ) -> u32 {
+        match command {
+            DATA_START_TRANSMISSION_1 | DATA_START_TRANSMISSION_2 => {
+                self.transmit(byte, violations)
+            }
+            PANEL_SETTING if index == 0 => {
+                self.lut_from_registers = byte & 0x20 != 0;
+                self.black_white = byte & 0x10 != 0;
+            }
+            LUT_FOR_VCOM..=LUT_BLACK_TO_BLACK => {
+                self.luts[usize::from(command - LUT_FOR_VCOM)].push(byte)
+            }
+            VCOM_AND_DATA_INTERVAL_SETTING if index == 0 => self.inverted = byte & 0x10 == 0,
+            DEEP_SLEEP if index == 0 => self.asleep = byte == 0xA5,
+            CASCADE_SETTING if index == 0 => self.fixed_temperature = byte & 0x02 != 0,
+            FORCE_TEMPERATURE if index == 0 => self.forced_temperature = byte as i8,
+            PARTIAL_WINDOW if index < self.params.len() => {
+                self.params[index] = byte;
+                self.set_partial_window(index + 1);
+            }
+            _ => {}
+        }
+        0
+    }
+
+    fn is_asleep(&self) -> bool {
+        self.asleep
+    }
+
+    fn ram(&self) -> &Ram {
+        &self.ram
+    }
+
+    fn image(&self) -> &Image {
+        &self.image
+    }
+
+    fn refreshes(&self) -> u32 {
+        self.refreshes
+    }
+
+    fn lut(&self, command: u8) -> Option<&[u8]> {
+        match command {
+            LUT_FOR_VCOM..=LUT_BLACK_TO_BLACK if self.lut_from_registers => {
+                Some(&self.luts[usize::from(command - LUT_FOR_VCOM)])
+            }
+            _ => None,
+        }
+    }
+
+    fn temperature(&self) -> Option<i8> {
+        if self.fixed_temperature {
+            Some(self.forced_temperature)
+        } else {
+            Some(SENSOR_CELSIUS)
+        }
+    }
+}
@@ -0,0 +1,531 @@
+//! Snapshots of display buffers and simulated panels as PBM/PGM/PPM and PNG images
+//!
+//! A [Snapshot] is an RGB image of what a buffer would look like on the panel. It can be taken
+//! from any [Display], [TriDisplay], [OctDisplay] or [QuadDisplay] (as seen with its current
+//! rotation) and from the image of a simulated panel (`simulator` feature), written as image file
+//! and loaded back to compare it with the expected one.
+//!
+//! Only available with the `std` feature.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# fn main() -> std::io::Result<()> {
+//!use epd_waveshare::{epd2in9::Display2in9, snapshot::Snapshot};
+//!
+//!let display = Display2in9::default();
+//!// ...draw on the display
+//!
+//!let snapshot = Snapshot::from_display(&display);
+//!assert_eq!(snapshot, Snapshot::load("tests/snapshots/2in9.png")?);
+//!# Ok(())
+//!# }
+//!```
+
+#[cfg(feature = "graphics")]
+use crate::graphics::{
+    find_gray_position, find_oct_position, find_position, Display, DisplayRotation, OctDisplay,
+    QuadDisplay, TriDisplay,
+};
+#[cfg(feature = "graphics")]
+use embedded_graphics_core::prelude::*;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::vec::Vec;
+
+const BLACK: [u8; 3] = [0x00, 0x00, 0x00];
+const WHITE: [u8; 3] = [0xff, 0xff, 0xff];
+#[cfg(any(feature = "graphics", feature = "simulator", test))]
+const RED: [u8; 3] = [0xff, 0x00, 0x00];
+
+/// Image file formats of a [Snapshot]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Binary portable bitmap (P4), pixels darker than mid gray become black
+    Pbm,
+    /// Binary portable graymap (P5) with 8 bit
+    Pgm,
+    /// Binary portable pixmap (P6) with 8 bit per channel
+    Ppm,
+    /// PNG with 8 bit RGB
+    Png,
+}
+
+impl Format {
+    /// Format belonging to the extension of `path`
+    pub fn from_path(path: &Path) -> Option<Format> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "pbm" => Some(Format::Pbm),
+            "pgm" => Some(Format::Pgm),
+            "ppm" => Some(Format::Ppm),
+            "png" => Some(Format::Png),
+            _ => None,
+        }
+    }
+}
+
+/// RGB image of a display buffer, see the [module documentation](self)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 3]>,
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Size of a display as seen with the given rotation
+#[cfg(feature = "graphics")]
+fn rotated_size(width: u32, height: u32, rotation: DisplayRotation) -> (u32, u32) {
+    match rotation {
+        DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (width, height),
+        DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (height, width),
+    }
+}
+
+impl Snapshot {
+    /// Creates a snapshot from RGB pixels, row by row
+    ///
+    /// Panics if the number of pixels doesn't match the size.
+    pub fn new(width: u32, height: u32, pixels: Vec<[u8; 3]>) -> Self {
+        assert_eq!(pixels.len(), (width * height) as usize);
+        Snapshot {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Creates a snapshot by asking for the color of every pixel
+    pub fn from_fn(width: u32, height: u32, mut pixel: impl FnMut(u32, u32) -> [u8; 3]) -> Self {
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                pixels.push(pixel(x, y));
+            }
+        }
+        Snapshot::new(width, height, pixels)
+    }
+
+    /// Snapshot of a black/white display as seen with its current rotation
+    #[cfg(feature = "graphics")]
+    pub fn from_display<D: Display>(display: &D) -> Self {
+        let Size { width, height } = display.buffer_size();
+        let rotation = display.rotation();
+        let buffer = display.buffer();
+        let (rotated_width, rotated_height) = rotated_size(width, height, rotation);
+        Snapshot::from_fn(rotated_width, rotated_height, |x, y| {
+            let (index, bit) = find_position(x, y, width, height, rotation);
+            if buffer[index as usize] & bit != 0 {
+                WHITE
+            } else {
+                BLACK
+            }
+        })
+    }
+
+    /// Snapshot of a black/white/chromatic display as seen with its current rotation,
+    /// the chromatic color is shown as red
+    #[cfg(feature = "graphics")]
+    pub fn from_tri_display<D: TriDisplay>(display: &D) -> Self {
+        let Size { width, height } = display.buffer_size();
+        let rotation = display.rotation();
+        let (bw, chromatic) = (display.bw_buffer(), display.chromatic_buffer());
+        let (rotated_width, rotated_height) = rotated_size(width, height, rotation);
+        Snapshot::from_fn(rotated_width, rotated_height, |x, y| {
+            let (index, bit) = find_position(x, y, width, height, rotation);
+            let index = index as usize;
+            if chromatic[index] & bit == 0 {
+                RED
+            } else if bw[index] & bit != 0 {
+                WHITE
+            } else {
+                BLACK
+            }
+        })
+    }
+
+    /// Snapshot of a seven color display as seen with its current rotation
+    #[cfg(feature = "graphics")]
+    pub fn from_oct_display<D: OctDisplay>(display: &D) -> Self {
+        let Size { width, height } = display.buffer_size();
+        let rotation = display.rotation();
+        let buffer = display.buffer();
+        let (rotated_width, rotated_height) = rotated_size(width, height, rotation);
+        Snapshot::from_fn(rotated_width, rotated_height, |x, y| {
+            let (index, upper) = find_oct_position(x, y, width, height, rotation);
+            let byte = buffer[index as usize];
+            let nibble = if upper { byte >> 4 } else { byte & 0x0f };
+            let (r, g, b) = crate::color::OctColor::from_nibble(nibble)
+                .unwrap_or(crate::color::OctColor::HiZ)
+                .rgb();
+            [r, g, b]
+        })
+    }
+
+    /// Snapshot of a 4 grayscale display as seen with its current rotation
+    #[cfg(feature = "graphics")]
+    pub fn from_quad_display<D: QuadDisplay>(display: &D) -> Self {
+        let Size { width, height } = display.buffer_size();
+        let rotation = display.rotation();
+        let buffer = display.buffer();
+        let (rotated_width, rotated_height) = rotated_size(width, height, rotation);
+        Snapshot::from_fn(rotated_width, rotated_height, |x, y| {
+            let (index, shift) = find_gray_position(x, y, width, height, rotation);
+            let bits = buffer[index as usize] >> shift;
+            let luma = crate::color::QuadColor::from_bits(bits).luma();
+            [luma; 3]
+        })
+    }
+
+    /// Snapshot of the image rendered by a simulated panel
+    #[cfg(any(test, feature = "simulator"))]
+    pub fn from_simulator(image: &crate::simulator::Image) -> Self {
+        use crate::color::TriColor;
+
+        Snapshot::from_fn(image.width(), image.height(), |x, y| {
+            match image.pixel(x, y) {
+                TriColor::White => WHITE,
+                TriColor::Black => BLACK,
+                TriColor::Chromatic => RED,
+            }
+        })
+    }
+
+    /// Width in pixels
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Height in pixels
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// RGB color of the pixel at `x`, `y`
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        assert!(x < self.width && y < self.height);
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// All pixels, row by row
+    pub fn pixels(&self) -> &[[u8; 3]] {
+        &self.pixels
+    }
+
+    /// Number of pixels which differ from `other`, `None` if the sizes differ
+    pub fn diff(&self, other: &Snapshot) -> Option<usize> {
+        if (self.width, self.height) != (other.width, other.height) {
+            return None;
+        }
+        Some(
+            self.pixels
+                .iter()
+                .zip(other.pixels.iter())
+                .filter(|(a, b)| a != b)
+                .count(),
+        )
+    }
+
+    /// Writes the snapshot in the given format
+    pub fn write<W: Write>(&self, format: Format, writer: W) -> io::Result<()> {
+        match format {
+            Format::Pbm => self.write_pbm(writer),
+            Format::Pgm => self.write_pgm(writer),
+            Format::Ppm => self.write_ppm(writer),
+            Format::Png => self.write_png(writer),
+        }
+    }
+
+    /// Writes the snapshot to `path`, the format is chosen by the file extension
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = Format::from_path(path).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "unknown image extension")
+        })?;
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(format, &mut writer)?;
+        writer.flush()
+    }
+
+    /// Loads a snapshot written by [save](Snapshot::save) or any binary PBM/PGM/PPM or PNG image
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Snapshot::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a binary PBM/PGM/PPM or PNG image, the format is detected from its content
+    pub fn read<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        if reader.fill_buf()?.starts_with(b"\x89PNG") {
+            Snapshot::read_png(reader)
+        } else {
+            Snapshot::read_netpbm(reader)
+        }
+    }
+
+    fn luma(pixel: [u8; 3]) -> u8 {
+        let [r, g, b] = pixel;
+        ((u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114) / 1000) as u8
+    }
+
+    fn write_pbm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "P4\n{} {}\n", self.width, self.height)?;
+        // rows are padded to full bytes, 1 is black
+        let mut row = std::vec![0u8; self.width.div_ceil(8) as usize];
+        for pixels in self.pixels.chunks(self.width as usize) {
+            row.iter_mut().for_each(|byte| *byte = 0);
+            for (x, &pixel) in pixels.iter().enumerate() {
+                if Snapshot::luma(pixel) < 0x80 {
+                    row[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+            writer.write_all(&row)?;
+        }
+        Ok(())
+    }
+
+    fn write_pgm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "P5\n{} {}\n255\n", self.width, self.height)?;
+        let data: Vec<u8> = self.pixels.iter().map(|&p| Snapshot::luma(p)).collect();
+        writer.write_all(&data)
+    }
+
+    fn write_ppm<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.pixels.concat())
+    }
+
+    fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels.concat())?;
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn read_png<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info()?;
+        let mut data = std::vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut data)?;
+        let data = &data[..info.buffer_size()];
+
+        let pixels = match info.color_type {
+            png::ColorType::Grayscale => data.iter().map(|&l| [l; 3]).collect(),
+            png::ColorType::GrayscaleAlpha => data.chunks(2).map(|p| [p[0]; 3]).collect(),
+            png::ColorType::Rgb => data.chunks(3).map(|p| [p[0], p[1], p[2]]).collect(),
+            png::ColorType::Rgba => data.chunks(4).map(|p| [p[0], p[1], p[2]]).collect(),
+            png::ColorType::Indexed => return Err(invalid_data("unexpanded indexed png")),
+        };
+        Ok(Snapshot::new(info.width, info.height, pixels))
+    }
+
+    fn read_netpbm<R: BufRead>(mut reader: R) -> io::Result<Self> {
+        let magic = read_token(&mut reader)?;
+        let width = read_number(&mut reader)?;
+        let height = read_number(&mut reader)?;
+        let max = if magic == "P4" {
+            1
+        } else {
+            read_number(&mut reader)?
+        };
+        if max == 0 || max > 255 {
+            return Err(invalid_data("only 8 bit netpbm images are supported"));
+        }
+        let scale = |value: u8| (u32::from(value) * 255 / max) as u8;
+
+        let count = (width * height) as usize;
+        let pixels = match magic.as_str() {
+            "P4" => {
+                let row_len = width.div_ceil(8) as usize;
+                let mut data = std::vec![0; row_len * height as usize];
+                reader.read_exact(&mut data)?;
+                let mut pixels = Vec::with_capacity(count);
+                for row in data.chunks(row_len) {
+                    for x in 0..width as usize {
+                        let black = row[x / 8] & (0x80 >> (x % 8)) != 0;
+                        pixels.push(if black { BLACK } else { WHITE });
+                    }
+                }
+                pixels
+            }
+            "P5" => {
+                let mut data = std::vec![0; count];
+                reader.read_exact(&mut data)?;
+                data.iter().map(|&l| [scale(l); 3]).collect()
+            }
+            "P6" => {
+                let mut data = std::vec![0; count * 3];
+                reader.read_exact(&mut data)?;
+                data.chunks(3)
+                    .map(|p| [scale(p[0]), scale(p[1]), scale(p[2])])
+                    .collect()
+            }
+            _ => return Err(invalid_data("unsupported image format")),
+        };
+        Ok(Snapshot::new(width, height, pixels))
+    }
+}
+
+/// Reads a whitespace separated token of a netpbm header, skipping comments
+///
+/// Consumes exactly one whitespace character after the token, as required before the raster.
+fn read_token<R: BufRead>(reader: &mut R) -> io::Result<std::string::String> {
+    let mut token = std::string::String::new();
+    let mut byte = [0u8];
+    loop {
+        reader.read_exact(&mut byte)?;
+        match byte[0] {
+            b'#' if token.is_empty() => {
+                let mut comment = Vec::new();
+                reader.read_until(b'\n', &mut comment)?;
+            }
+            b if b.is_ascii_whitespace() => {
+                if !token.is_empty() {
+                    return Ok(token);
+                }
+            }
+            b => token.push(b as char),
+        }
+    }
+}
+
+fn read_number<R: BufRead>(reader: &mut R) -> io::Result<u32> {
+    read_token(reader)?
+        .parse()
+        .map_err(|_| invalid_data("invalid number in image header"))
+}
+
+#[cfg(all(test, feature = "graphics"))]
+mod tests {
+    use super::*;
+    use crate::color::{Black, Color, OctColor, QuadColor, TriColor};
+    use crate::graphics::VarDisplay;
+    use embedded_graphics::{
+        prelude::*,
+        primitives::{Line, PrimitiveStyle},
+    };
+
+    fn roundtrip(snapshot: &Snapshot, format: Format) -> Snapshot {
+        let mut data = Vec::new();
+        snapshot.write(format, &mut data).unwrap();
+        Snapshot::read(&data[..]).unwrap()
+    }
+
+    #[test]
+    fn display_with_padding_and_rotation() {
+        // 122 pixels wide, so every row is padded by 6 bits
+        let mut buffer = [Color::White.get_byte_value(); crate::buffer_len(122, 250)];
+        let mut display = VarDisplay::new(122, 250, &mut buffer);
+        display.set_rotation(DisplayRotation::Rotate90);
+        let _ = Line::new(Point::new(0, 0), Point::new(249, 0))
+            .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+            .draw(&mut display);
+
+        let snapshot = Snapshot::from_display(&display);
+        assert_eq!((snapshot.width(), snapshot.height()), (250, 122));
+        assert!((0..250).all(|x| snapshot.pixel(x, 0) == BLACK));
+        assert!((0..250).all(|x| snapshot.pixel(x, 1) == WHITE));
+
+        for format in [Format::Pbm, Format::Pgm, Format::Ppm, Format::Png] {
+            assert_eq!(roundtrip(&snapshot, format), snapshot);
+        }
+    }
+
+    #[test]
+    fn display_with_rotated_size() {
+        let mut display = crate::epd2in13_GDEQ0213B74::Display2in13::default();
+        display.set_rotation(DisplayRotation::Rotate90);
+        let _ = Line::new(Point::new(0, 0), Point::new(249, 0))
+            .into_styled(PrimitiveStyle::with_stroke(Black, 1))
+            .draw(&mut display);
+
+        let snapshot = Snapshot::from_display(&display);
+        assert_eq!((snapshot.width(), snapshot.height()), (250, 122));
+        assert!((0..250).all(|x| snapshot.pixel(x, 0) == BLACK));
+        assert!((1..122).all(|y| (0..250).all(|x| snapshot.pixel(x, y) == WHITE)));
+
+        let mut display = crate::epd2in13_GDEW0213T5D::DisplayT5DGray::default();
+        display.set_rotation(DisplayRotation::Rotate270);
+        let _ = Pixel(Point::new(200, 3), QuadColor::DarkGray).draw(&mut display);
+        let snapshot = Snapshot::from_quad_display(&display);
+        assert_eq!((snapshot.width(), snapshot.height()), (212, 104));
+        assert_eq!(snapshot.pixel(200, 3), [QuadColor::DarkGray.luma(); 3]);
+        let gray = (0..104)
+            .flat_map(|y| (0..212).map(move |x| (x, y)))
+            .filter(|&(x, y)| snapshot.pixel(x, y) != [0xff; 3])
+            .count();
+        assert_eq!(gray, 1);
+    }
+
+    #[test]
+    fn tri_display() {
+        let mut display = crate::epd2in13bc::Display2in13bc::default();
+        let _ = Pixel(Point::new(1, 0), TriColor::Chromatic).draw(&mut display);
+        let _ = Pixel(Point::new(2, 0), TriColor::Black).draw(&mut display);
+
+        let snapshot = Snapshot::from_tri_display(&display);
+        assert_eq!(snapshot.pixel(0, 0), WHITE);
+        assert_eq!(snapshot.pixel(1, 0), RED);
+        assert_eq!(snapshot.pixel(2, 0), BLACK);
+        assert_eq!(roundtrip(&snapshot, Format::Png), snapshot);
+        assert_eq!(roundtrip(&snapshot, Format::Ppm), snapshot);
+    }
+
+    #[test]
+    fn oct_and_quad_display() {
+        let mut display = crate::epd5in65f::Display5in65f::default();
+        let _ = Pixel(Point::new(3, 1), OctColor::Green).draw(&mut display);
+        let snapshot = Snapshot::from_oct_display(&display);
+        assert_eq!(snapshot.pixel(3, 1), [0x00, 0xff, 0x00]);
+        assert_eq!(snapshot.pixel(2, 1), [0xff, 0xff, 0xff]);
+
+        let mut display = crate::epd2in13_GDEW0213T5D::DisplayT5DGray::default();
+        let _ = Pixel(Point::new(5, 7), QuadColor::DarkGray).draw(&mut display);
+        let snapshot = Snapshot::from_quad_display(&display);
+        assert_eq!(snapshot.pixel(5, 7), [QuadColor::DarkGray.luma(); 3]);
+        assert_eq!(roundtrip(&snapshot, Format::Pgm), snapshot);
+    }
+
+    #[test]
+    fn simulated_panel_matches_display() {
+        use crate::epd4in2::{Display4in2, Epd4in2};
+        use crate::prelude::WaveshareDisplay;
+        use crate::simulator::SimulatedPanel;
+
+        let mut display = Display4in2::default();
+        let _ = Line::new(Point::new(10, 20), Point::new(300, 200))
+            .into_styled(PrimitiveStyle::with_stroke(Black, 3))
+            .draw(&mut display);
+
+        let panel = SimulatedPanel::il0398(crate::epd4in2::WIDTH, crate::epd4in2::HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd4in2<_, _, _, _, _, _>>();
+        epd.update_and_display_frame(&mut spi, display.buffer(), &mut delay)
+            .unwrap();
+
+        let expected = Snapshot::from_display(&display);
+        assert_eq!(Snapshot::from_simulator(&panel.image()), expected);
+        assert_eq!(roundtrip(&expected, Format::Pbm), expected);
+    }
+
+    #[test]
+    fn diff() {
+        let a = Snapshot::from_fn(3, 2, |_, _| WHITE);
+        let b = Snapshot::from_fn(3, 2, |x, y| if (x, y) == (1, 1) { BLACK } else { WHITE });
+        assert_eq!(a.diff(&b), Some(1));
+        assert_eq!(a.diff(&Snapshot::from_fn(2, 3, |_, _| WHITE)), None);
+    }
+
+    #[test]
+    fn read_netpbm_with_comment() {
+        let data = b"P5\n# comment\n2 1\n15\n\x00\x0f";
+        let snapshot = Snapshot::read(&data[..]).unwrap();
+        assert_eq!(snapshot.pixels(), [BLACK, WHITE]);
+    }
+}
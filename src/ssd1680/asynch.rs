@@ -0,0 +1,445 @@
+//! Async implementation of the SSD1675, SSD1680 and SSD1681 driver
+
+use embedded_hal_1::digital::{InputPin, OutputPin};
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiBus};
+
+use core::marker::PhantomData;
+
+use super::command::Command;
+use super::sequence::{self, RamWindow};
+use super::{Panel, Ssd1680, DEFAULT_BACKGROUND_COLOR, IS_BUSY_LOW};
+use crate::asynch::{
+    AsyncDriverError, AsyncLutSelection, AsyncPartialUpdate, AsyncQuickRefresh,
+    AsyncWaveshareDisplay,
+};
+use crate::buffer_len;
+use crate::color::Color;
+use crate::error::EpdError;
+use crate::interface::DisplayInterface;
+use crate::sequence::Step;
+use crate::traits::RefreshLut;
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    async fn run_async(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        steps: &[Step<'_>],
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .run_async(spi, delay, IS_BUSY_LOW, steps)
+            .await
+    }
+
+    async fn init_async(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &[Step::Reset(P::RESET_DELAY_MS)])
+            .await?;
+        let quick = self.refresh_mode == RefreshLut::Quick;
+        if quick && !P::QUICK_INIT_SW_RESET {
+            self.write_waveform_async(spi, delay).await?;
+            return self.run_async(spi, delay, &sequence::init_quick()).await;
+        }
+
+        let window = RamWindow::full::<P>();
+        self.run_async(spi, delay, &sequence::init::<P>(quick, &window))
+            .await?;
+        self.write_waveform_async(spi, delay).await?;
+        let temperature = self.temperature.map(sequence::temperature);
+        self.run_async(
+            spi,
+            delay,
+            &sequence::init_end::<P>(quick, temperature.as_ref()),
+        )
+        .await
+    }
+
+    /// Writes the waveform of the current mode, [set_lut](AsyncLutSelection::set_lut) with
+    /// `None`
+    async fn write_waveform_async(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        match self.waveform().map_err(EpdError::Lut)? {
+            Some(waveform) => {
+                self.run_async(spi, delay, &sequence::write_waveform(&waveform))
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    async fn write_ram_async(
+        &mut self,
+        spi: &mut SPI,
+        window: &RamWindow,
+        ram: Command,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::write_ram(window, ram, buffer))
+            .await
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    AsyncWaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    type DisplayColor = Color;
+
+    async fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Ssd1680 {
+            interface: DisplayInterface::new(cs, busy, dc, rst),
+            sleep_mode: P::SLEEP_MODE,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh_mode: RefreshLut::Full,
+            temperature: None,
+            panel: PhantomData,
+        };
+
+        epd.init_async(spi, delay).await?;
+        Ok(epd)
+    }
+
+    async fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        debug!("go_to_sleep 0x{:x}", self.sleep_mode as u8);
+        self.run_async(spi, delay, &sequence::sleep::<P>(self.sleep_mode))
+            .await
+    }
+
+    async fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.init_async(spi, delay).await
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.background_color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    fn width(&self) -> u32 {
+        P::WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        P::HEIGHT
+    }
+
+    async fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let window = RamWindow::full::<P>();
+        self.write_ram_async(spi, &window, Command::WriteRam, buffer, delay)
+            .await?;
+        if self.refresh_mode == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.write_ram_async(spi, &window, Command::WriteRamRed, buffer, delay)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let update_control = self.display_update_control();
+        info!("display_frame() {:x}", update_control.as_u8());
+        self.run_async(
+            spi,
+            delay,
+            &sequence::display_frame(&[update_control.as_u8()]),
+        )
+        .await
+    }
+
+    async fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        AsyncWaveshareDisplay::update_frame(self, spi, buffer, delay).await?;
+        AsyncWaveshareDisplay::display_frame(self, spi, delay).await?;
+
+        if self.refresh_mode == RefreshLut::Quick {
+            let window = RamWindow::full::<P>();
+            self.write_ram_async(spi, &window, Command::WriteRamRed, buffer, delay)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let color = self.background_color.get_byte_value();
+        let len = buffer_len(P::WIDTH as usize, P::HEIGHT as usize) as u32;
+        let window = RamWindow::full::<P>();
+
+        self.run_async(
+            spi,
+            delay,
+            &sequence::fill_ram(&window, Command::WriteRam, color, len),
+        )
+        .await?;
+
+        // Always keep the base buffer equals to current if not doing partial refresh.
+        if self.refresh_mode == RefreshLut::Full {
+            self.run_async(
+                spi,
+                delay,
+                &sequence::fill_ram(&window, Command::WriteRamRed, color, len),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    fn is_busy(&mut self) -> Result<bool, AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.is_busy_now(IS_BUSY_LOW)
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.interface.set_busy_timeout(timeout_ms);
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    AsyncPartialUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    async fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = RamWindow::new(x, y, width, height);
+        self.write_ram_async(spi, &window, Command::WriteRam, buffer, delay)
+            .await?;
+        if self.refresh_mode == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.write_ram_async(spi, &window, Command::WriteRamRed, buffer, delay)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    AsyncLutSelection<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    /// Another mode than the current one re-initializes the display, like
+    /// [set_refresh_mode](Ssd1680::set_refresh_mode)
+    async fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh_rate: Option<RefreshLut>,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        match refresh_rate {
+            Some(refresh_mode) if refresh_mode != self.refresh_mode => {
+                self.refresh_mode = refresh_mode;
+                self.init_async(spi, delay).await
+            }
+            _ => self.write_waveform_async(spi, delay).await,
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    AsyncQuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    async fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let window = RamWindow::full::<P>();
+        self.write_ram_async(spi, &window, Command::WriteRamRed, buffer, delay)
+            .await
+    }
+
+    async fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let window = RamWindow::full::<P>();
+        self.write_ram_async(spi, &window, Command::WriteRam, buffer, delay)
+            .await
+    }
+
+    async fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        AsyncWaveshareDisplay::display_frame(self, spi, delay).await
+    }
+
+    async fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay).await?;
+        AsyncWaveshareDisplay::display_frame(self, spi, delay).await
+    }
+
+    async fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = RamWindow::new(x, y, width, height);
+        self.write_ram_async(spi, &window, Command::WriteRamRed, buffer, delay)
+            .await
+    }
+
+    async fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = RamWindow::new(x, y, width, height);
+        self.write_ram_async(spi, &window, Command::WriteRam, buffer, delay)
+            .await
+    }
+
+    async fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+
+        let color = self.background_color.get_byte_value();
+        let len = buffer_len(width as usize, height as usize) as u32;
+        let window = RamWindow::new(x, y, width, height);
+        self.run_async(
+            spi,
+            delay,
+            &sequence::fill_ram(&window, Command::WriteRam, color, len),
+        )
+        .await
+    }
+}
@@ -0,0 +1,267 @@
+//! SPI Commands for the SSD1675, SSD1680 and SSD1681
+
+use crate::traits;
+
+/// Obtained from SSD1680 datasheet v0.14
+/// https://www.crystalfontz.com/controllers/SolomonSystech/SSD1680/497/
+///
+/// The SSD1675 only lacks a few of them (e.g. `TemperatureSensorControl`) and has the dummy line
+/// period and the gate line width in addition.
+#[derive(Copy, Clone, Debug)]
+pub(crate) enum Command {
+    DriverOutputControl = 0x01,
+    GateDrivingVoltageCtrl = 0x03,
+    SourceDrivingVoltageCtrl = 0x04,
+
+    GateScanStartPosition = 0x0f,
+    DeepSleepMode = 0x10,
+    DataEntryModeSetting = 0x11,
+    SwReset = 0x12,
+
+    TemperatureSensorControl = 0x18,
+    TemperatureSensorControlWrite = 0x1A,
+
+    MasterActivation = 0x20,
+    DisplayUpdateControl1 = 0x21,
+    DisplayUpdateControl2 = 0x22,
+
+    WriteRam = 0x24,
+    WriteRamRed = 0x26,
+
+    WriteVcomRegister = 0x2C,
+
+    WriteLutRegister = 0x32,
+    SetDummyLinePeriod = 0x3A,
+    SetGateLineWidth = 0x3B,
+    BorderWaveformControl = 0x3C,
+    EndOption = 0x3F,
+
+    SetRamXAddressStartEndPosition = 0x44,
+    SetRamYAddressStartEndPosition = 0x45,
+    SetRamXAddressCounter = 0x4E,
+    SetRamYAddressCounter = 0x4F,
+}
+
+pub(crate) struct DriverOutput {
+    pub scan_is_linear: bool,
+    pub scan_g0_is_first: bool,
+    pub scan_dir_incr: bool,
+
+    pub width: u16,
+}
+
+impl DriverOutput {
+    pub const fn to_bytes(&self) -> [u8; 3] {
+        [
+            self.width as u8,
+            (self.width >> 8) as u8,
+            !self.scan_dir_incr as u8
+                | (!self.scan_g0_is_first as u8) << 1
+                | (!self.scan_is_linear as u8) << 2,
+        ]
+    }
+}
+
+/// These are not directly documented, but the bitfield is easily reversed from
+/// documentation and sample code
+/// [7|6|5|4|3|2|1|0]
+///  | | | | | | | `--- disable clock
+///  | | | | | | `----- disable analog
+///  | | | | | `------- display
+///  | | | | `--------- undocumented and unknown use,
+///  | | | |            but used in waveshare reference code
+///  | | | `----------- load LUT
+///  | | `------------- load temp
+///  | `--------------- enable clock
+///  `----------------- enable analog
+pub(crate) struct DisplayUpdateControl2(pub u8);
+
+impl DisplayUpdateControl2 {
+    pub const fn new() -> DisplayUpdateControl2 {
+        DisplayUpdateControl2(0x00)
+    }
+
+    pub const fn as_u8(&self) -> u8 {
+        self.0
+    }
+
+    pub const fn disable_clock(self) -> Self {
+        DisplayUpdateControl2(self.0 | 0x01)
+    }
+
+    pub const fn disable_analog(self) -> Self {
+        DisplayUpdateControl2(self.0 | 0x02)
+    }
+
+    pub const fn display(self) -> Self {
+        DisplayUpdateControl2(self.0 | 0x04)
+    }
+
+    pub const fn display_mode2(self) -> Self {
+        DisplayUpdateControl2(self.0 | 0x08)
+    }
+
+    pub const fn load_lut(self) -> Self {
+        DisplayUpdateControl2(self.0 | 0x10)
+    }
+
+    pub const fn load_temp(self) -> Self {
+        DisplayUpdateControl2(self.0 | 0x20)
+    }
+
+    pub const fn enable_clock(self) -> Self {
+        DisplayUpdateControl2(self.0 | 0x40)
+    }
+
+    pub const fn enable_analog(self) -> Self {
+        DisplayUpdateControl2(self.0 | 0x80)
+    }
+}
+
+pub(crate) enum DataEntryModeIncr {
+    XIncrYIncr = 0x3,
+}
+
+pub(crate) enum DataEntryModeDir {
+    XDir = 0x0,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) enum BorderWaveFormVbd {
+    Gs = 0x0,
+    Vcom = 0x2,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) enum BorderWaveFormFixLevel {
+    Vss = 0x0,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) enum BorderWaveFormGs {
+    Lut0 = 0x0,
+    Lut1 = 0x1,
+    Lut3 = 0x3,
+}
+
+pub(crate) struct BorderWaveForm {
+    pub vbd: BorderWaveFormVbd,
+    pub fix_level: BorderWaveFormFixLevel,
+    pub gs_trans: BorderWaveFormGs,
+}
+
+impl BorderWaveForm {
+    pub const fn to_u8(&self) -> u8 {
+        (self.vbd as u8) << 6 | (self.fix_level as u8) << 4 | self.gs_trans as u8
+    }
+}
+
+/// Deep sleep mode entered by `sleep`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeepSleepMode {
+    /// Doesn't sleep, keeps access to RAM and controller
+    Normal = 0x00,
+
+    /// Sleeps without access to RAM/controller but keeps RAM content
+    Mode1 = 0x01,
+
+    /// Same as `Mode1` but RAM content is not kept
+    Mode2 = 0x11,
+}
+
+/// Register value of a VCOM voltage in decivolt, -0.2 V to -3 V
+pub const fn vcom(decivolt: i32) -> u8 {
+    assert!(-30 <= decivolt && decivolt <= -2);
+    match -decivolt {
+        2 => 0x08,
+        3 => 0x0B,
+        4 => 0x10,
+        5 => 0x14,
+        6 => 0x17,
+        7 => 0x1B,
+        8 => 0x20,
+        9 => 0x24,
+        10 => 0x28,
+        11 => 0x2C,
+        12 => 0x2F,
+        13 => 0x34,
+        14 => 0x37,
+        15 => 0x3C,
+        16 => 0x40,
+        17 => 0x44,
+        18 => 0x48,
+        19 => 0x4B,
+        20 => 0x50,
+        21 => 0x54,
+        22 => 0x58,
+        23 => 0x5B,
+        24 => 0x5F,
+        25 => 0x64,
+        26 => 0x68,
+        27 => 0x6C,
+        28 => 0x6F,
+        29 => 0x73,
+        30 => 0x78,
+        _ => 0,
+    }
+}
+
+/// Register value of a gate driving voltage (VGH) in decivolt, 10 V to 21 V in 0.5 V steps
+pub const fn gate_driving_decivolt(decivolt: i32) -> u8 {
+    assert!(100 <= decivolt && decivolt <= 210 && decivolt % 5 == 0);
+    ((decivolt - 100) / 5 + 0x03) as u8
+}
+
+/// Register value of a source driving voltage (VSH1, VSH2 or VSL) in decivolt: 2.4 V to 8.8 V
+/// in 0.1 V steps, 9 V to 18 V in 0.2 V steps and -9 V to -18 V in 0.5 V steps
+pub const fn source_driving_decivolt(decivolt: i32) -> u8 {
+    if 24 <= decivolt && decivolt <= 88 {
+        ((decivolt - 24) + 0x8E) as u8
+    } else if 90 <= decivolt && decivolt <= 180 && decivolt % 2 == 0 {
+        ((decivolt - 90) / 2 + 0x23) as u8
+    } else {
+        assert!(-180 <= decivolt && decivolt <= -90 && decivolt % 5 == 0);
+        (((-decivolt - 90) / 5) * 2 + 0x1A) as u8
+    }
+}
+
+impl traits::Command for Command {
+    /// Returns the address of the command
+    fn address(self) -> u8 {
+        self as u8
+    }
+}
+
+impl Command {
+    /// The command with this address, used to decode [traces](crate::trace)
+    #[cfg(any(test, feature = "std"))]
+    pub(crate) fn from_address(address: u8) -> Option<Self> {
+        Some(match address {
+            0x01 => Command::DriverOutputControl,
+            0x03 => Command::GateDrivingVoltageCtrl,
+            0x04 => Command::SourceDrivingVoltageCtrl,
+            0x0F => Command::GateScanStartPosition,
+            0x10 => Command::DeepSleepMode,
+            0x11 => Command::DataEntryModeSetting,
+            0x12 => Command::SwReset,
+            0x18 => Command::TemperatureSensorControl,
+            0x1A => Command::TemperatureSensorControlWrite,
+            0x20 => Command::MasterActivation,
+            0x21 => Command::DisplayUpdateControl1,
+            0x22 => Command::DisplayUpdateControl2,
+            0x24 => Command::WriteRam,
+            0x26 => Command::WriteRamRed,
+            0x2C => Command::WriteVcomRegister,
+            0x32 => Command::WriteLutRegister,
+            0x3A => Command::SetDummyLinePeriod,
+            0x3B => Command::SetGateLineWidth,
+            0x3C => Command::BorderWaveformControl,
+            0x3F => Command::EndOption,
+            0x44 => Command::SetRamXAddressStartEndPosition,
+            0x45 => Command::SetRamYAddressStartEndPosition,
+            0x4E => Command::SetRamXAddressCounter,
+            0x4F => Command::SetRamYAddressCounter,
+            _ => return None,
+        })
+    }
+}
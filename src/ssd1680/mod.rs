@@ -0,0 +1,935 @@
+//! Generic driver for black/white displays with a Solomon Systech SSD1675, SSD1680 or SSD1681
+//! controller
+//!
+//! The controllers only differ in a few commands, the displays in their resolution and their
+//! waveforms. A display is described by a [Panel] and driven by [Ssd1680], e.g. the
+//! [epd2in13_v2](crate::epd2in13_v2) and the [epd2in13_GDEQ0213B74](crate::epd2in13_GDEQ0213B74)
+//! are both presets of it.
+//!
+//! Panels without a [Panel::FULL_WAVEFORM] refresh with the waveform of the OTP, which the
+//! controller loads for the temperature of its sensor (or the one set with
+//! [ExternalTemperature::set_temperature]). Without [Panel::QUICK_WAVEFORMS] the quick refresh
+//! uses display mode 2 of the OTP.
+//!
+//! # Example
+//!
+//! A new panel is a few lines of configuration, e.g. the 2.9" GDEY029T94 of Good Display:
+//!
+//!```rust
+//!use epd_waveshare::ssd1680::{Controller, Panel, Ssd1680};
+//!
+//!/// Good Display GDEY029T94, 128x296 pixels
+//!pub struct Gdey029t94;
+//!
+//!impl Panel for Gdey029t94 {
+//!    const WIDTH: u32 = 128;
+//!    const HEIGHT: u32 = 296;
+//!    const CONTROLLER: Controller = Controller::Ssd1680;
+//!}
+//!
+//!pub type Epd2in9<SPI, CS, BUSY, DC, RST, DELAY> =
+//!    Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, Gdey029t94>;
+//!```
+//!
+//! # References
+//!
+//! - [SSD1680 datasheet](https://www.crystalfontz.com/controllers/SolomonSystech/SSD1680/497/)
+//! - [SSD1675 datasheet](http://www.e-paper-display.com/download_detail/downloadsId=682.html)
+
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::{InputPin, OutputPin},
+};
+
+use crate::buffer_len;
+use crate::color::Color;
+use crate::error::EpdError;
+use crate::interface::DisplayInterface;
+use crate::lut::{Lut, LutError, LutLimits};
+use crate::sequence::Step;
+use crate::traits::{
+    Deinit, DriverError, ExternalTemperature, FullRefresh, InternalWiAdditions, LutSelection,
+    PanelInfo, PartialUpdate, QuickRefresh, RefreshLut, StreamingFrame, TemperatureBand,
+    WaveshareDisplay,
+};
+
+pub(crate) mod command;
+pub use self::command::{gate_driving_decivolt, source_driving_decivolt, vcom, DeepSleepMode};
+use self::command::{Command, DisplayUpdateControl2};
+
+mod sequence;
+use self::sequence::RamWindow;
+
+#[cfg(feature = "async")]
+mod asynch;
+
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+const IS_BUSY_LOW: bool = false;
+
+/// Controller of a [Panel]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Controller {
+    /// SSD1675(B): 70 byte LUTs, no display mode 2 for the quick refresh
+    Ssd1675,
+    /// SSD1680: 153 byte LUTs, up to 176x296 pixels
+    Ssd1680,
+    /// SSD1681: 153 byte LUTs, up to 200x200 pixels
+    Ssd1681,
+}
+
+/// Waveform written to the registers instead of loading it from the OTP
+///
+/// The voltages are register values, see [vcom], [gate_driving_decivolt] and
+/// [source_driving_decivolt].
+#[derive(Copy, Clone, Debug)]
+pub struct Waveform {
+    /// LUT register (`WriteLutRegister`), 70 bytes on the SSD1675 and 153 bytes otherwise
+    pub lut: &'static [u8],
+    /// `EndOption`, SSD1680 and SSD1681 only
+    pub end_option: Option<u8>,
+    /// Gate driving voltage (VGH), the reset value if `None`
+    pub gate_voltage: Option<u8>,
+    /// Source driving voltages VSH1, VSH2 and VSL, the reset values if `None`
+    pub source_voltages: Option<[u8; 3]>,
+    /// VCOM register
+    pub vcom: u8,
+}
+
+/// Resolution and waveforms of a display driven by [Ssd1680]
+pub trait Panel {
+    /// Width of the display
+    const WIDTH: u32;
+    /// Height of the display
+    const HEIGHT: u32;
+    /// Controller of the display
+    const CONTROLLER: Controller;
+    /// Length of the hardware reset pulse in ms
+    const RESET_DELAY_MS: u8 = 10;
+    /// Waveform of [RefreshLut::Full], `None` for the OTP
+    const FULL_WAVEFORM: Option<Waveform> = None;
+    /// Waveforms of [RefreshLut::Quick] for [TemperatureBand::Cold], [TemperatureBand::Normal]
+    /// and [TemperatureBand::Hot], `None` for display mode 2 of the OTP
+    ///
+    /// The register waveforms of both modes are refused with [EpdError::Lut] instead of being
+    /// written if they leave a DC bias beyond the [default limits](LutLimits::default).
+    const QUICK_WAVEFORMS: Option<[Waveform; 3]> = None;
+    /// Deep sleep mode of `sleep` until [set_sleep_mode](Ssd1680::set_sleep_mode)
+    const SLEEP_MODE: DeepSleepMode = DeepSleepMode::Mode1;
+    /// Disables the analog and clock of the controller before the deep sleep
+    const DISABLE_ANALOG_BEFORE_SLEEP: bool = false;
+    /// Fully initializes the controller after a `SwReset` with [RefreshLut::Quick] too,
+    /// otherwise only the quick waveform is loaded and the analog and clock are enabled
+    const QUICK_INIT_SW_RESET: bool = true;
+}
+
+/// Driver for a [Panel] with an SSD1675, SSD1680 or SSD1681 controller
+///
+/// `SINGLE_BYTE_WRITE` sends data one byte at a time, for controllers failing on bulk transfers.
+pub struct Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool = false> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+
+    sleep_mode: DeepSleepMode,
+
+    /// Background Color
+    background_color: Color,
+    refresh_mode: RefreshLut,
+    /// Temperature set with [ExternalTemperature::set_temperature]
+    temperature: Option<i8>,
+    panel: PhantomData<P>,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        // HW reset
+        self.run(spi, delay, &[Step::Reset(P::RESET_DELAY_MS)])?;
+        let quick = self.refresh_mode == RefreshLut::Quick;
+        if quick && !P::QUICK_INIT_SW_RESET {
+            self.set_lut(spi, None, delay)?;
+            return self.run(spi, delay, &sequence::init_quick());
+        }
+
+        let window = RamWindow::full::<P>();
+        self.run(spi, delay, &sequence::init::<P>(quick, &window))?;
+        self.set_lut(spi, None, delay)?;
+        let temperature = self.temperature.map(sequence::temperature);
+        self.run(
+            spi,
+            delay,
+            &sequence::init_end::<P>(quick, temperature.as_ref()),
+        )
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool> Deinit<CS, BUSY, DC, RST>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+{
+    fn deinit(self) -> (CS, BUSY, DC, RST) {
+        self.interface.deinit()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool> PanelInfo
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    P: Panel,
+{
+    const WIDTH: u32 = P::WIDTH;
+    const HEIGHT: u32 = P::HEIGHT;
+    const COLOR_DEPTH: u8 = 1;
+    const IS_BUSY_LOW: bool = IS_BUSY_LOW;
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    type DisplayColor = Color;
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DriverError<SPI, CS, BUSY, DC, RST>> {
+        let mut epd = Ssd1680 {
+            interface: DisplayInterface::new(cs, busy, dc, rst),
+            sleep_mode: P::SLEEP_MODE,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            refresh_mode: RefreshLut::Full,
+            temperature: None,
+            panel: PhantomData,
+        };
+
+        epd.init(spi, delay)?;
+        Ok(epd)
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        debug!("go_to_sleep 0x{:x}", self.sleep_mode as u8);
+        self.run(spi, delay, &sequence::sleep::<P>(self.sleep_mode))
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let window = RamWindow::full::<P>();
+        self.run(
+            spi,
+            delay,
+            &sequence::write_ram(&window, Command::WriteRam, buffer),
+        )?;
+
+        if self.refresh_mode == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.run(
+                spi,
+                delay,
+                &sequence::write_ram(&window, Command::WriteRamRed, buffer),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Never use directly this function when using partial refresh, or also
+    /// keep the base buffer in syncd using `set_partial_base_buffer` function.
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let update_control = self.display_update_control();
+        info!("display_frame() {:x}", update_control.as_u8());
+        self.run(
+            spi,
+            delay,
+            &sequence::display_frame(&[update_control.as_u8()]),
+        )
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.update_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+
+        if self.refresh_mode == RefreshLut::Quick {
+            self.set_partial_base_buffer(spi, buffer, delay)?;
+        }
+        Ok(())
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let color = self.background_color.get_byte_value();
+        let len = buffer_len(P::WIDTH as usize, P::HEIGHT as usize) as u32;
+        let window = RamWindow::full::<P>();
+
+        self.run(
+            spi,
+            delay,
+            &sequence::fill_ram(&window, Command::WriteRam, color, len),
+        )?;
+
+        // Always keep the base buffer equals to current if not doing partial refresh.
+        if self.refresh_mode == RefreshLut::Full {
+            self.run(
+                spi,
+                delay,
+                &sequence::fill_ram(&window, Command::WriteRamRed, color, len),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn set_background_color(&mut self, background_color: Color) {
+        self.background_color = background_color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    fn is_busy(&self) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.is_busy(IS_BUSY_LOW)
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.interface.set_busy_timeout(timeout_ms);
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    PartialUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// With [RefreshLut::Quick] only the new data is written, the controller compares it with
+    /// the base buffer which isn't updated for the window afterwards. Use
+    /// [QuickRefresh::update_partial_old_frame] and [QuickRefresh::update_partial_new_frame]
+    /// to send both.
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = RamWindow::new(x, y, width, height);
+        self.run(
+            spi,
+            delay,
+            &sequence::write_ram(&window, Command::WriteRam, buffer),
+        )?;
+
+        if self.refresh_mode == RefreshLut::Full {
+            // Always keep the base buffer equal to current if not doing partial refresh.
+            self.run(
+                spi,
+                delay,
+                &sequence::write_ram(&window, Command::WriteRamRed, buffer),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    LutSelection<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// Writes the [Waveform] of the current mode (`None`) to the registers, waveforms of the
+    /// OTP are loaded by `display_frame` for every refresh instead.
+    ///
+    /// Another mode than the current one switches with
+    /// [set_refresh_mode](Ssd1680::set_refresh_mode), which re-initializes the display.
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh_rate: Option<RefreshLut>,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        match refresh_rate {
+            Some(refresh_mode) if refresh_mode != self.refresh_mode => {
+                self.set_refresh_mode(spi, delay, refresh_mode)
+            }
+            _ => match self.waveform().map_err(EpdError::Lut)? {
+                Some(waveform) => self.run(spi, delay, &sequence::write_waveform(&waveform)),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    fn begin_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let window = RamWindow::full::<P>();
+        self.run(spi, delay, &sequence::begin_ram(&window, Command::WriteRam))
+    }
+
+    fn write_frame_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.data(spi, data)
+    }
+
+    fn end_frame(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
+    }
+
+    /// Writes the frame as base of quick refreshes as well in full refresh mode
+    fn begin_base_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        if self.refresh_mode != RefreshLut::Full {
+            return Ok(false);
+        }
+        let window = RamWindow::full::<P>();
+        self.run(
+            spi,
+            delay,
+            &sequence::begin_ram(&window, Command::WriteRamRed),
+        )?;
+        Ok(true)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    P: Panel,
+{
+    /// Band of the temperature set with [ExternalTemperature::set_temperature],
+    /// [TemperatureBand::Normal] without one
+    pub fn temperature_band(&self) -> TemperatureBand {
+        self.temperature
+            .map_or(TemperatureBand::Normal, TemperatureBand::from_celsius)
+    }
+
+    /// Selects which sleep mode will be used when triggering the deep sleep.
+    pub fn set_sleep_mode(&mut self, mode: DeepSleepMode) {
+        self.sleep_mode = mode;
+    }
+
+    /// `DisplayUpdateControl2` loading the OTP waveform for the temperature of the sensor, or
+    /// the one written by [ExternalTemperature::set_temperature]
+    fn load_otp_waveform(&self) -> DisplayUpdateControl2 {
+        if self.temperature.is_some() {
+            DisplayUpdateControl2::new().load_lut()
+        } else {
+            DisplayUpdateControl2::new().load_temp().load_lut()
+        }
+    }
+
+    /// `DisplayUpdateControl2` of `display_frame` in the current refresh mode
+    fn display_update_control(&self) -> DisplayUpdateControl2 {
+        match self.refresh_mode {
+            RefreshLut::Full if P::FULL_WAVEFORM.is_some() => DisplayUpdateControl2::new()
+                .enable_clock()
+                .enable_analog()
+                .display()
+                .disable_analog()
+                .disable_clock(),
+            RefreshLut::Full => self
+                .load_otp_waveform()
+                .enable_clock()
+                .enable_analog()
+                .display()
+                .disable_analog()
+                .disable_clock(),
+            // Analog and clock stay enabled since init, display with the register LUT
+            RefreshLut::Quick if P::CONTROLLER == Controller::Ssd1675 => {
+                DisplayUpdateControl2::new().display()
+            }
+            RefreshLut::Quick if P::QUICK_WAVEFORMS.is_some() => {
+                DisplayUpdateControl2::new().display().display_mode2()
+            }
+            RefreshLut::Quick => self
+                .load_otp_waveform()
+                .enable_clock()
+                .enable_analog()
+                .display()
+                .display_mode2(),
+        }
+    }
+
+    /// The validated register [Waveform] of the current mode and temperature band, `None` for
+    /// the OTP
+    fn waveform(&self) -> Result<Option<Waveform>, LutError> {
+        let waveform = match self.refresh_mode {
+            RefreshLut::Full => P::FULL_WAVEFORM,
+            RefreshLut::Quick => {
+                P::QUICK_WAVEFORMS.map(|[cold, normal, hot]| match self.temperature_band() {
+                    TemperatureBand::Cold => cold,
+                    TemperatureBand::Normal => normal,
+                    TemperatureBand::Hot => hot,
+                })
+            }
+        };
+        if let Some(waveform) = &waveform {
+            let lut = match P::CONTROLLER {
+                Controller::Ssd1675 => Lut::ssd1675(waveform.lut),
+                Controller::Ssd1680 | Controller::Ssd1681 => Lut::ssd1680(waveform.lut),
+            };
+            lut.and_then(|lut| lut.validate(&LutLimits::default()))?;
+            debug!(
+                "waveform: driving voltage {:?}, source drive voltages {:?}, vcom 0x{:x}",
+                waveform.gate_voltage, waveform.source_voltages, waveform.vcom
+            );
+        }
+        Ok(waveform)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// When using partial refresh, the controller uses the provided buffer for
+    /// comparison with new buffer.
+    pub fn set_partial_base_buffer(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+        let window = RamWindow::full::<P>();
+        self.run(
+            spi,
+            delay,
+            &sequence::write_ram(&window, Command::WriteRamRed, buffer),
+        )
+    }
+
+    /// Sets the refresh mode. When changing mode, the screen will be
+    /// re-initialized accordingly.
+    pub fn set_refresh_mode(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        refresh_mode: RefreshLut,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        if self.refresh_mode != refresh_mode {
+            self.refresh_mode = refresh_mode;
+            self.init(spi, delay)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn run(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        steps: &[Step<'_>],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.run(spi, delay, IS_BUSY_LOW, steps)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    ExternalTemperature<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// Writes the temperature register (`TemperatureSensorControlWrite`), the OTP waveforms
+    /// aren't loaded for the temperature of the sensor anymore afterwards
+    ///
+    /// With [RefreshLut::Quick] the waveform of the new [TemperatureBand] is uploaded if the
+    /// band changes.
+    fn set_temperature(
+        &mut self,
+        spi: &mut SPI,
+        celsius: i8,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let band = self.temperature_band();
+        self.temperature = Some(celsius);
+        debug!("temperature {}", celsius);
+        let temperature = sequence::temperature(celsius);
+        self.run(spi, delay, &sequence::set_temperature(&temperature))?;
+        if self.refresh_mode == RefreshLut::Quick && self.temperature_band() != band {
+            self.set_lut(spi, None, delay)?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// Writes the base buffer the new frame is compared with (`WriteRamRed`)
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.set_partial_base_buffer(spi, buffer, delay)
+    }
+
+    /// Writes the new frame, the old one needs to be sent with `update_old_frame` before
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+        let window = RamWindow::full::<P>();
+        self.run(
+            spi,
+            delay,
+            &sequence::write_ram(&window, Command::WriteRam, buffer),
+        )
+    }
+
+    /// Wrapper around `display_frame`, the driver needs to be in [RefreshLut::Quick]
+    /// (see [set_refresh_mode](Ssd1680::set_refresh_mode)) for a quick refresh.
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.display_frame(spi, delay)
+    }
+
+    /// Wrapper around `update_new_frame` and `display_frame`
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    /// Writes a window of the base buffer (`WriteRamRed`)
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = RamWindow::new(x, y, width, height);
+        self.run(
+            spi,
+            delay,
+            &sequence::write_ram(&window, Command::WriteRamRed, buffer),
+        )
+    }
+
+    /// Writes a window of the new frame, the old one needs to be sent with
+    /// `update_partial_old_frame` before
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = RamWindow::new(x, y, width, height);
+        self.run(
+            spi,
+            delay,
+            &sequence::write_ram(&window, Command::WriteRam, buffer),
+        )
+    }
+
+    /// Fills a window of the new frame with the background color
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+
+        let color = self.background_color.get_byte_value();
+        let len = buffer_len(width as usize, height as usize) as u32;
+        let window = RamWindow::new(x, y, width, height);
+        self.run(
+            spi,
+            delay,
+            &sequence::fill_ram(&window, Command::WriteRam, color, len),
+        )
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P, const SINGLE_BYTE_WRITE: bool>
+    FullRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, P, SINGLE_BYTE_WRITE>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// Writes `buffer` to both RAMs and refreshes it with the full waveform
+    ///
+    /// In [RefreshLut::Quick] the waveforms are swapped without the re-initialization of
+    /// [set_refresh_mode](Ssd1680::set_refresh_mode).
+    fn full_refresh_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        if self.refresh_mode == RefreshLut::Full {
+            self.update_and_display_frame(spi, buffer, delay)?;
+            return self.set_refresh_mode(spi, delay, RefreshLut::Quick);
+        }
+
+        let window = RamWindow::full::<P>();
+        self.run(
+            spi,
+            delay,
+            &sequence::write_ram(&window, Command::WriteRam, buffer),
+        )?;
+        self.set_partial_base_buffer(spi, buffer, delay)?;
+        self.run(spi, delay, &sequence::full_refresh_begin())?;
+
+        self.refresh_mode = RefreshLut::Full;
+        let refreshed = self
+            .set_lut(spi, None, delay)
+            .and_then(|()| self.display_frame(spi, delay));
+        self.refresh_mode = RefreshLut::Quick;
+        refreshed?;
+
+        self.set_lut(spi, None, delay)?;
+        self.run(spi, delay, &sequence::full_refresh_end::<P>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::ssd1680::FULL_REFRESH_MS;
+    use crate::simulator::{Plane, SimulatedPanel};
+
+    #[test]
+    fn voltages() {
+        assert_eq!(vcom(-21), 0x54);
+        assert_eq!(gate_driving_decivolt(190), 0x15);
+        assert_eq!(source_driving_decivolt(150), 0x41);
+        assert_eq!(source_driving_decivolt(50), 0xA8);
+        assert_eq!(source_driving_decivolt(-150), 0x32);
+    }
+
+    #[test]
+    fn refuse_invalid_waveforms() {
+        use crate::lut::LutError;
+
+        struct Truncated;
+        const TRUNCATED: Waveform = Waveform {
+            lut: &[0; 70],
+            end_option: None,
+            gate_voltage: None,
+            source_voltages: None,
+            vcom: 0x24,
+        };
+        impl Panel for Truncated {
+            const WIDTH: u32 = 128;
+            const HEIGHT: u32 = 296;
+            const CONTROLLER: Controller = Controller::Ssd1680;
+            const QUICK_WAVEFORMS: Option<[Waveform; 3]> = Some([TRUNCATED; 3]);
+        }
+
+        let panel = SimulatedPanel::ssd1680(128, 296);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Ssd1680<_, _, _, _, _, _, Truncated>>();
+        assert_eq!(
+            epd.set_refresh_mode(&mut spi, &mut delay, RefreshLut::Quick),
+            Err(EpdError::Lut(LutError::Length {
+                expected: 153,
+                actual: 70
+            }))
+        );
+        assert_eq!(panel.lut(Command::WriteLutRegister as u8), None);
+    }
+
+    #[test]
+    fn otp_preset() {
+        // 1.54" with 200x200 pixels, both refreshes with the waveforms of the OTP
+        struct Gdey0154d67;
+        impl Panel for Gdey0154d67 {
+            const WIDTH: u32 = 200;
+            const HEIGHT: u32 = 200;
+            const CONTROLLER: Controller = Controller::Ssd1681;
+        }
+        type Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> =
+            Ssd1680<SPI, CS, BUSY, DC, RST, DELAY, Gdey0154d67>;
+
+        let panel = SimulatedPanel::ssd1680(200, 200);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd1in54<_, _, _, _, _, _>>();
+        assert_eq!((epd.width(), epd.height()), (200, 200));
+
+        let white = std::vec![0xFF; buffer_len(200, 200)];
+        epd.update_and_display_frame(&mut spi, &white, &mut delay)
+            .unwrap();
+        assert_eq!(panel.ram(Plane::Old), white);
+
+        // Display mode 2 of the OTP, the register LUT stays untouched
+        epd.set_refresh_mode(&mut spi, &mut delay, RefreshLut::Quick)
+            .unwrap();
+        let start = panel.elapsed_ms();
+        epd.update_partial_new_frame(&mut spi, &[0x00; 3 * 2], 176, 190, 24, 2, &mut delay)
+            .unwrap();
+        epd.display_new_frame(&mut spi, &mut delay).unwrap();
+        assert!(panel.elapsed_ms() - start < u64::from(FULL_REFRESH_MS));
+        assert!(panel.image().is_black_window(176, 190, 24, 2));
+        assert_eq!(panel.lut(0x32), None);
+        assert_eq!(panel.violations(), []);
+    }
+}
@@ -0,0 +1,313 @@
+//! Command sequences of the SSD1675, SSD1680 and SSD1681, shared by the blocking and the async
+//! driver
+
+use core::slice;
+
+use super::command::{
+    BorderWaveForm, BorderWaveFormFixLevel, BorderWaveFormGs, BorderWaveFormVbd, Command,
+    DataEntryModeDir, DataEntryModeIncr, DeepSleepMode, DisplayUpdateControl2, DriverOutput,
+};
+use super::{Controller, Panel, Waveform};
+use crate::sequence::{Step, Steps};
+
+/// Data of [Command::DriverOutputControl]
+const fn driver_output(height: u32) -> [u8; 3] {
+    DriverOutput {
+        scan_is_linear: true,
+        scan_g0_is_first: true,
+        scan_dir_incr: true,
+        width: (height - 1) as u16,
+    }
+    .to_bytes()
+}
+
+/// Simple X/Y auto increase
+const DATA_ENTRY_MODE: [u8; 1] =
+    [DataEntryModeIncr::XIncrYIncr as u8 | DataEntryModeDir::XDir as u8];
+
+/// Border of full refreshes
+const BORDER_FULL: [u8; 1] = [BorderWaveForm {
+    vbd: BorderWaveFormVbd::Gs,
+    fix_level: BorderWaveFormFixLevel::Vss,
+    gs_trans: BorderWaveFormGs::Lut3,
+}
+.to_u8()];
+
+/// Keeps the border at VCOM, otherwise it flickers with every partial update
+const BORDER_QUICK: [u8; 1] = [BorderWaveForm {
+    vbd: BorderWaveFormVbd::Vcom,
+    fix_level: BorderWaveFormFixLevel::Vss,
+    gs_trans: BorderWaveFormGs::Lut0,
+}
+.to_u8()];
+
+/// Border of quick refreshes of panels without [Panel::QUICK_INIT_SW_RESET]
+const BORDER_QUICK_INIT: [u8; 1] = [BorderWaveForm {
+    vbd: BorderWaveFormVbd::Gs,
+    fix_level: BorderWaveFormFixLevel::Vss,
+    gs_trans: BorderWaveFormGs::Lut1,
+}
+.to_u8()];
+
+/// During partial update, clock/analog are not disabled between 2 updates.
+const ENABLE_ANALOG_AND_CLOCK: [u8; 1] = [DisplayUpdateControl2::new()
+    .enable_analog()
+    .enable_clock()
+    .as_u8()];
+
+const DISABLE_ANALOG_AND_CLOCK: [u8; 1] = [DisplayUpdateControl2::new()
+    .enable_analog()
+    .enable_clock()
+    .disable_analog()
+    .disable_clock()
+    .as_u8()];
+
+/// Data of the RAM address commands for a window
+pub(super) struct RamWindow {
+    x_range: [u8; 2],
+    y_range: [u8; 4],
+    x_counter: [u8; 1],
+    y_counter: [u8; 2],
+}
+
+impl RamWindow {
+    pub(super) fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        let end_x = x + width - 1;
+        let end_y = y + height - 1;
+        RamWindow {
+            x_range: [(x >> 3) as u8, (end_x >> 3) as u8],
+            y_range: [y as u8, (y >> 8) as u8, end_y as u8, (end_y >> 8) as u8],
+            x_counter: [(x >> 3) as u8],
+            y_counter: [y as u8, (y >> 8) as u8],
+        }
+    }
+
+    /// The whole display
+    pub(super) fn full<P: Panel>() -> Self {
+        Self::new(0, 0, P::WIDTH, P::HEIGHT)
+    }
+}
+
+/// Sets the X and Y ranges and the counters to the start of `window`
+fn set_ram_window(window: &RamWindow) -> [Step<'_>; 9] {
+    [
+        Step::cmd(Command::SetRamXAddressStartEndPosition),
+        Step::Data(&window.x_range),
+        Step::cmd(Command::SetRamYAddressStartEndPosition),
+        Step::Data(&window.y_range),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::SetRamXAddressCounter),
+        Step::Data(&window.x_counter),
+        Step::cmd(Command::SetRamYAddressCounter),
+        Step::Data(&window.y_counter),
+    ]
+}
+
+/// Starts writing `ram` (`WriteRam` or `WriteRamRed`) at the start of `window`
+pub(super) fn begin_ram(window: &RamWindow, ram: Command) -> Steps<'_, 10> {
+    let mut steps = Steps::new();
+    steps.extend(&set_ram_window(window));
+    steps.push(Step::cmd(ram));
+    steps
+}
+
+/// Writes `data` to the `window` of `ram`
+pub(super) fn write_ram<'a>(window: &'a RamWindow, ram: Command, data: &'a [u8]) -> Steps<'a, 11> {
+    let mut steps = Steps::new();
+    steps.extend(&begin_ram(window, ram));
+    steps.push(Step::Data(data));
+    steps
+}
+
+/// Fills the `window` of `ram` with `bytes` times `color`
+pub(super) fn fill_ram(window: &RamWindow, ram: Command, color: u8, bytes: u32) -> Steps<'_, 11> {
+    let mut steps = Steps::new();
+    steps.extend(&begin_ram(window, ram));
+    steps.push(Step::DataRepeat(color, bytes));
+    steps
+}
+
+/// Initialization after the hardware reset, up to the waveform
+pub(super) fn init<P: Panel>(quick: bool, window: &RamWindow) -> Steps<'_, 24> {
+    let mut steps = Steps::new();
+    steps.extend(&[
+        Step::WaitUntilIdle,
+        Step::cmd(Command::SwReset),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DriverOutputControl),
+        Step::Data(&const { driver_output(P::HEIGHT) }),
+    ]);
+    if P::CONTROLLER == Controller::Ssd1675 {
+        // These 2 are the reset values
+        steps.extend(&[
+            Step::cmd(Command::SetDummyLinePeriod),
+            Step::Data(&[0x30]),
+            Step::cmd(Command::GateScanStartPosition),
+            Step::Data(&[0, 0]),
+        ]);
+    }
+    steps.extend(&[
+        Step::cmd(Command::DataEntryModeSetting),
+        Step::Data(&DATA_ENTRY_MODE),
+    ]);
+    steps.extend(&set_ram_window(window));
+    steps.push(Step::cmd(Command::BorderWaveformControl));
+    steps.push(Step::Data(if quick { &BORDER_QUICK } else { &BORDER_FULL }));
+    if P::CONTROLLER == Controller::Ssd1675 {
+        steps.extend(&[Step::cmd(Command::SetGateLineWidth), Step::Data(&[10])]);
+    }
+    steps
+}
+
+/// Initialization after the waveform, `temperature` as written by [set_temperature]
+pub(super) fn init_end<P: Panel>(quick: bool, temperature: Option<&[u8; 2]>) -> Steps<'_, 9> {
+    let mut steps = Steps::new();
+    if quick {
+        steps.extend(&[
+            Step::cmd(Command::DisplayUpdateControl2),
+            Step::Data(&ENABLE_ANALOG_AND_CLOCK),
+            Step::cmd(Command::MasterActivation),
+            Step::WaitUntilIdle,
+        ]);
+    } else if P::CONTROLLER != Controller::Ssd1675 {
+        steps.extend(&[
+            Step::cmd(Command::DisplayUpdateControl1),
+            Step::Data(&[0, 0x80]),
+        ]);
+    }
+    if P::CONTROLLER != Controller::Ssd1675 {
+        // Internal temperature sensor
+        steps.extend(&[
+            Step::cmd(Command::TemperatureSensorControl),
+            Step::Data(&[0x80]),
+        ]);
+    }
+    if let Some(temperature) = temperature {
+        steps.extend(&[
+            Step::cmd(Command::TemperatureSensorControlWrite),
+            Step::Data(temperature),
+        ]);
+    }
+    steps.push(Step::WaitUntilIdle);
+    steps
+}
+
+/// Initialization with the quick refresh of panels without [Panel::QUICK_INIT_SW_RESET], after
+/// the waveform
+pub(super) fn init_quick() -> [Step<'static>; 7] {
+    [
+        Step::cmd(Command::DisplayUpdateControl2),
+        Step::Data(&ENABLE_ANALOG_AND_CLOCK),
+        Step::cmd(Command::MasterActivation),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::BorderWaveformControl),
+        Step::Data(&BORDER_QUICK_INIT),
+        Step::WaitUntilIdle,
+    ]
+}
+
+/// Full refresh in between quick refreshes, before the full waveform
+pub(super) fn full_refresh_begin() -> [Step<'static>; 2] {
+    [
+        Step::cmd(Command::BorderWaveformControl),
+        Step::Data(&BORDER_FULL),
+    ]
+}
+
+/// Back to quick refreshes after a full refresh, after the quick waveform
+///
+/// The full refresh disabled analog and clock, the quick refreshes expect them enabled.
+pub(super) fn full_refresh_end<P: Panel>() -> [Step<'static>; 6] {
+    [
+        Step::cmd(Command::DisplayUpdateControl2),
+        Step::Data(&ENABLE_ANALOG_AND_CLOCK),
+        Step::cmd(Command::MasterActivation),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::BorderWaveformControl),
+        Step::Data(if P::QUICK_INIT_SW_RESET {
+            &BORDER_QUICK
+        } else {
+            &BORDER_QUICK_INIT
+        }),
+    ]
+}
+
+/// Writes a waveform to the registers, it has to be validated before
+pub(super) fn write_waveform(waveform: &Waveform) -> Steps<'_, 11> {
+    let mut steps = Steps::new();
+    steps.extend(&[
+        Step::WaitUntilIdle,
+        Step::cmd(Command::WriteLutRegister),
+        Step::Data(waveform.lut),
+    ]);
+    if let Some(end_option) = &waveform.end_option {
+        steps.extend(&[
+            Step::cmd(Command::EndOption),
+            Step::Data(slice::from_ref(end_option)),
+        ]);
+    }
+    if let Some(voltage) = &waveform.gate_voltage {
+        steps.extend(&[
+            Step::cmd(Command::GateDrivingVoltageCtrl),
+            Step::Data(slice::from_ref(voltage)),
+        ]);
+    }
+    if let Some(voltages) = &waveform.source_voltages {
+        steps.extend(&[
+            Step::cmd(Command::SourceDrivingVoltageCtrl),
+            Step::Data(voltages),
+        ]);
+    }
+    steps.extend(&[
+        Step::cmd(Command::WriteVcomRegister),
+        Step::Data(slice::from_ref(&waveform.vcom)),
+    ]);
+    steps
+}
+
+/// Refreshes the display with the actions of `update_control` (`DisplayUpdateControl2`)
+pub(super) fn display_frame(update_control: &[u8; 1]) -> [Step<'_>; 4] {
+    [
+        Step::cmd(Command::DisplayUpdateControl2),
+        Step::Data(update_control),
+        Step::cmd(Command::MasterActivation),
+        Step::WaitUntilIdle,
+    ]
+}
+
+pub(super) fn sleep<P: Panel>(mode: DeepSleepMode) -> Steps<'static, 7> {
+    let mut steps = Steps::new();
+    steps.push(Step::WaitUntilIdle);
+    if P::DISABLE_ANALOG_BEFORE_SLEEP {
+        steps.extend(&[
+            Step::cmd(Command::DisplayUpdateControl2),
+            Step::Data(&DISABLE_ANALOG_AND_CLOCK),
+            Step::cmd(Command::MasterActivation),
+        ]);
+    }
+    steps.extend(&[
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DeepSleepMode),
+        Step::Data(match mode {
+            DeepSleepMode::Normal => &[DeepSleepMode::Normal as u8],
+            DeepSleepMode::Mode1 => &[DeepSleepMode::Mode1 as u8],
+            DeepSleepMode::Mode2 => &[DeepSleepMode::Mode2 as u8],
+        }),
+    ]);
+    steps
+}
+
+/// Data of the temperature register: whole °C in the first byte, no 1/16 °C fraction in the
+/// second one
+pub(super) fn temperature(celsius: i8) -> [u8; 2] {
+    [celsius as u8, 0x00]
+}
+
+/// Makes the controller use `temperature` (see [temperature]) instead of its sensor
+pub(super) fn set_temperature(temperature: &[u8; 2]) -> [Step<'_>; 3] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::TemperatureSensorControlWrite),
+        Step::Data(temperature),
+    ]
+}
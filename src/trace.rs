@@ -0,0 +1,981 @@
+//! Recording and decoding of the communication with a panel
+//!
+//! A [Recorder] wraps the SPI bus, pins and delay handed to a driver and records what happens on
+//! them, like a logic analyzer would: every SPI write with the level of DC, CS edges, reset
+//! pulses, reads of the busy pin and delays. [decode] turns the [Event]s into [Transaction]s,
+//! which print the commands with the names of the driver's `Command` enum and decode the
+//! parameters of the window, resolution, data entry, temperature and sleep commands of the
+//! UC8151 and SSD1680 family:
+//!
+//!```text
+//!RESET low for 20 ms
+//!delay 200 ms
+//!uc8151::command::Command::PowerSetting [03 00 2B 2B 03]
+//!uc8151::command::Command::BoosterSoftStart [17 17 17]
+//!uc8151::command::Command::PowerOn
+//!delay 5 ms
+//!BUSY low, read 75 times over 75 ms
+//!BUSY high
+//!uc8151::command::Command::PanelSetting (LUT from registers, B/W) [3F]
+//!...
+//!uc8151::command::Command::PartialWindow (x 8..=23, y 10..=13, scan inside window) [00 08 00 17 00 0A 00 0D 01]
+//!uc8151::command::Command::DataStartTransmission2 [00 00 00 00 00 00 00 00]
+//!```
+//!
+//! Only available with the `std` feature.
+//!
+//! # Example
+//!
+//!```rust, no_run
+//!# use embedded_hal_mock::*;
+//!# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
+//!use epd_waveshare::{
+//!    epd2in13_GDEW0213T5D::*,
+//!    prelude::*,
+//!    trace::{CommandSet, Recorder},
+//!};
+//!#
+//!# let expectations = [];
+//!# let spi = spi::Mock::new(&expectations);
+//!# let expectations = [];
+//!# let cs_pin = pin::Mock::new(&expectations);
+//!# let busy_in = pin::Mock::new(&expectations);
+//!# let dc = pin::Mock::new(&expectations);
+//!# let rst = pin::Mock::new(&expectations);
+//!# let delay = delay::MockNoop::new();
+//!
+//!let recorder = Recorder::new();
+//!let (mut spi, mut delay) = (recorder.spi(spi), recorder.delay(delay));
+//!let (cs, busy, dc, rst) = (recorder.cs(cs_pin), recorder.busy(busy_in), recorder.dc(dc), recorder.rst(rst));
+//!
+//!let mut epd = Epd2in13_T5D::new(&mut spi, cs, busy, dc, rst, &mut delay)?;
+//!epd.clear_frame(&mut spi, &mut delay)?;
+//!print!("{}", recorder.decode(CommandSet::Uc8151));
+//!# Ok(())
+//!# }
+//!```
+
+use core::any::type_name;
+use core::cell::RefCell;
+use core::fmt;
+use embedded_hal::{
+    blocking::{
+        delay::{DelayMs, DelayUs},
+        spi::Write,
+    },
+    digital::v2::{InputPin, OutputPin},
+};
+use std::rc::Rc;
+use std::string::String;
+use std::vec::Vec;
+use std::{format, vec};
+
+use crate::ssd1680::command::Command as Ssd1680Command;
+use crate::uc8151::command::Command as Uc8151Command;
+
+/// Data bytes printed before the rest is abbreviated
+const PRINTED_DATA: usize = 16;
+
+/// Something that happened on the bus, pins or delay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// CS was set low
+    Select,
+    /// CS was set high
+    Deselect,
+    /// One SPI write, commands are sent with DC low and data with DC high
+    Write {
+        /// Level of DC during the write
+        dc_high: bool,
+        /// The bytes written
+        bytes: Vec<u8>,
+    },
+    /// RST was set low and kept low for `low_us` of delays
+    Reset {
+        /// Delays while RST was low in µs
+        low_us: u64,
+    },
+    /// The busy pin was read with the same level `reads` times in a row
+    Busy {
+        /// Level of the busy pin
+        high: bool,
+        /// Number of reads
+        reads: u32,
+        /// Delays between the reads in µs
+        waited_us: u64,
+    },
+    /// Delays in a row outside of reset pulses and busy waits
+    Delay {
+        /// Duration in µs
+        us: u64,
+    },
+}
+
+struct State {
+    events: Vec<Event>,
+    dc_high: bool,
+    rst_low: bool,
+}
+
+impl State {
+    fn push_delay(&mut self, us: u64) {
+        if self.rst_low {
+            if let Some(Event::Reset { low_us }) = self
+                .events
+                .iter_mut()
+                .rev()
+                .find(|event| matches!(event, Event::Reset { .. }))
+            {
+                *low_us += us;
+                return;
+            }
+        }
+        match self.events.last_mut() {
+            Some(Event::Delay { us: delayed }) => *delayed += us,
+            _ => self.events.push(Event::Delay { us }),
+        }
+    }
+
+    fn push_busy(&mut self, high: bool) {
+        // A delay between two reads belongs to the busy wait of the first one
+        if let [.., Event::Busy { waited_us, .. }, Event::Delay { us }] = self.events.as_mut_slice()
+        {
+            *waited_us += *us;
+            self.events.pop();
+        }
+        match self.events.last_mut() {
+            Some(Event::Busy {
+                high: last, reads, ..
+            }) if *last == high => *reads += 1,
+            _ => self.events.push(Event::Busy {
+                high,
+                reads: 1,
+                waited_us: 0,
+            }),
+        }
+    }
+}
+
+/// Records the communication of a driver, see the [module documentation](self)
+///
+/// All wrappers share the recording, so it can be inspected while the driver owns them.
+#[derive(Clone)]
+pub struct Recorder {
+    state: Rc<RefCell<State>>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Recorder {
+    /// Starts an empty recording
+    pub fn new() -> Self {
+        Recorder {
+            state: Rc::new(RefCell::new(State {
+                events: Vec::new(),
+                dc_high: false,
+                rst_low: false,
+            })),
+        }
+    }
+
+    /// Records the writes to `spi`
+    pub fn spi<SPI>(&self, spi: SPI) -> TracedSpi<SPI> {
+        TracedSpi {
+            inner: spi,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Records the edges of the chip select pin
+    pub fn cs<P>(&self, pin: P) -> TracedPin<P> {
+        self.pin(pin, PinRole::Cs)
+    }
+
+    /// Records the reads of the busy pin
+    pub fn busy<P>(&self, pin: P) -> TracedPin<P> {
+        self.pin(pin, PinRole::Busy)
+    }
+
+    /// Records the level of the data/command pin with the SPI writes
+    pub fn dc<P>(&self, pin: P) -> TracedPin<P> {
+        self.pin(pin, PinRole::Dc)
+    }
+
+    /// Records the pulses of the reset pin
+    pub fn rst<P>(&self, pin: P) -> TracedPin<P> {
+        self.pin(pin, PinRole::Rst)
+    }
+
+    /// Records the delays
+    pub fn delay<D>(&self, delay: D) -> TracedDelay<D> {
+        TracedDelay {
+            inner: delay,
+            state: self.state.clone(),
+        }
+    }
+
+    fn pin<P>(&self, pin: P, role: PinRole) -> TracedPin<P> {
+        TracedPin {
+            inner: pin,
+            state: self.state.clone(),
+            role,
+        }
+    }
+
+    /// Everything recorded so far
+    pub fn events(&self) -> Vec<Event> {
+        self.state.borrow().events.clone()
+    }
+
+    /// Drops the recorded events, e.g. to only look at the next refresh
+    pub fn clear(&self) {
+        self.state.borrow_mut().events.clear();
+    }
+
+    /// Decodes the recorded events, see [decode]
+    pub fn decode(&self, commands: CommandSet) -> Decoded {
+        decode(&self.state.borrow().events, commands)
+    }
+}
+
+/// SPI bus wrapped by a [Recorder]
+pub struct TracedSpi<SPI> {
+    inner: SPI,
+    state: Rc<RefCell<State>>,
+}
+
+impl<SPI> TracedSpi<SPI> {
+    /// Returns the wrapped bus
+    pub fn into_inner(self) -> SPI {
+        self.inner
+    }
+}
+
+impl<SPI: Write<u8>> Write<u8> for TracedSpi<SPI> {
+    type Error = SPI::Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        let dc_high = state.dc_high;
+        state.events.push(Event::Write {
+            dc_high,
+            bytes: words.to_vec(),
+        });
+        drop(state);
+        self.inner.write(words)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PinRole {
+    Cs,
+    Busy,
+    Dc,
+    Rst,
+}
+
+/// Pin wrapped by a [Recorder]
+pub struct TracedPin<P> {
+    inner: P,
+    state: Rc<RefCell<State>>,
+    role: PinRole,
+}
+
+impl<P> TracedPin<P> {
+    /// Returns the wrapped pin
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: OutputPin> OutputPin for TracedPin<P> {
+    type Error = P::Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        match self.role {
+            PinRole::Cs => state.events.push(Event::Select),
+            PinRole::Dc => state.dc_high = false,
+            PinRole::Rst => {
+                if !state.rst_low {
+                    state.events.push(Event::Reset { low_us: 0 });
+                }
+                state.rst_low = true;
+            }
+            PinRole::Busy => {}
+        }
+        drop(state);
+        self.inner.set_low()
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        let mut state = self.state.borrow_mut();
+        match self.role {
+            PinRole::Cs => state.events.push(Event::Deselect),
+            PinRole::Dc => state.dc_high = true,
+            PinRole::Rst => state.rst_low = false,
+            PinRole::Busy => {}
+        }
+        drop(state);
+        self.inner.set_high()
+    }
+}
+
+impl<P: InputPin> InputPin for TracedPin<P> {
+    type Error = P::Error;
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        let high = self.inner.is_high()?;
+        if self.role == PinRole::Busy {
+            self.state.borrow_mut().push_busy(high);
+        }
+        Ok(high)
+    }
+
+    fn is_low(&self) -> Result<bool, Self::Error> {
+        self.is_high().map(|high| !high)
+    }
+}
+
+/// Delay wrapped by a [Recorder]
+pub struct TracedDelay<D> {
+    inner: D,
+    state: Rc<RefCell<State>>,
+}
+
+impl<D> TracedDelay<D> {
+    /// Returns the wrapped delay
+    pub fn into_inner(self) -> D {
+        self.inner
+    }
+}
+
+impl<D: DelayUs<u32>> DelayUs<u32> for TracedDelay<D> {
+    fn delay_us(&mut self, us: u32) {
+        self.state.borrow_mut().push_delay(u64::from(us));
+        self.inner.delay_us(us);
+    }
+}
+
+impl<D: DelayMs<u8>> DelayMs<u8> for TracedDelay<D> {
+    fn delay_ms(&mut self, ms: u8) {
+        self.state.borrow_mut().push_delay(u64::from(ms) * 1000);
+        self.inner.delay_ms(ms);
+    }
+}
+
+impl<D: DelayMs<u16>> DelayMs<u16> for TracedDelay<D> {
+    fn delay_ms(&mut self, ms: u16) {
+        self.state.borrow_mut().push_delay(u64::from(ms) * 1000);
+        self.inner.delay_ms(ms);
+    }
+}
+
+impl<D: DelayMs<u32>> DelayMs<u32> for TracedDelay<D> {
+    fn delay_ms(&mut self, ms: u32) {
+        self.state.borrow_mut().push_delay(u64::from(ms) * 1000);
+        self.inner.delay_ms(ms);
+    }
+}
+
+/// The `Command` enum to name the commands of a trace with
+///
+/// Presets use the commands of their controller, e.g. the GDEW0213T5D those of [Uc8151](CommandSet::Uc8151).
+/// Parameters are decoded for [Uc8151](CommandSet::Uc8151), [Ssd1680](CommandSet::Ssd1680)
+/// and [TypeA](CommandSet::TypeA), the other sets only name the commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandSet {
+    /// [epd1in54](crate::epd1in54), [epd2in9](crate::epd2in9) and [epd2in9_v2](crate::epd2in9_v2)
+    TypeA,
+    /// [epd1in54b](crate::epd1in54b)
+    Epd1in54b,
+    /// [epd1in54c](crate::epd1in54c)
+    Epd1in54c,
+    /// [epd2in13bc](crate::epd2in13bc)
+    Epd2in13bc,
+    /// [epd2in7b](crate::epd2in7b)
+    Epd2in7b,
+    /// [epd2in9bc](crate::epd2in9bc)
+    Epd2in9bc,
+    /// [epd4in2](crate::epd4in2)
+    Epd4in2,
+    /// [epd5in65f](crate::epd5in65f)
+    Epd5in65f,
+    /// [epd7in5](crate::epd7in5)
+    Epd7in5,
+    /// [epd7in5_hd](crate::epd7in5_hd)
+    Epd7in5Hd,
+    /// [epd7in5_v2](crate::epd7in5_v2)
+    Epd7in5V2,
+    /// [ssd1680](crate::ssd1680) with the [epd2in13_v2](crate::epd2in13_v2) and
+    /// [epd2in13_GDEQ0213B74](crate::epd2in13_GDEQ0213B74) presets
+    Ssd1680,
+    /// [uc8151](crate::uc8151) with the [epd2in13_GDEW0213T5D](crate::epd2in13_GDEW0213T5D),
+    /// [epd2in13d](crate::epd2in13d) and [epd2in9d](crate::epd2in9d) presets
+    Uc8151,
+}
+
+/// Path of a `Command` enum in the crate
+fn path<T>() -> &'static str {
+    let name = type_name::<T>();
+    name.strip_prefix("epd_waveshare::").unwrap_or(name)
+}
+
+macro_rules! name {
+    ($command:ty, $address:expr) => {
+        <$command>::from_address($address)
+            .map(|command| format!("{}::{:?}", path::<$command>(), command))
+    };
+}
+
+impl CommandSet {
+    /// Name of the command with `address`, e.g. `uc8151::command::Command::PartialWindow`
+    pub fn name(self, address: u8) -> Option<String> {
+        match self {
+            CommandSet::TypeA => name!(crate::type_a::command::Command, address),
+            CommandSet::Epd1in54b => name!(crate::epd1in54b::command::Command, address),
+            CommandSet::Epd1in54c => name!(crate::epd1in54c::command::Command, address),
+            CommandSet::Epd2in13bc => name!(crate::epd2in13bc::command::Command, address),
+            CommandSet::Epd2in7b => name!(crate::epd2in7b::command::Command, address),
+            CommandSet::Epd2in9bc => name!(crate::epd2in9bc::command::Command, address),
+            CommandSet::Epd4in2 => name!(crate::epd4in2::command::Command, address),
+            CommandSet::Epd5in65f => name!(crate::epd5in65f::command::Command, address),
+            CommandSet::Epd7in5 => name!(crate::epd7in5::command::Command, address),
+            CommandSet::Epd7in5Hd => name!(crate::epd7in5_hd::command::Command, address),
+            CommandSet::Epd7in5V2 => name!(crate::epd7in5_v2::command::Command, address),
+            CommandSet::Ssd1680 => name!(Ssd1680Command, address),
+            CommandSet::Uc8151 => name!(Uc8151Command, address),
+        }
+    }
+
+    /// Decoded parameters of the command with `address`, if the data has the expected length
+    fn parameters(self, address: u8, data: &[u8]) -> Option<String> {
+        match self {
+            CommandSet::Uc8151 => uc8151_parameters(Uc8151Command::from_address(address)?, data),
+            // Type A controllers (SSD1608, IL3820) encode these commands like the SSD1680
+            CommandSet::TypeA => {
+                crate::type_a::command::Command::from_address(address)?;
+                ssd1680_parameters(Ssd1680Command::from_address(address)?, data)
+            }
+            CommandSet::Ssd1680 => ssd1680_parameters(Ssd1680Command::from_address(address)?, data),
+            _ => None,
+        }
+    }
+}
+
+fn uc8151_parameters(command: Uc8151Command, data: &[u8]) -> Option<String> {
+    let u16_at = |i: usize| u16::from(data[i]) << 8 | u16::from(data[i + 1]);
+    Some(match (command, data.len()) {
+        (Uc8151Command::PanelSetting, 1) => format!(
+            "LUT from {}, {}",
+            if data[0] & 0x20 != 0 {
+                "registers"
+            } else {
+                "OTP"
+            },
+            if data[0] & 0x10 != 0 { "B/W" } else { "B/W/R" },
+        ),
+        (Uc8151Command::ResolutionSetting, 3) => {
+            format!(
+                "{} × {}",
+                data[0],
+                u16::from(data[1] & 0x01) << 8 | u16::from(data[2])
+            )
+        }
+        (Uc8151Command::ResolutionSetting, 4) => format!("{} × {}", u16_at(0), u16_at(2)),
+        (Uc8151Command::PartialWindow, 7) => format!(
+            "x {}..={}, y {}..={}, {}",
+            data[0],
+            data[1],
+            u16::from(data[2] & 0x01) << 8 | u16::from(data[3]),
+            u16::from(data[4] & 0x01) << 8 | u16::from(data[5]),
+            partial_scan(data[6]),
+        ),
+        // IL0398 layout with 16 bit coordinates
+        (Uc8151Command::PartialWindow, 9) => format!(
+            "x {}..={}, y {}..={}, {}",
+            u16_at(0),
+            u16_at(2),
+            u16_at(4),
+            u16_at(6),
+            partial_scan(data[8]),
+        ),
+        (Uc8151Command::DeepSleep, 1) if data[0] == 0xA5 => String::from("check code"),
+        (Uc8151Command::DeepSleep, 1) => String::from("wrong check code, ignored"),
+        (Uc8151Command::CascadeSetting, 1) if data[0] & 0x02 != 0 => {
+            String::from("forced temperature")
+        }
+        (Uc8151Command::CascadeSetting, 1) => String::from("temperature sensor"),
+        (Uc8151Command::ForceTemperature, 1) => format!("{} °C", data[0] as i8),
+        _ => return None,
+    })
+}
+
+fn partial_scan(pt_scan: u8) -> &'static str {
+    if pt_scan & 0x01 != 0 {
+        "scan inside window"
+    } else {
+        "scan whole panel"
+    }
+}
+
+fn ssd1680_parameters(command: Ssd1680Command, data: &[u8]) -> Option<String> {
+    let u9_at = |i: usize| u16::from(data[i + 1] & 0x01) << 8 | u16::from(data[i]);
+    Some(match (command, data.len()) {
+        (Ssd1680Command::DriverOutputControl, 3) => format!("{} gates", u9_at(0) + 1),
+        (Ssd1680Command::DataEntryModeSetting, 1) => format!(
+            "X {}, Y {}, counter moves in {} direction",
+            if data[0] & 0x01 != 0 {
+                "increments"
+            } else {
+                "decrements"
+            },
+            if data[0] & 0x02 != 0 {
+                "increments"
+            } else {
+                "decrements"
+            },
+            if data[0] & 0x04 != 0 { "Y" } else { "X" },
+        ),
+        (Ssd1680Command::DeepSleepMode, 1) => String::from(match data[0] {
+            0x00 => "normal mode",
+            0x01 => "deep sleep mode 1",
+            _ => "deep sleep mode 2",
+        }),
+        (Ssd1680Command::TemperatureSensorControlWrite, 1..=2) => {
+            format!("{} °C", data[0] as i8)
+        }
+        (Ssd1680Command::DisplayUpdateControl2, 1) => {
+            let flags = [
+                (0x80, "enable clock"),
+                (0x40, "enable analog"),
+                (0x20, "load temperature"),
+                (0x10, "load LUT"),
+                (0x08, "mode 2"),
+                (0x04, "display"),
+                (0x02, "disable analog"),
+                (0x01, "disable clock"),
+            ];
+            let set: Vec<&str> = flags
+                .iter()
+                .filter(|(bit, _)| data[0] & bit != 0)
+                .map(|&(_, name)| name)
+                .collect();
+            set.join(", ")
+        }
+        (Ssd1680Command::SetRamXAddressStartEndPosition, 2) => format!(
+            "x {}..={}",
+            u16::from(data[0] & 0x3F) * 8,
+            u16::from(data[1] & 0x3F) * 8 + 7
+        ),
+        (Ssd1680Command::SetRamYAddressStartEndPosition, 4) => {
+            format!("y {}..={}", u9_at(0), u9_at(2))
+        }
+        (Ssd1680Command::SetRamXAddressCounter, 1) => {
+            format!("x {}", u16::from(data[0] & 0x3F) * 8)
+        }
+        (Ssd1680Command::SetRamYAddressCounter, 2) => format!("y {}", u9_at(0)),
+        _ => return None,
+    })
+}
+
+/// A step of a decoded trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Transaction {
+    /// A command with the data sent until the next command
+    Command {
+        /// Address of the command
+        address: u8,
+        /// Data of the command
+        data: Vec<u8>,
+        /// Command and data bytes sent while CS was high, which the controller ignores
+        without_cs: usize,
+    },
+    /// Data sent before the first command or after a reset
+    Data(Vec<u8>),
+    /// A pulse of the reset pin, see [Event::Reset]
+    Reset {
+        /// Delays while RST was low in µs
+        low_us: u64,
+    },
+    /// Reads of the busy pin, see [Event::Busy]
+    Busy {
+        /// Level of the busy pin
+        high: bool,
+        /// Number of reads
+        reads: u32,
+        /// Delays between the reads in µs
+        waited_us: u64,
+    },
+    /// A delay, see [Event::Delay]
+    Delay {
+        /// Duration in µs
+        us: u64,
+    },
+}
+
+/// Groups `events` into [Transaction]s, named and printed with the enum of `commands`
+pub fn decode(events: &[Event], commands: CommandSet) -> Decoded {
+    let mut transactions = Vec::new();
+    let mut selected = false;
+    // Index of the command the controller currently receives data for
+    let mut current = None;
+    for event in events {
+        match event {
+            Event::Select => selected = true,
+            Event::Deselect => selected = false,
+            Event::Write { dc_high, bytes } => {
+                for &byte in bytes {
+                    if !dc_high {
+                        current = Some(transactions.len());
+                        transactions.push(Transaction::Command {
+                            address: byte,
+                            data: Vec::new(),
+                            without_cs: usize::from(!selected),
+                        });
+                        continue;
+                    }
+                    match current.map(|i| &mut transactions[i]) {
+                        Some(Transaction::Command {
+                            data, without_cs, ..
+                        }) => {
+                            data.push(byte);
+                            *without_cs += usize::from(!selected);
+                        }
+                        _ => match transactions.last_mut() {
+                            Some(Transaction::Data(data)) => data.push(byte),
+                            _ => transactions.push(Transaction::Data(vec![byte])),
+                        },
+                    }
+                }
+            }
+            &Event::Reset { low_us } => {
+                current = None;
+                transactions.push(Transaction::Reset { low_us });
+            }
+            &Event::Busy {
+                high,
+                reads,
+                waited_us,
+            } => transactions.push(Transaction::Busy {
+                high,
+                reads,
+                waited_us,
+            }),
+            &Event::Delay { us } => transactions.push(Transaction::Delay { us }),
+        }
+    }
+    Decoded {
+        commands,
+        transactions,
+    }
+}
+
+/// A decoded trace, prints one [Transaction] per line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    commands: CommandSet,
+    transactions: Vec<Transaction>,
+}
+
+impl Decoded {
+    /// The decoded transactions
+    pub fn transactions(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// The transaction as it is printed
+    pub fn line(&self, transaction: &Transaction) -> String {
+        format!("{}", Line(self.commands, transaction))
+    }
+
+    /// All transactions as they are printed
+    pub fn lines(&self) -> Vec<String> {
+        self.transactions.iter().map(|t| self.line(t)).collect()
+    }
+}
+
+impl fmt::Display for Decoded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for transaction in &self.transactions {
+            writeln!(f, "{}", Line(self.commands, transaction))?;
+        }
+        Ok(())
+    }
+}
+
+struct Line<'a>(CommandSet, &'a Transaction);
+
+impl fmt::Display for Line<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Line(commands, transaction) = *self;
+        match transaction {
+            Transaction::Command {
+                address,
+                data,
+                without_cs,
+            } => {
+                match commands.name(*address) {
+                    Some(name) => write!(f, "{}", name)?,
+                    None => write!(f, "unknown command 0x{:02X}", address)?,
+                }
+                if let Some(parameters) = commands.parameters(*address, data) {
+                    write!(f, " ({})", parameters)?;
+                }
+                if !data.is_empty() {
+                    write!(f, " ")?;
+                    write_data(f, data)?;
+                }
+                if *without_cs > 0 {
+                    write!(f, " !{} bytes without CS", without_cs)?;
+                }
+                Ok(())
+            }
+            Transaction::Data(data) => {
+                write!(f, "data without command ")?;
+                write_data(f, data)
+            }
+            Transaction::Reset { low_us } => write!(f, "RESET low for {}", Duration(*low_us)),
+            Transaction::Busy {
+                high,
+                reads: 1,
+                waited_us: 0,
+            } => write!(f, "BUSY {}", level(*high)),
+            Transaction::Busy {
+                high,
+                reads,
+                waited_us: 0,
+            } => write!(f, "BUSY {}, read {} times", level(*high), reads),
+            Transaction::Busy {
+                high,
+                reads,
+                waited_us,
+            } => write!(
+                f,
+                "BUSY {}, read {} times over {}",
+                level(*high),
+                reads,
+                Duration(*waited_us)
+            ),
+            Transaction::Delay { us } => write!(f, "delay {}", Duration(*us)),
+        }
+    }
+}
+
+fn level(high: bool) -> &'static str {
+    if high {
+        "high"
+    } else {
+        "low"
+    }
+}
+
+fn write_data(f: &mut fmt::Formatter<'_>, data: &[u8]) -> fmt::Result {
+    write!(f, "[")?;
+    for (i, byte) in data.iter().take(PRINTED_DATA).enumerate() {
+        if i > 0 {
+            write!(f, " ")?;
+        }
+        write!(f, "{:02X}", byte)?;
+    }
+    if data.len() > PRINTED_DATA {
+        write!(f, " … {} bytes", data.len())?;
+    }
+    write!(f, "]")
+}
+
+struct Duration(u64);
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 % 1000 == 0 {
+            write!(f, "{} ms", self.0 / 1000)
+        } else {
+            write!(f, "{} µs", self.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::SimulatedPanel;
+    use crate::traits::Command as CommandTrait;
+
+    #[test]
+    fn command_addresses_round_trip() {
+        macro_rules! round_trip {
+            ($($command:ty),*) => {$(
+                for address in 0..=u8::MAX {
+                    if let Some(command) = <$command>::from_address(address) {
+                        assert_eq!(command.address(), address);
+                    }
+                }
+            )*};
+        }
+        round_trip!(
+            crate::type_a::command::Command,
+            crate::epd1in54b::command::Command,
+            crate::epd1in54c::command::Command,
+            crate::epd2in13bc::command::Command,
+            crate::epd2in7b::command::Command,
+            crate::epd2in9bc::command::Command,
+            crate::epd4in2::command::Command,
+            crate::epd5in65f::command::Command,
+            crate::epd7in5::command::Command,
+            crate::epd7in5_hd::command::Command,
+            crate::epd7in5_v2::command::Command,
+            Ssd1680Command,
+            Uc8151Command
+        );
+        assert_eq!(
+            CommandSet::Uc8151.name(0x90).as_deref(),
+            Some("uc8151::command::Command::PartialWindow")
+        );
+        assert_eq!(CommandSet::Ssd1680.name(0x00), None);
+    }
+
+    #[test]
+    fn decode_events() {
+        let events = [
+            Event::Reset { low_us: 10_000 },
+            Event::Select,
+            Event::Write {
+                dc_high: false,
+                bytes: vec![0x44],
+            },
+            Event::Deselect,
+            Event::Select,
+            Event::Write {
+                dc_high: true,
+                bytes: vec![0x02, 0x03],
+            },
+            Event::Deselect,
+            Event::Write {
+                dc_high: false,
+                bytes: vec![0x20],
+            },
+            Event::Busy {
+                high: true,
+                reads: 3,
+                waited_us: 2000,
+            },
+            Event::Busy {
+                high: false,
+                reads: 1,
+                waited_us: 0,
+            },
+        ];
+        assert_eq!(
+            decode(&events, CommandSet::Ssd1680).lines(),
+            [
+                "RESET low for 10 ms",
+                "ssd1680::command::Command::SetRamXAddressStartEndPosition (x 16..=31) [02 03]",
+                "ssd1680::command::Command::MasterActivation !1 bytes without CS",
+                "BUSY high, read 3 times over 2 ms",
+                "BUSY low",
+            ]
+        );
+    }
+
+    #[test]
+    fn record_uc8151_partial_frame() {
+        use crate::epd2in13_GDEW0213T5D::{Epd2in13_T5D, HEIGHT, WIDTH};
+        use crate::traits::{PartialUpdate, WaveshareDisplay};
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let recorder = Recorder::new();
+        let (mut epd, mut spi, mut delay) =
+            panel.connect_traced::<Epd2in13_T5D<_, _, _, _, _, _>>(&recorder);
+        let init = recorder.decode(CommandSet::Uc8151).lines();
+        assert_eq!(init[1], "RESET low for 10 ms");
+        assert!(init.contains(
+            &"uc8151::command::Command::ResolutionSetting (104 × 212) [68 00 D4]".into()
+        ));
+        assert!(init.iter().any(|line| line.starts_with("BUSY low, read ")));
+
+        recorder.clear();
+        epd.update_partial_frame(&mut spi, &[0x00; 2 * 4], 8, 10, 16, 4, &mut delay)
+            .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+        assert_eq!(
+            recorder.decode(CommandSet::Uc8151).lines(),
+            [
+                "BUSY high",
+                "uc8151::command::Command::PartialIn",
+                "uc8151::command::Command::PartialWindow (x 8..=23, y 10..=13, scan inside window) \
+                 [00 08 00 17 00 0A 00 0D 01]",
+                "uc8151::command::Command::DataStartTransmission2 [00 00 00 00 00 00 00 00]",
+                "uc8151::command::Command::PartialOut",
+                "BUSY high",
+                "uc8151::command::Command::DisplayRefresh",
+            ]
+        );
+        assert!(panel.image().is_black_window(8, 10, 16, 4));
+        assert_eq!(panel.violations(), []);
+    }
+
+    /// SPI writes and CS cycles of `update_frame` with a full frame
+    fn frame_transfers<EPD>(panel: &SimulatedPanel) -> (usize, usize)
+    where
+        EPD: crate::traits::WaveshareDisplay<
+            TracedSpi<crate::simulator::Spi>,
+            TracedPin<crate::simulator::Pin>,
+            TracedPin<crate::simulator::Pin>,
+            TracedPin<crate::simulator::Pin>,
+            TracedPin<crate::simulator::Pin>,
+            TracedDelay<crate::simulator::Delay>,
+        >,
+    {
+        let recorder = Recorder::new();
+        let (mut epd, mut spi, mut delay) = panel.connect_traced::<EPD>(&recorder);
+        let frame = std::vec![0xFF; crate::buffer_len(EPD::WIDTH as usize, EPD::HEIGHT as usize)];
+        recorder.clear();
+        epd.update_frame(&mut spi, &frame, &mut delay).unwrap();
+        let events = recorder.events();
+        let writes = events
+            .iter()
+            .filter(|event| matches!(event, Event::Write { .. }))
+            .count();
+        let selects = events
+            .iter()
+            .filter(|&event| *event == Event::Select)
+            .count();
+        (writes, selects)
+    }
+
+    #[test]
+    fn spi_transfers_per_frame() {
+        use crate::epd2in13_GDEQ0213B74::{Epd2in13, Gdeq0213b74, HEIGHT, WIDTH};
+        use crate::ssd1680::Ssd1680;
+        use crate::{epd2in13_v2, epd4in2};
+
+        // 2 × 4000 bytes of RAM, in bulk and one byte at a time
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        assert_eq!(
+            frame_transfers::<Epd2in13<_, _, _, _, _, _>>(&panel),
+            (20, 20)
+        );
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        assert_eq!(
+            frame_transfers::<Ssd1680<_, _, _, _, _, _, Gdeq0213b74, true>>(&panel),
+            (8028, 8028)
+        );
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        assert_eq!(
+            frame_transfers::<epd2in13_v2::Epd2in13<_, _, _, _, _, _>>(&panel),
+            (8028, 8028)
+        );
+
+        // 2 × 15 000 bytes, the cleared old frame in 256 byte chunks, the frame in up to
+        // 4096 byte chunks on Linux
+        let panel = SimulatedPanel::il0398(epd4in2::WIDTH, epd4in2::HEIGHT);
+        let (writes, selects) = frame_transfers::<epd4in2::Epd4in2<_, _, _, _, _, _>>(&panel);
+        assert_eq!(selects, 4);
+        assert!(writes <= 65);
+    }
+}
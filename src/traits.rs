@@ -1,9 +1,20 @@
+use crate::error::EpdError;
 use core::marker::Sized;
 use embedded_hal::{
     blocking::{delay::*, spi::Write},
     digital::v2::*,
 };
 
+/// The [EpdError] returned by the drivers with the SPI peripheral `SPI` and the pins
+/// `CS`, `BUSY`, `DC` and `RST`
+pub type DriverError<SPI, CS, BUSY, DC, RST> = EpdError<
+    <SPI as Write<u8>>::Error,
+    <BUSY as InputPin>::Error,
+    <CS as OutputPin>::Error,
+    <DC as OutputPin>::Error,
+    <RST as OutputPin>::Error,
+>;
+
 /// All commands need to have this trait which gives the address of the command
 /// which needs to be send via SPI with activated CommandsPin (Data/Command Pin in CommandMode)
 pub(crate) trait Command {
@@ -11,10 +22,11 @@ pub(crate) trait Command {
 }
 
 /// Seperates the different LUT for the Display Refresh process
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Default)]
 pub enum RefreshLut {
     // OTP,
     /// The "normal" full Lookuptable for the Refresh-Sequence
+    #[default]
     Full,
     /// The direct LUT where not the full refresh sequence is followed.
     /// This might lead to some
@@ -22,9 +34,35 @@ pub enum RefreshLut {
     Quick,
 }
 
-impl Default for RefreshLut {
-    fn default() -> Self {
-        RefreshLut::Full
+/// Temperature range selecting the waveforms of the drivers which upload their own LUTs
+///
+/// See [ExternalTemperature::set_temperature].
+#[derive(Debug, Clone, PartialEq, Eq, Copy, Default)]
+pub enum TemperatureBand {
+    /// Below [COLD_BELOW](TemperatureBand::COLD_BELOW): longer waveforms for the slower particles
+    Cold,
+    /// The waveforms of the panel manufacturer
+    #[default]
+    Normal,
+    /// Above [HOT_ABOVE](TemperatureBand::HOT_ABOVE): shorter waveforms against overdriving
+    Hot,
+}
+
+impl TemperatureBand {
+    /// Lowest temperature of [TemperatureBand::Normal] in °C
+    pub const COLD_BELOW: i8 = 5;
+    /// Highest temperature of [TemperatureBand::Normal] in °C
+    pub const HOT_ABOVE: i8 = 35;
+
+    /// Band of a temperature in °C
+    pub fn from_celsius(celsius: i8) -> Self {
+        if celsius < Self::COLD_BELOW {
+            TemperatureBand::Cold
+        } else if celsius > Self::HOT_ABOVE {
+            TemperatureBand::Hot
+        } else {
+            TemperatureBand::Normal
+        }
     }
 }
 
@@ -47,7 +85,11 @@ where
     /// This function calls [reset](WaveshareDisplay::reset),
     /// so you don't need to call reset your self when trying to wake your device up
     /// after setting it to sleep.
-    fn init(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 }
 
 /// Functions to interact with three color panels
@@ -69,30 +111,65 @@ where
         spi: &mut SPI,
         black: &[u8],
         chromatic: &[u8],
-    ) -> Result<(), SPI::Error>;
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Update only the black/white data of the display.
     ///
     /// This must be finished by calling `update_chromatic_frame`.
-    fn update_achromatic_frame(&mut self, spi: &mut SPI, black: &[u8]) -> Result<(), SPI::Error>;
+    ///
+    /// Returns [EpdError::BufferSize] if `black` isn't as long as a frame of one bit per pixel.
+    fn update_achromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        black: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Update only the chromatic data of the display.
     ///
     /// This should be preceded by a call to `update_achromatic_frame`.
     /// This data takes precedence over the black/white data.
-    fn update_chromatic_frame(&mut self, spi: &mut SPI, chromatic: &[u8])
-        -> Result<(), SPI::Error>;
+    ///
+    /// Returns [EpdError::BufferSize] if `chromatic` isn't as long as a frame of one bit per pixel.
+    fn update_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        chromatic: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Panel data known at compile time
+///
+/// Lets generic code size buffers or pick a busy polarity without a driver instance, e.g.
+/// `<Epd4in2<SPI, CS, BUSY, DC, RST, DELAY> as PanelInfo>::WIDTH`.
+pub trait PanelInfo {
+    /// Width of the panel in pixels
+    const WIDTH: u32;
+    /// Height of the panel in pixels
+    const HEIGHT: u32;
+    /// Bits per pixel of a frame with all colors of the panel: 1 for black and white, 2 for
+    /// three colors sent as two planes, 4 for the seven colors of [OctColor](crate::color::OctColor)
+    ///
+    /// Grayscale modes aren't counted, see [GrayscaleDisplay].
+    const COLOR_DEPTH: u8;
+    /// Whether the BUSY pin is low while the controller is busy
+    const IS_BUSY_LOW: bool;
 }
 
 /// All the functions to interact with the EPDs
 ///
-/// This trait includes all public functions to use the EPDs
+/// This trait includes the functions every EPD supports. Partial updates, the choice of the
+/// LUT, quick refreshes, grayscale and three colors are separate traits implemented by the
+/// drivers whose panels can do them: [PartialUpdate], [LutSelection], [QuickRefresh],
+/// [GrayscaleDisplay] and [WaveshareThreeColorDisplay].
 ///
 /// # Example
 ///
 ///```rust, no_run
 ///# use embedded_hal_mock::*;
-///# fn main() -> Result<(), MockError> {
+///# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
 ///use embedded_graphics::{
 ///    pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 ///};
@@ -128,7 +205,7 @@ where
 ///# Ok(())
 ///# }
 ///```
-pub trait WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+pub trait WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>: PanelInfo
 where
     SPI: Write<u8>,
     CS: OutputPin,
@@ -149,19 +226,27 @@ where
         dc: DC,
         rst: RST,
         delay: &mut DELAY,
-    ) -> Result<Self, SPI::Error>
+    ) -> Result<Self, DriverError<SPI, CS, BUSY, DC, RST>>
     where
         Self: Sized;
 
     /// Let the device enter deep-sleep mode to save power.
     ///
     /// The deep sleep mode returns to standby with a hardware reset.
-    fn sleep(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Wakes the device up from sleep
     ///
     /// Also reintialises the device if necessary.
-    fn wake_up(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Sets the backgroundcolor for various commands like [clear_frame](WaveshareDisplay::clear_frame)
     fn set_background_color(&mut self, color: Self::DisplayColor);
@@ -170,38 +255,33 @@ where
     fn background_color(&self) -> &Self::DisplayColor;
 
     /// Get the width of the display
-    fn width(&self) -> u32;
+    fn width(&self) -> u32 {
+        Self::WIDTH
+    }
 
     /// Get the height of the display
-    fn height(&self) -> u32;
+    fn height(&self) -> u32 {
+        Self::HEIGHT
+    }
 
     /// Transmit a full frame to the SRAM of the EPD
+    ///
+    /// Returns [EpdError::BufferSize] if `buffer` isn't as long as a frame.
     fn update_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
-
-    /// Transmits partial data to the SRAM of the EPD
-    ///
-    /// (x,y) is the top left corner
-    ///
-    /// BUFFER needs to be of size: width / 8 * height !
-    fn update_partial_frame(
-        &mut self,
-        spi: &mut SPI,
-        buffer: &[u8],
-        x: u32,
-        y: u32,
-        width: u32,
-        height: u32,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Displays the frame data from SRAM
     ///
     /// This function waits until the device isn`t busy anymore
-    fn display_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Provide a combined update&display and save some time (skipping a busy check in between)
     fn update_and_display_frame(
@@ -209,13 +289,74 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Clears the frame buffer on the EPD with the declared background color
     ///
     /// The background color can be changed with [`WaveshareDisplay::set_background_color`]
-    fn clear_frame(&mut self, spi: &mut SPI, delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Checks if the display is busy transmitting data
+    ///
+    /// This is normally handled by the more complicated commands themselves,
+    /// but in the case you send data and commands directly you might need to check
+    /// if the device is still busy
+    fn is_busy(&self) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Sets the maximum time in ms to wait for the display to become idle
+    ///
+    /// Every method waiting for the busy pin returns [EpdError::BusyTimeout] once it is exceeded.
+    /// `None` waits forever. Defaults to [DEFAULT_BUSY_TIMEOUT_MS](crate::DEFAULT_BUSY_TIMEOUT_MS).
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>);
+}
 
+/// Displays which can write a window of the frame
+pub trait PartialUpdate<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Transmits partial data to the SRAM of the EPD
+    ///
+    /// (x,y) is the top left corner
+    ///
+    /// BUFFER needs to be of size: width / 8 * height !
+    ///
+    /// Returns [EpdError::BufferSize] for other buffer sizes and [EpdError::WindowAlignment]
+    /// if x or width aren't multiples of 8 or the window doesn't fit into the display.
+    #[allow(clippy::too_many_arguments)]
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Displays with more than one waveform to refresh with
+pub trait LutSelection<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
     /// Trait for using various Waveforms from different LUTs
     /// E.g. for partial refreshes
     ///
@@ -228,14 +369,42 @@ where
         &mut self,
         spi: &mut SPI,
         refresh_mode: Option<RefreshLut>,
-    ) -> Result<(), SPI::Error>;
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+}
 
-    /// Checks if the display is busy transmitting data
+/// Displays which can show 4 grayscale frames
+///
+/// The frames have 2 bits per pixel, like [QuadDisplay](crate::graphics::QuadDisplay) buffers.
+pub trait GrayscaleDisplay<SPI, CS, BUSY, DC, RST, DELAY>:
+    WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Transmits a full 4 grayscale frame to the SRAM of the EPD and loads the grayscale waveforms
     ///
-    /// This is normally handled by the more complicated commands themselves,
-    /// but in the case you send data and commands directly you might need to check
-    /// if the device is still busy
-    fn is_busy(&self) -> bool;
+    /// The grayscale waveforms stay active until the next [set_lut](LutSelection::set_lut),
+    /// so prefer [update_and_display_gray_frame](GrayscaleDisplay::update_and_display_gray_frame).
+    fn update_gray_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Transmits and displays a full 4 grayscale frame, then restores the waveforms of the
+    /// current [RefreshLut]
+    fn update_and_display_gray_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 }
 
 /// Allows quick refresh support for displays that support it; lets you send both
@@ -249,7 +418,7 @@ where
 /// Example:
 ///```rust, no_run
 ///# use embedded_hal_mock::*;
-///# fn main() -> Result<(), MockError> {
+///# fn main() -> Result<(), epd_waveshare::error::EpdError<MockError, MockError>> {
 ///# use embedded_graphics::{
 ///#   pixelcolor::BinaryColor::On as Black, prelude::*, primitives::{Line, PrimitiveStyle},
 ///# };
@@ -272,13 +441,13 @@ where
 ///let mut buffer = [DEFAULT_BACKGROUND_COLOR.get_byte_value(); 80 / 8 * 80];
 ///let mut display = VarDisplay::new(frame_width, frame_height, &mut buffer);
 ///
-///epd.update_partial_old_frame(&mut spi, display.buffer(), x, y, frame_width, frame_height)
+///epd.update_partial_old_frame(&mut spi, display.buffer(), x, y, frame_width, frame_height, &mut delay)
 ///  .ok();
 ///
 ///display.clear_buffer(Color::White);
 ///// Execute drawing commands here.
 ///
-///epd.update_partial_new_frame(&mut spi, display.buffer(), x, y, frame_width, frame_height)
+///epd.update_partial_new_frame(&mut spi, display.buffer(), x, y, frame_width, frame_height, &mut delay)
 ///  .ok();
 ///# Ok(())
 ///# }
@@ -293,23 +462,31 @@ where
     DELAY: DelayMs<u8>,
 {
     /// Updates the old frame.
+    ///
+    /// Returns [EpdError::BufferSize] if `buffer` isn't as long as a frame.
     fn update_old_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Updates the new frame.
+    ///
+    /// Returns [EpdError::BufferSize] if `buffer` isn't as long as a frame.
     fn update_new_frame(
         &mut self,
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Displays the new frame
-    fn display_new_frame(&mut self, spi: &mut SPI, _delay: &mut DELAY) -> Result<(), SPI::Error>;
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Updates and displays the new frame.
     fn update_and_display_new_frame(
@@ -317,9 +494,10 @@ where
         spi: &mut SPI,
         buffer: &[u8],
         delay: &mut DELAY,
-    ) -> Result<(), SPI::Error>;
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Updates the old frame for a portion of the display.
+    #[allow(clippy::too_many_arguments)]
     fn update_partial_old_frame(
         &mut self,
         spi: &mut SPI,
@@ -328,9 +506,11 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Updates the new frame for a portion of the display.
+    #[allow(clippy::too_many_arguments)]
     fn update_partial_new_frame(
         &mut self,
         spi: &mut SPI,
@@ -339,10 +519,12 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
 
     /// Clears the partial frame buffer on the EPD with the declared background color
     /// The background color can be changed with [`WaveshareDisplay::set_background_color`]
+    #[allow(clippy::too_many_arguments)]
     fn clear_partial_frame(
         &mut self,
         spi: &mut SPI,
@@ -350,5 +532,300 @@ where
         y: u32,
         width: u32,
         height: u32,
-    ) -> Result<(), SPI::Error>;
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Full refreshes in between quick refreshes
+///
+/// Used by [Ghosting](crate::ghosting::Ghosting) and
+/// [QuickRefreshSession](crate::session::QuickRefreshSession) to clear the ghosting of the
+/// quick refreshes. Drivers whose mode switch is expensive (e.g. a re-initialization) refresh
+/// without it.
+pub trait FullRefresh<SPI, CS, BUSY, DC, RST, DELAY>:
+    LutSelection<SPI, CS, BUSY, DC, RST, DELAY> + QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Refreshes `buffer` with [RefreshLut::Full] and writes it as old and new frame, the
+    /// driver is in [RefreshLut::Quick] afterwards
+    ///
+    /// The default switches the LUT with [set_lut](LutSelection::set_lut) before and after the
+    /// refresh.
+    fn full_refresh_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.set_lut(spi, Some(RefreshLut::Full), delay)?;
+        self.update_and_display_frame(spi, buffer, delay)?;
+        self.set_lut(spi, Some(RefreshLut::Quick), delay)?;
+        self.update_old_frame(spi, buffer, delay)
+    }
+}
+
+/// Displays whose frame can be written in consecutive chunks instead of one buffer
+///
+/// [begin_frame](StreamingFrame::begin_frame), any number of
+/// [write_frame_data](StreamingFrame::write_frame_data) calls and
+/// [end_frame](StreamingFrame::end_frame) send the same as
+/// [update_frame](WaveshareDisplay::update_frame) with the concatenated chunks, so the whole
+/// frame never needs to be in memory, see `paged::PagedDisplay`.
+/// Tri-color displays only write the achromatic frame this way, see [StreamingColorFrame].
+///
+/// Nothing else may be sent to the display until the frame is finished and the chunks need
+/// to add up to a full frame.
+pub trait StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Starts writing a frame
+    fn begin_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Writes the next bytes of the frame, in the buffer format of `update_frame`
+    fn write_frame_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Finishes the frame after all bytes were written
+    fn end_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+
+    /// Starts writing the finished frame again into the RAM holding the base of quick refreshes
+    ///
+    /// Some controllers keep the displayed frame in a second RAM and their `update_frame`
+    /// writes the frame into both RAMs in full refresh mode. Returns `false` without sending
+    /// anything if the frame is complete, otherwise the same frame needs to be written again
+    /// and finished with [end_frame](StreamingFrame::end_frame).
+    fn begin_base_frame(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        Ok(false)
+    }
+
+    /// Writes the frame from the chunks returned by `chunks`, e.g. read from flash
+    ///
+    /// Sends the same as [update_frame](WaveshareDisplay::update_frame) with the concatenated
+    /// chunks. `chunks` is called again for every
+    /// [base frame](StreamingFrame::begin_base_frame), i.e. only once for most displays.
+    fn update_frame_from_chunks<F, I>(
+        &mut self,
+        spi: &mut SPI,
+        mut chunks: F,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut() -> I,
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        self.begin_frame(spi, delay)?;
+        for chunk in chunks() {
+            self.write_frame_data(spi, chunk.as_ref())?;
+        }
+        self.end_frame(spi, delay)?;
+        if self.begin_base_frame(spi, delay)? {
+            for chunk in chunks() {
+                self.write_frame_data(spi, chunk.as_ref())?;
+            }
+            self.end_frame(spi, delay)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the frame from the bytes returned by `bytes`, e.g. from a decompressor
+    ///
+    /// The bytes are sent in chunks of 64 bytes, otherwise like
+    /// [update_frame_from_chunks](StreamingFrame::update_frame_from_chunks).
+    fn update_frame_from_bytes<F, I>(
+        &mut self,
+        spi: &mut SPI,
+        mut bytes: F,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+    where
+        F: FnMut() -> I,
+        I: IntoIterator<Item = u8>,
+    {
+        self.begin_frame(spi, delay)?;
+        write_bytes(self, spi, bytes())?;
+        self.end_frame(spi, delay)?;
+        if self.begin_base_frame(spi, delay)? {
+            write_bytes(self, spi, bytes())?;
+            self.end_frame(spi, delay)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes `bytes` with [StreamingFrame::write_frame_data] in chunks of 64 bytes
+fn write_bytes<EPD, SPI, CS, BUSY, DC, RST, DELAY>(
+    epd: &mut EPD,
+    spi: &mut SPI,
+    bytes: impl IntoIterator<Item = u8>,
+) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>
+where
+    EPD: StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY> + ?Sized,
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    let mut chunk = [0; 64];
+    let mut len = 0;
+    for byte in bytes {
+        chunk[len] = byte;
+        len += 1;
+        if len == chunk.len() {
+            epd.write_frame_data(spi, &chunk)?;
+            len = 0;
+        }
+    }
+    if len > 0 {
+        epd.write_frame_data(spi, &chunk[..len])?;
+    }
+    Ok(())
+}
+
+/// Tri-color displays whose chromatic frame can be written in consecutive chunks
+///
+/// Like the achromatic frame with [StreamingFrame], but started with
+/// [begin_chromatic_frame](StreamingColorFrame::begin_chromatic_frame).
+pub trait StreamingColorFrame<SPI, CS, BUSY, DC, RST, DELAY>:
+    StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Starts writing the chromatic frame, finished with
+    /// [end_frame](StreamingFrame::end_frame) as well
+    fn begin_chromatic_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Displays whose controller can be told the panel temperature instead of measuring it
+///
+/// The controllers pick the waveforms of their OTP memory by temperature. Drivers uploading
+/// their own LUTs switch to the waveforms of the [TemperatureBand] instead.
+pub trait ExternalTemperature<SPI, CS, BUSY, DC, RST, DELAY>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+{
+    /// Sets the temperature of the panel in °C for the following refreshes
+    ///
+    /// The temperature is kept across [wake_up](WaveshareDisplay::wake_up).
+    fn set_temperature(
+        &mut self,
+        spi: &mut SPI,
+        celsius: i8,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>>;
+}
+
+/// Drivers which give their pins back
+///
+/// Used by [Asleep::deinit](crate::power::Asleep::deinit) to release the pins of a sleeping display.
+pub trait Deinit<CS, BUSY, DC, RST> {
+    /// Consumes the driver and returns the CS, BUSY, DC and RST pins
+    ///
+    /// The display isn't sent to sleep, do that before with [WaveshareDisplay::sleep].
+    fn deinit(self) -> (CS, BUSY, DC, RST);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buffer_len;
+    use crate::simulator::{Plane, SimulatedPanel};
+
+    #[test]
+    fn temperature_band() {
+        assert_eq!(TemperatureBand::from_celsius(-20), TemperatureBand::Cold);
+        assert_eq!(TemperatureBand::from_celsius(4), TemperatureBand::Cold);
+        assert_eq!(TemperatureBand::from_celsius(5), TemperatureBand::Normal);
+        assert_eq!(TemperatureBand::from_celsius(35), TemperatureBand::Normal);
+        assert_eq!(TemperatureBand::from_celsius(36), TemperatureBand::Hot);
+        assert_eq!(TemperatureBand::default(), TemperatureBand::Normal);
+    }
+
+    #[test]
+    fn update_frame_from_bytes_writes_base_frame() {
+        use crate::epd2in13_GDEQ0213B74::{Epd2in13, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13<_, _, _, _, _, _>>();
+
+        // The full refresh mode writes the base RAM as well
+        let mut frame = std::vec![0xFF; buffer_len(WIDTH as usize, HEIGHT as usize)];
+        frame[16..18].fill(0x00);
+        let mut calls = 0;
+        epd.update_frame_from_bytes(
+            &mut spi,
+            || {
+                calls += 1;
+                frame.iter().copied()
+            },
+            &mut delay,
+        )
+        .unwrap();
+        epd.display_frame(&mut spi, &mut delay).unwrap();
+        assert_eq!(calls, 2);
+        assert_eq!(panel.ram(Plane::New), frame);
+        assert_eq!(panel.ram(Plane::Old), frame);
+        assert!(panel.image().is_black_window(0, 1, 16, 1));
+        assert_eq!(panel.violations(), []);
+    }
+
+    #[test]
+    fn update_frame_from_chunks_after_window() {
+        use crate::epd2in9_v2::{Epd2in9, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::ssd1680(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in9<_, _, _, _, _, _>>();
+
+        epd.update_partial_frame(&mut spi, &[0x00; 2 * 4], 40, 60, 16, 4, &mut delay)
+            .unwrap();
+        let mut frame = std::vec![0xFF; buffer_len(WIDTH as usize, HEIGHT as usize)];
+        frame[100..200].fill(0x0F);
+        epd.update_frame_from_chunks(&mut spi, || frame.chunks(300), &mut delay)
+            .unwrap();
+        assert_eq!(panel.ram(Plane::New), frame);
+        assert_eq!(panel.violations(), []);
+    }
 }
@@ -8,7 +8,7 @@ use crate::traits;
 ///
 /// For more infos about the addresses and what they are doing look into the pdfs
 #[allow(dead_code)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub(crate) enum Command {
     /// Driver Output control
     ///     3 Databytes:
@@ -81,6 +81,39 @@ impl traits::Command for Command {
     }
 }
 
+impl Command {
+    /// The command with this address, used to decode [traces](crate::trace)
+    #[cfg(any(test, feature = "std"))]
+    pub(crate) fn from_address(address: u8) -> Option<Self> {
+        Some(match address {
+            0x01 => Command::DriverOutputControl,
+            0x0C => Command::BoosterSoftStartControl,
+            0x0F => Command::GateScanStartPosition,
+            0x10 => Command::DeepSleepMode,
+            0x11 => Command::DataEntryModeSetting,
+            0x12 => Command::SwReset,
+            0x1A => Command::TemperatureSensorControl,
+            0x20 => Command::MasterActivation,
+            0x21 => Command::DisplayUpdateControl1,
+            0x22 => Command::DisplayUpdateControl2,
+            0x24 => Command::WriteRam,
+            0x26 => Command::WriteRam2,
+            0x2C => Command::WriteVcomRegister,
+            0x32 => Command::WriteLutRegister,
+            0x37 => Command::WriteOtpSelection,
+            0x3A => Command::SetDummyLinePeriod,
+            0x3B => Command::SetGateLineWidth,
+            0x3C => Command::BorderWaveformControl,
+            0x44 => Command::SetRamXAddressStartEndPosition,
+            0x45 => Command::SetRamYAddressStartEndPosition,
+            0x4E => Command::SetRamXAddressCounter,
+            0x4F => Command::SetRamYAddressCounter,
+            0xFF => Command::Nop,
+            _ => return None,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Command;
@@ -0,0 +1,349 @@
+//! Async implementation of the UC8151 driver
+
+use embedded_hal_1::digital::{InputPin, OutputPin};
+use embedded_hal_async::{delay::DelayNs, digital::Wait, spi::SpiBus};
+
+use core::marker::PhantomData;
+
+use super::{sequence, LutSource, Panel, Uc8151, DEFAULT_BACKGROUND_COLOR};
+use crate::asynch::{
+    AsyncDriverError, AsyncLutSelection, AsyncPartialUpdate, AsyncQuickRefresh,
+    AsyncWaveshareDisplay,
+};
+use crate::buffer_len;
+use crate::color::Color;
+use crate::error::EpdError;
+use crate::interface::DisplayInterface;
+use crate::sequence::Step;
+use crate::traits::RefreshLut;
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    async fn run_async(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        steps: &[Step<'_>],
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface
+            .run_async(spi, delay, P::IS_BUSY_LOW, steps)
+            .await
+    }
+
+    async fn init_async(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::init::<P>(self.lut_source))
+            .await?;
+        AsyncLutSelection::set_lut(self, spi, None, delay).await?;
+        self.run_async(spi, delay, &[Step::WaitUntilIdle]).await
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> AsyncWaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    type DisplayColor = Color;
+
+    async fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Uc8151 {
+            interface,
+            color,
+            refresh: RefreshLut::Quick,
+            lut_source: LutSource::Registers,
+            temperature: None,
+            panel: PhantomData,
+        };
+
+        epd.init_async(spi, delay).await?;
+
+        Ok(epd)
+    }
+
+    async fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::sleep()).await
+    }
+
+    async fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.init_async(spi, delay).await
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn width(&self) -> u32 {
+        P::WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        P::HEIGHT
+    }
+
+    async fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let color_value = self.color.get_byte_value();
+        let steps = sequence::update_frame::<P>(color_value, buffer);
+        self.run_async(spi, delay, &steps).await
+    }
+
+    async fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run_async(spi, delay, &sequence::display_frame()).await
+    }
+
+    async fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let color_value = self.color.get_byte_value();
+        let steps = sequence::update_and_display_frame::<P>(color_value, buffer);
+        self.run_async(spi, delay, &steps).await
+    }
+
+    async fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        let color_value = self.color.get_byte_value();
+        self.run_async(spi, delay, &sequence::clear_frame::<P>(color_value))
+            .await
+    }
+
+    fn is_busy(&mut self) -> Result<bool, AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.is_busy_now(P::IS_BUSY_LOW)
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.interface.set_busy_timeout(timeout_ms);
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> AsyncPartialUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    async fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = sequence::partial_window(x, y, width, height);
+        let steps = sequence::update_partial_frame(&window, buffer);
+        self.run_async(spi, delay, &steps).await
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> AsyncLutSelection<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    async fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh_rate: Option<RefreshLut>,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        let steps = sequence::set_lut(P::LUTS.lut(self.refresh, self.temperature_band()));
+        self.run_async(spi, delay, &steps).await
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> AsyncQuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: SpiBus<u8>,
+    CS: OutputPin,
+    BUSY: InputPin + Wait,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayNs,
+    P: Panel,
+{
+    async fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let steps = sequence::update_old_or_new_frame(true, buffer);
+        self.run_async(spi, delay, &steps).await
+    }
+
+    async fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let steps = sequence::update_old_or_new_frame(false, buffer);
+        self.run_async(spi, delay, &steps).await
+    }
+
+    async fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        AsyncWaveshareDisplay::display_frame(self, spi, delay).await
+    }
+
+    async fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay).await?;
+        AsyncWaveshareDisplay::display_frame(self, spi, delay).await
+    }
+
+    async fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = sequence::partial_window(x, y, width, height);
+        let steps = sequence::update_partial_old_frame(&window, buffer);
+        self.run_async(spi, delay, &steps).await
+    }
+
+    async fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = sequence::partial_window(x, y, width, height);
+        let steps = sequence::update_partial_new_frame(&window, buffer);
+        self.run_async(spi, delay, &steps).await
+    }
+
+    async fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), AsyncDriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+
+        let color_value = self.color.get_byte_value();
+        let window = sequence::partial_window(x, y, width, height);
+        let bytes = buffer_len(width as usize, height as usize) as u32;
+        let steps = sequence::clear_partial_frame::<P>(&window, color_value, bytes);
+        self.run_async(spi, delay, &steps).await
+    }
+}
@@ -1,21 +1,7 @@
 //! This file contains look-up-tables used to set voltages used during
 //! various categories of pixel refreshes.
 
-#[derive(Debug)]
-pub struct Uc8151Lut {
-    pub vcom0: [u8; 44],
-    pub ww: [u8; 42],
-    pub bw: [u8; 42],
-    pub bb: [u8; 42],
-    pub wb: [u8; 42],
-}
-
-impl Default for Uc8151Lut
-{
-    fn default() -> Self {
-        FULL_LUT
-    }
-}
+use super::lut::Uc8151Lut;
 
 #[rustfmt::skip]
 pub(crate) const FULL_LUT: Uc8151Lut = Uc8151Lut {
@@ -66,6 +52,15 @@ pub(crate) const FULL_LUT: Uc8151Lut = Uc8151Lut {
     ],
 };
 
+/// Estimate of [FULL_LUT] for [TemperatureBand::Cold](crate::traits::TemperatureBand::Cold):
+/// the frames of all phases are stretched by 1.5, there is no published cold waveform
+pub(crate) const FULL_LUT_COLD: Uc8151Lut = FULL_LUT.scaled(3, 2);
+/// Estimate of [FULL_LUT] for [TemperatureBand::Hot](crate::traits::TemperatureBand::Hot):
+/// the frames of all phases are shortened to 0.75, there is no published hot waveform
+pub(crate) const FULL_LUT_HOT: Uc8151Lut = FULL_LUT.scaled(3, 4);
+
+/// Drives only the pixels changing their color, the ones keeping it stay grounded so they
+/// don't build up a DC bias
 #[rustfmt::skip]
 pub(crate) const DIRECT_LUT: Uc8151Lut = Uc8151Lut {
     vcom0: [
@@ -78,7 +73,7 @@ pub(crate) const DIRECT_LUT: Uc8151Lut = Uc8151Lut {
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
     ww: [
-        0xA0, 0x0E, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x0E, 0x00, 0x00, 0x00, 0x01,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
@@ -96,7 +91,7 @@ pub(crate) const DIRECT_LUT: Uc8151Lut = Uc8151Lut {
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
     bb: [
-        0x50, 0x0E, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x0E, 0x00, 0x00, 0x00, 0x01,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
@@ -115,49 +110,61 @@ pub(crate) const DIRECT_LUT: Uc8151Lut = Uc8151Lut {
     ],
 };
 
+/// Estimate of [DIRECT_LUT] for [TemperatureBand::Cold](crate::traits::TemperatureBand::Cold):
+/// the frames of all phases are stretched by 1.5, there is no published cold waveform
+pub(crate) const DIRECT_LUT_COLD: Uc8151Lut = DIRECT_LUT.scaled(3, 2);
+/// Estimate of [DIRECT_LUT] for [TemperatureBand::Hot](crate::traits::TemperatureBand::Hot):
+/// the frames of all phases are shortened to 0.75, there is no published hot waveform
+pub(crate) const DIRECT_LUT_HOT: Uc8151Lut = DIRECT_LUT.scaled(3, 4);
+
+/// Waveforms for the 4 grayscale mode.
+///
+/// The old data (`DataStartTransmission1`) holds the high bit and the new data
+/// (`DataStartTransmission2`) the low bit of every pixel, so each of the four
+/// transition LUTs drives one of the four gray levels.
 #[rustfmt::skip]
-pub(crate) const QUICK_LUT: Uc8151Lut = Uc8151Lut {
+pub(crate) const GRAY_LUT: Uc8151Lut = Uc8151Lut {
     vcom0: [
-        0x00, 0x0E, 0x00, 0x00, 0x00, 0x02,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x0A, 0x00, 0x00, 0x00, 0x01,
+        0x60, 0x14, 0x14, 0x00, 0x00, 0x01,
+        0x00, 0x14, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x13, 0x0A, 0x01, 0x00, 0x01,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
     ww: [
-        0x40, 0x0A, 0x01, 0x00, 0x00, 0x01,
-        0xA0, 0x0E, 0x00, 0x00, 0x00, 0x01,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x40, 0x0A, 0x00, 0x00, 0x00, 0x01,
+        0x90, 0x14, 0x14, 0x00, 0x00, 0x01,
+        0x10, 0x14, 0x0A, 0x00, 0x00, 0x01,
+        0xA0, 0x13, 0x01, 0x00, 0x00, 0x01,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
     bw: [
-        0x40, 0x0A, 0x01, 0x00, 0x00, 0x01,
-        0xA0, 0x0E, 0x00, 0x00, 0x00, 0x01,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x40, 0x0A, 0x00, 0x00, 0x00, 0x01,
+        0x90, 0x14, 0x14, 0x00, 0x00, 0x01,
+        0x00, 0x14, 0x0A, 0x00, 0x00, 0x01,
+        0x99, 0x0C, 0x01, 0x03, 0x04, 0x01,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
-    bb: [
-        0x80, 0x0A, 0x01, 0x00, 0x00, 0x01,
-        0x50, 0x0E, 0x00, 0x00, 0x00, 0x01,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    wb: [
+        0x40, 0x0A, 0x00, 0x00, 0x00, 0x01,
+        0x90, 0x14, 0x14, 0x00, 0x00, 0x01,
+        0x00, 0x14, 0x0A, 0x00, 0x00, 0x01,
+        0x99, 0x0B, 0x04, 0x04, 0x01, 0x01,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
     ],
-    wb: [
-        0x80, 0x0A, 0x01, 0x00, 0x00, 0x01,
-        0x50, 0x0E, 0x00, 0x00, 0x00, 0x01,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    bb: [
+        0x80, 0x0A, 0x00, 0x00, 0x00, 0x01,
+        0x90, 0x14, 0x14, 0x00, 0x00, 0x01,
+        0x20, 0x14, 0x0A, 0x00, 0x00, 0x01,
+        0x50, 0x13, 0x01, 0x00, 0x00, 0x01,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
@@ -0,0 +1,341 @@
+//! Waveforms (LUTs) of the UC8151 controller
+//!
+//! A waveform is uploaded into five registers, one for VCOM and one for each pixel transition.
+//! Every register holds up to [GROUPS](Uc8151Lut::GROUPS) groups of 6 bytes:
+//!
+//! | byte | content                                            |
+//! |------|----------------------------------------------------|
+//! | 0    | level select of phase A to D, 2 bits each (A high) |
+//! | 1..5 | number of frames of phase A to D                   |
+//! | 5    | repeat count of the group                          |
+//!
+//! The VCOM register has two more bytes, which are left at 0.
+
+use crate::lut::scale_phases;
+pub use crate::lut::Waveform;
+use crate::traits::{RefreshLut, TemperatureBand};
+
+/// Bytes of one group
+const GROUP_LEN: usize = 6;
+
+/// Voltage selected during a [Phase]
+///
+/// The pixel waveforms drive the source lines, the VCOM waveform is relative to `VCOM_DC`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// GND, `VCOM_DC` for the VCOM waveform
+    Ground,
+    /// VDH, `VCOM_DC + VDH` for the VCOM waveform
+    High,
+    /// VDL, `VCOM_DC + VDL` for the VCOM waveform
+    Low,
+    /// VDHR, floating for the VCOM waveform
+    Floating,
+}
+
+impl Level {
+    fn bits(self) -> u8 {
+        match self {
+            Level::Ground => 0b00,
+            Level::High => 0b01,
+            Level::Low => 0b10,
+            Level::Floating => 0b11,
+        }
+    }
+
+    pub(crate) fn from_bits(bits: u8) -> Self {
+        match bits & 0b11 {
+            0b00 => Level::Ground,
+            0b01 => Level::High,
+            0b10 => Level::Low,
+            _ => Level::Floating,
+        }
+    }
+}
+
+/// One of the four phases of a group: a voltage held for a number of frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Phase {
+    /// Selected voltage
+    pub level: Level,
+    /// Duration in frames, 0 skips the phase
+    pub frames: u8,
+}
+
+impl Phase {
+    /// Skipped phase
+    pub const NONE: Phase = Phase::new(Level::Ground, 0);
+
+    /// Holds `level` for `frames` frames
+    pub const fn new(level: Level, frames: u8) -> Self {
+        Phase { level, frames }
+    }
+}
+
+/// Waveform registers of the UC8151
+///
+/// [Default] is the full refresh waveform, [new](Uc8151Lut::new) an empty waveform to start
+/// building from:
+///
+/// ```rust
+/// use epd_waveshare::uc8151::{Level, Phase, Uc8151Lut, Waveform};
+///
+/// // Longer frames for a cold panel: drive the pixels to the opposite color and back
+/// let lut = Uc8151Lut::new()
+///     .with_timing(0, [0x20, 0x20, 0, 0], 2)
+///     .with_levels(Waveform::BlackToWhite, 0, [Level::Low, Level::High, Level::Ground, Level::Ground])
+///     .with_levels(Waveform::WhiteToBlack, 0, [Level::High, Level::Low, Level::Ground, Level::Ground])
+///     .with_repeat(0, 3);
+///
+/// let (phases, repeat) = lut.group(Waveform::BlackToWhite, 0);
+/// assert_eq!(phases[1], Phase::new(Level::High, 0x20));
+/// assert_eq!(repeat, 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Uc8151Lut {
+    /// VCOM waveform (`LutForVcom`)
+    pub vcom0: [u8; 44],
+    /// White to white waveform (`LutWhiteToWhite`)
+    pub ww: [u8; 42],
+    /// Black to white waveform (`LutBlackToWhite`)
+    pub bw: [u8; 42],
+    /// Black to black waveform (`LutBlackToBlack`)
+    pub bb: [u8; 42],
+    /// White to black waveform (`LutWhiteToBlack`)
+    pub wb: [u8; 42],
+}
+
+impl Default for Uc8151Lut {
+    fn default() -> Self {
+        super::constants::FULL_LUT
+    }
+}
+
+impl Uc8151Lut {
+    /// Number of groups per register
+    pub const GROUPS: usize = 7;
+
+    /// Waveform which doesn't drive the pixels at all
+    pub const fn new() -> Self {
+        Uc8151Lut {
+            vcom0: [0; 44],
+            ww: [0; 42],
+            bw: [0; 42],
+            bb: [0; 42],
+            wb: [0; 42],
+        }
+    }
+
+    /// Copy of the waveform the driver loads for `refresh` between 5 °C and 35 °C
+    pub fn builtin(refresh: RefreshLut) -> Self {
+        super::DEFAULT_LUTS
+            .lut(refresh, TemperatureBand::Normal)
+            .clone()
+    }
+
+    /// Bytes of a register
+    pub fn waveform(&self, waveform: Waveform) -> &[u8] {
+        match waveform {
+            Waveform::Vcom => &self.vcom0,
+            Waveform::WhiteToWhite => &self.ww,
+            Waveform::BlackToWhite => &self.bw,
+            Waveform::WhiteToBlack => &self.wb,
+            Waveform::BlackToBlack => &self.bb,
+        }
+    }
+
+    fn waveform_mut(&mut self, waveform: Waveform) -> &mut [u8] {
+        match waveform {
+            Waveform::Vcom => &mut self.vcom0,
+            Waveform::WhiteToWhite => &mut self.ww,
+            Waveform::BlackToWhite => &mut self.bw,
+            Waveform::WhiteToBlack => &mut self.wb,
+            Waveform::BlackToBlack => &mut self.bb,
+        }
+    }
+
+    fn group_mut(&mut self, waveform: Waveform, group: usize) -> &mut [u8] {
+        assert!(group < Self::GROUPS, "LUT group out of range");
+        &mut self.waveform_mut(waveform)[group * GROUP_LEN..(group + 1) * GROUP_LEN]
+    }
+
+    /// Phases and repeat count of a group
+    ///
+    /// # Panics
+    ///
+    /// If `group` isn't below [GROUPS](Uc8151Lut::GROUPS)
+    pub fn group(&self, waveform: Waveform, group: usize) -> ([Phase; 4], u8) {
+        assert!(group < Self::GROUPS, "LUT group out of range");
+        let bytes = &self.waveform(waveform)[group * GROUP_LEN..(group + 1) * GROUP_LEN];
+        let mut phases = [Phase::NONE; 4];
+        for (i, phase) in phases.iter_mut().enumerate() {
+            let level = Level::from_bits(bytes[0] >> (6 - 2 * i));
+            *phase = Phase::new(level, bytes[1 + i]);
+        }
+        (phases, bytes[5])
+    }
+
+    /// Sets the phases and the repeat count of a group
+    ///
+    /// # Panics
+    ///
+    /// If `group` isn't below [GROUPS](Uc8151Lut::GROUPS)
+    pub fn set_group(&mut self, waveform: Waveform, group: usize, phases: [Phase; 4], repeat: u8) {
+        let bytes = self.group_mut(waveform, group);
+        bytes[0] = 0;
+        for (i, phase) in phases.iter().enumerate() {
+            bytes[0] |= phase.level.bits() << (6 - 2 * i);
+            bytes[1 + i] = phase.frames;
+        }
+        bytes[5] = repeat;
+    }
+
+    /// Builder version of [set_group](Uc8151Lut::set_group)
+    pub fn with_group(
+        mut self,
+        waveform: Waveform,
+        group: usize,
+        phases: [Phase; 4],
+        repeat: u8,
+    ) -> Self {
+        self.set_group(waveform, group, phases, repeat);
+        self
+    }
+
+    /// Sets the levels of the phases of a group, keeping their frames and the repeat count
+    ///
+    /// # Panics
+    ///
+    /// If `group` isn't below [GROUPS](Uc8151Lut::GROUPS)
+    pub fn with_levels(mut self, waveform: Waveform, group: usize, levels: [Level; 4]) -> Self {
+        let bytes = self.group_mut(waveform, group);
+        bytes[0] = levels
+            .iter()
+            .enumerate()
+            .fold(0, |select, (i, level)| select | level.bits() << (6 - 2 * i));
+        self
+    }
+
+    /// Sets the frames of the phases and the repeat count of a group in all five registers,
+    /// keeping the levels
+    ///
+    /// The registers are run in parallel, so their timing should match.
+    ///
+    /// # Panics
+    ///
+    /// If `group` isn't below [GROUPS](Uc8151Lut::GROUPS)
+    pub fn with_timing(mut self, group: usize, frames: [u8; 4], repeat: u8) -> Self {
+        for &waveform in &Waveform::ALL {
+            let bytes = self.group_mut(waveform, group);
+            bytes[1..5].copy_from_slice(&frames);
+            bytes[5] = repeat;
+        }
+        self
+    }
+
+    /// Scales the frames of all phases by `num / den`, keeping the levels and repeat counts
+    ///
+    /// Estimates the waveforms of the cold and hot [TemperatureBand]s, see [scale_phases].
+    pub(crate) const fn scaled(self, num: u16, den: u16) -> Self {
+        Uc8151Lut {
+            vcom0: scale_register(self.vcom0, num, den),
+            ww: scale_register(self.ww, num, den),
+            bw: scale_register(self.bw, num, den),
+            bb: scale_register(self.bb, num, den),
+            wb: scale_register(self.wb, num, den),
+        }
+    }
+
+    /// Sets the repeat count of a group in all five registers
+    ///
+    /// # Panics
+    ///
+    /// If `group` isn't below [GROUPS](Uc8151Lut::GROUPS)
+    pub fn with_repeat(mut self, group: usize, repeat: u8) -> Self {
+        for &waveform in &Waveform::ALL {
+            self.group_mut(waveform, group)[5] = repeat;
+        }
+        self
+    }
+}
+
+/// Scales the frames of all groups of a register, see [scale_phases]
+const fn scale_register<const N: usize>(register: [u8; N], num: u16, den: u16) -> [u8; N] {
+    scale_phases(
+        register,
+        0,
+        N / GROUP_LEN,
+        GROUP_LEN,
+        [1, 2, 3, 4],
+        num,
+        den,
+    )
+}
+
+/// Where the UC8151 takes the waveforms of a refresh from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LutSource {
+    /// Waveforms programmed into the OTP memory of the panel (`PanelSetting` 0x1F)
+    Otp,
+    /// Waveforms uploaded into the LUT registers (`PanelSetting` 0x3F)
+    Registers,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_builtin() {
+        let lut = Uc8151Lut::default();
+        let (phases, repeat) = lut.group(Waveform::WhiteToWhite, 1);
+        assert_eq!(
+            phases,
+            [
+                Phase::new(Level::Low, 0x17),
+                Phase::new(Level::High, 0x17),
+                Phase::NONE,
+                Phase::NONE,
+            ]
+        );
+        assert_eq!(repeat, 2);
+        assert_eq!(lut.group(Waveform::Vcom, 6), ([Phase::NONE; 4], 0));
+    }
+
+    #[test]
+    fn build_groups() {
+        let phases = [
+            Phase::new(Level::High, 1),
+            Phase::new(Level::Low, 2),
+            Phase::new(Level::Floating, 3),
+            Phase::new(Level::Ground, 4),
+        ];
+        let lut = Uc8151Lut::new()
+            .with_group(Waveform::BlackToBlack, 6, phases, 5)
+            .with_timing(0, [9, 8, 7, 6], 1)
+            .with_levels(Waveform::Vcom, 0, [Level::Floating; 4])
+            .with_repeat(6, 10);
+
+        assert_eq!(lut.bb[36..], [0b01_10_11_00, 1, 2, 3, 4, 10]);
+        assert_eq!(lut.group(Waveform::BlackToBlack, 6), (phases, 10));
+        assert_eq!(lut.ww[..6], [0, 9, 8, 7, 6, 1]);
+        assert_eq!(lut.vcom0[..6], [0xFF, 9, 8, 7, 6, 1]);
+        assert_eq!(lut.vcom0[42..], [0, 0]);
+        assert_eq!(lut.group(Waveform::WhiteToBlack, 6).1, 10);
+
+        let mut roundtrip = Uc8151Lut::new();
+        for &waveform in &Waveform::ALL {
+            for group in 0..Uc8151Lut::GROUPS {
+                let (phases, repeat) = lut.group(waveform, group);
+                roundtrip.set_group(waveform, group, phases, repeat);
+            }
+        }
+        assert_eq!(roundtrip, lut);
+    }
+
+    #[test]
+    #[should_panic]
+    fn group_out_of_range() {
+        let _ = Uc8151Lut::new().with_repeat(Uc8151Lut::GROUPS, 1);
+    }
+}
@@ -0,0 +1,1049 @@
+//! Generic driver for black/white displays with an UltraChip UC8151 (or the compatible IL0373)
+//! controller
+//!
+//! A display is described by a [Panel] with its resolution, busy polarity and [LutSet] and
+//! driven by [Uc8151]. The presets are the
+//! [GDEW0213T5D](crate::epd2in13_GDEW0213T5D) (also on Lilygo T5 boards), the flexible
+//! [Waveshare 2.13" (D)](crate::epd2in13d) and the flexible [Waveshare 2.9" (D)](crate::epd2in9d).
+//!
+//! # Example
+//!
+//! Another panel is a few lines of configuration, e.g. a 152x152 one with the default waveforms:
+//!
+//!```rust
+//!use epd_waveshare::uc8151::{Panel, Uc8151};
+//!
+//!/// 1.54" with 152x152 pixels
+//!pub struct Panel1in54;
+//!
+//!impl Panel for Panel1in54 {
+//!    const WIDTH: u32 = 152;
+//!    const HEIGHT: u32 = 152;
+//!}
+//!
+//!pub type Epd1in54<SPI, CS, BUSY, DC, RST, DELAY> =
+//!    Uc8151<SPI, CS, BUSY, DC, RST, DELAY, Panel1in54>;
+//!```
+//!
+//! # Grayscale
+//!
+//! The 4 grayscales (2 bits per pixel) are sent with
+//! [GrayscaleDisplay::update_and_display_gray_frame]. The driver splits the frame into the two
+//! bit planes and temporarily loads the grayscale waveforms for that refresh.
+//!
+//! # Custom waveforms
+//!
+//! Own waveforms are built with [Uc8151Lut] and loaded with [Uc8151::set_custom_lut].
+//! [Uc8151::set_lut_source] switches between the uploaded waveforms and the ones
+//! programmed into the OTP memory of the panel. Waveforms leaving a DC bias on the pixels are
+//! refused before the upload, see [crate::lut].
+//!
+//! # Temperature
+//!
+//! [ExternalTemperature::set_temperature] forces the temperature the controller picks the OTP
+//! waveforms with and selects the register waveforms of the [TemperatureBand] from the
+//! [LutSet] of the panel. The presets use the same waveforms in all bands, estimated cold and
+//! hot waveforms are an experimental opt-in, see [ESTIMATED_TEMPERATURE_LUTS].
+
+use core::marker::PhantomData;
+
+use embedded_hal::{
+    blocking::{delay::*, spi::Write},
+    digital::v2::*,
+};
+
+use crate::color::Color;
+use crate::error::EpdError;
+use crate::interface::DisplayInterface;
+use crate::lut::{Lut, LutLimits};
+use crate::sequence::Step;
+use crate::traits::{
+    Deinit, DriverError, ExternalTemperature, FullRefresh, GrayscaleDisplay, InternalWiAdditions,
+    LutSelection, PanelInfo, PartialUpdate, QuickRefresh, RefreshLut, StreamingFrame,
+    TemperatureBand, WaveshareDisplay,
+};
+use crate::{buffer_len, gray_buffer_len};
+
+// The Lookup Tables for the Display
+pub(crate) mod constants;
+
+mod lut;
+pub use self::lut::{Level, LutSource, Phase, Uc8151Lut, Waveform};
+
+/// Default Background Color
+pub const DEFAULT_BACKGROUND_COLOR: Color = Color::White;
+
+const SINGLE_BYTE_WRITE: bool = false;
+
+pub(crate) mod command;
+use self::command::Command;
+
+mod sequence;
+
+#[cfg(feature = "async")]
+mod asynch;
+
+/// Register waveforms of a [Panel]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LutSet {
+    /// Waveforms of [RefreshLut::Full] for [TemperatureBand::Cold], [TemperatureBand::Normal]
+    /// and [TemperatureBand::Hot]
+    pub full: [Uc8151Lut; 3],
+    /// Waveforms of [RefreshLut::Quick] for [TemperatureBand::Cold], [TemperatureBand::Normal]
+    /// and [TemperatureBand::Hot]
+    pub quick: [Uc8151Lut; 3],
+    /// Waveforms of the 4 grayscale mode, each transition LUT drives one of the gray levels
+    pub gray: Uc8151Lut,
+}
+
+impl LutSet {
+    /// Waveforms of the given refresh mode and temperature band
+    pub fn lut(&self, refresh: RefreshLut, band: TemperatureBand) -> &Uc8151Lut {
+        let luts = match refresh {
+            RefreshLut::Full => &self.full,
+            RefreshLut::Quick => &self.quick,
+        };
+        match band {
+            TemperatureBand::Cold => &luts[0],
+            TemperatureBand::Normal => &luts[1],
+            TemperatureBand::Hot => &luts[2],
+        }
+    }
+}
+
+/// Waveforms of the GDEW0213T5D, used by all presets
+///
+/// The full refresh drives the pixels to the opposite color and back, the quick refresh
+/// drives them directly to their new color. All temperature bands use the same waveforms,
+/// only the forced temperature of the OTP waveforms follows
+/// [set_temperature](ExternalTemperature::set_temperature).
+pub const DEFAULT_LUTS: LutSet = LutSet {
+    full: [
+        constants::FULL_LUT,
+        constants::FULL_LUT,
+        constants::FULL_LUT,
+    ],
+    quick: [
+        constants::DIRECT_LUT,
+        constants::DIRECT_LUT,
+        constants::DIRECT_LUT,
+    ],
+    gray: constants::GRAY_LUT,
+};
+
+/// Experimental: [DEFAULT_LUTS] with estimated waveforms for the cold and hot
+/// [TemperatureBand]s
+///
+/// Below 5 °C the frames of the phases are 1.5 times, above 35 °C 0.75 times as long as in
+/// the normal band. The panel manufacturer doesn't publish such waveforms and these haven't
+/// been tried on hardware, a [Panel] opts in by setting its [LUTS](Panel::LUTS) to them.
+pub const ESTIMATED_TEMPERATURE_LUTS: LutSet = LutSet {
+    full: [
+        constants::FULL_LUT_COLD,
+        constants::FULL_LUT,
+        constants::FULL_LUT_HOT,
+    ],
+    quick: [
+        constants::DIRECT_LUT_COLD,
+        constants::DIRECT_LUT,
+        constants::DIRECT_LUT_HOT,
+    ],
+    gray: constants::GRAY_LUT,
+};
+
+/// Resolution, busy polarity and waveforms of a display driven by [Uc8151]
+pub trait Panel {
+    /// Width of the display, a multiple of 8
+    const WIDTH: u32;
+    /// Height of the display
+    const HEIGHT: u32;
+    /// Whether the busy pin is low while the controller is busy
+    const IS_BUSY_LOW: bool = true;
+    /// Register waveforms
+    const LUTS: &'static LutSet = &DEFAULT_LUTS;
+}
+
+/// Driver for a [Panel] with an UC8151 controller
+pub struct Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P> {
+    /// Connection Interface
+    interface: DisplayInterface<SPI, CS, BUSY, DC, RST, DELAY, SINGLE_BYTE_WRITE>,
+    /// Background Color
+    color: Color,
+    /// Refresh LUT
+    refresh: RefreshLut,
+    /// Waveforms used for refreshes
+    lut_source: LutSource,
+    /// Temperature set with [ExternalTemperature::set_temperature]
+    temperature: Option<i8>,
+    panel: PhantomData<P>,
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> InternalWiAdditions<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    fn init(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run(spi, delay, &sequence::init::<P>(self.lut_source))?;
+        if let Some(celsius) = self.temperature {
+            self.run(spi, delay, &sequence::set_temperature(&[celsius as u8]))?;
+        }
+        self.set_lut(spi, None, delay)?;
+        self.wait_until_idle(delay)?;
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> Deinit<CS, BUSY, DC, RST>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+{
+    fn deinit(self) -> (CS, BUSY, DC, RST) {
+        self.interface.deinit()
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> PanelInfo for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    P: Panel,
+{
+    const WIDTH: u32 = P::WIDTH;
+    const HEIGHT: u32 = P::HEIGHT;
+    const COLOR_DEPTH: u8 = 1;
+    const IS_BUSY_LOW: bool = P::IS_BUSY_LOW;
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> WaveshareDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    type DisplayColor = Color;
+    fn new(
+        spi: &mut SPI,
+        cs: CS,
+        busy: BUSY,
+        dc: DC,
+        rst: RST,
+        delay: &mut DELAY,
+    ) -> Result<Self, DriverError<SPI, CS, BUSY, DC, RST>> {
+        let interface = DisplayInterface::new(cs, busy, dc, rst);
+        let color = DEFAULT_BACKGROUND_COLOR;
+
+        let mut epd = Uc8151 {
+            interface,
+            color,
+            refresh: RefreshLut::Quick,
+            lut_source: LutSource::Registers,
+            temperature: None,
+            panel: PhantomData,
+        };
+
+        epd.init(spi, delay)?;
+
+        Ok(epd)
+    }
+
+    fn wake_up(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.init(spi, delay)
+    }
+
+    fn sleep(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run(spi, delay, &sequence::sleep())
+    }
+
+    fn update_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let color_value = self.color.get_byte_value();
+        self.run(
+            spi,
+            delay,
+            &sequence::update_frame::<P>(color_value, buffer),
+        )
+    }
+
+    fn display_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run(spi, delay, &sequence::display_frame())
+    }
+
+    fn update_and_display_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        let color_value = self.color.get_byte_value();
+        self.run(
+            spi,
+            delay,
+            &sequence::update_and_display_frame::<P>(color_value, buffer),
+        )
+    }
+
+    fn clear_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let color_value = self.color.get_byte_value();
+        self.run(spi, delay, &sequence::clear_frame::<P>(color_value))
+    }
+
+    fn set_background_color(&mut self, color: Color) {
+        self.color = color;
+    }
+
+    fn background_color(&self) -> &Color {
+        &self.color
+    }
+
+    fn is_busy(&self) -> Result<bool, DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.is_busy(P::IS_BUSY_LOW)
+    }
+
+    fn set_busy_timeout(&mut self, timeout_ms: Option<u32>) {
+        self.interface.set_busy_timeout(timeout_ms);
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> PartialUpdate<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    fn update_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = sequence::partial_window(x, y, width, height);
+        self.run(spi, delay, &sequence::update_partial_frame(&window, buffer))
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> LutSelection<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    fn set_lut(
+        &mut self,
+        spi: &mut SPI,
+        refresh_rate: Option<RefreshLut>,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        if let Some(refresh_lut) = refresh_rate {
+            self.refresh = refresh_lut;
+        }
+        self.set_lut_helper(
+            spi,
+            P::LUTS.lut(self.refresh, self.temperature_band()),
+            delay,
+        )
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> StreamingFrame<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    fn begin_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let color_value = self.color.get_byte_value();
+        self.run(spi, delay, &sequence::begin_frame::<P>(color_value))
+    }
+
+    fn write_frame_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.data(spi, data)
+    }
+
+    fn end_frame(
+        &mut self,
+        _spi: &mut SPI,
+        _delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    fn send_data(
+        &mut self,
+        spi: &mut SPI,
+        data: &[u8],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.data(spi, data)
+    }
+
+    fn run(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        steps: &[Step<'_>],
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.run(spi, delay, P::IS_BUSY_LOW, steps)
+    }
+
+    fn wait_until_idle(
+        &mut self,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.interface.wait_until_idle(delay, P::IS_BUSY_LOW)
+    }
+
+    /// Sends one bit plane of a 2 bits per pixel `buffer`, built in chunks of [GRAY_CHUNK_LEN]
+    fn send_gray_plane(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        bit: u8,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let mut plane = [0u8; GRAY_CHUNK_LEN];
+        for pixels in buffer.chunks(2 * GRAY_CHUNK_LEN) {
+            let len = pixels.len().div_ceil(2);
+            for (byte, pixels) in plane.iter_mut().zip(pixels.chunks(2)) {
+                *byte = gray_plane_byte(pixels, bit);
+            }
+            self.send_data(spi, &plane[..len])?;
+        }
+        Ok(())
+    }
+
+    fn set_lut_helper(
+        &mut self,
+        spi: &mut SPI,
+        lut: &Uc8151Lut,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.run(spi, delay, &sequence::set_lut(lut))
+    }
+
+    /// Uploads a user waveform into the LUT registers and refreshes with it from now on
+    ///
+    /// The waveform is refused with [EpdError::Lut] if it leaves a DC bias beyond the
+    /// [default limits](LutLimits::default), see
+    /// [set_custom_lut_with_limits](Uc8151::set_custom_lut_with_limits).
+    ///
+    /// The waveform is replaced by the next [set_lut](LutSelection::set_lut), grayscale
+    /// frame or [wake_up](WaveshareDisplay::wake_up), as the registers don't survive deep sleep.
+    pub fn set_custom_lut(
+        &mut self,
+        spi: &mut SPI,
+        lut: &Uc8151Lut,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.set_custom_lut_with_limits(spi, lut, &LutLimits::default(), delay)
+    }
+
+    /// [set_custom_lut](Uc8151::set_custom_lut) with own limits for the DC bias and the
+    /// refresh duration
+    pub fn set_custom_lut_with_limits(
+        &mut self,
+        spi: &mut SPI,
+        lut: &Uc8151Lut,
+        limits: &LutLimits,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let analysis = Lut::uc8151(lut).validate(limits).map_err(EpdError::Lut)?;
+        debug!("Custom LUT: {} frames", analysis.frames());
+        self.set_lut_helper(spi, lut, delay)?;
+        self.set_lut_source(spi, LutSource::Registers, delay)
+    }
+
+    /// Selects whether refreshes use the uploaded waveforms or the ones in the OTP memory
+    ///
+    /// The source is kept across [wake_up](WaveshareDisplay::wake_up). The LUT registers keep
+    /// their content while the OTP waveforms are used.
+    pub fn set_lut_source(
+        &mut self,
+        spi: &mut SPI,
+        source: LutSource,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.lut_source = source;
+        self.run(spi, delay, &sequence::set_lut_source(source))
+    }
+
+    /// Helper function. Sets up the display to send pixel data to a custom
+    /// starting point.
+    ///
+    /// Only sends the data of `PartialWindow`, the caller sends the command before.
+    pub fn shift_display(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let window = sequence::partial_window(x, y, width, height);
+        self.interface.data(spi, &window)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P> {
+    /// Source of the waveforms used for refreshes
+    pub fn lut_source(&self) -> LutSource {
+        self.lut_source
+    }
+
+    /// Band of the temperature set with [ExternalTemperature::set_temperature],
+    /// [TemperatureBand::Normal] without one
+    pub fn temperature_band(&self) -> TemperatureBand {
+        self.temperature
+            .map_or(TemperatureBand::Normal, TemperatureBand::from_celsius)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> QuickRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// To be followed immediately after by `update_old_frame`.
+    fn update_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        self.run(spi, delay, &sequence::update_old_or_new_frame(true, buffer))
+    }
+
+    /// To be used immediately after `update_old_frame`.
+    fn update_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+
+        self.run(
+            spi,
+            delay,
+            &sequence::update_old_or_new_frame(false, buffer),
+        )
+    }
+
+    /// This is a wrapper around `display_frame` for using this device as a true
+    /// `QuickRefresh` device.
+    fn display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.display_frame(spi, delay)
+    }
+
+    /// This is wrapper around `update_new_frame` and `display_frame` for using
+    /// this device as a true `QuickRefresh` device.
+    ///
+    /// To be used immediately after `update_old_frame`.
+    fn update_and_display_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.update_new_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)
+    }
+
+    fn update_partial_old_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = sequence::partial_window(x, y, width, height);
+        self.run(
+            spi,
+            delay,
+            &sequence::update_partial_old_frame(&window, buffer),
+        )
+    }
+
+    /// Always call `update_partial_old_frame` before this, with buffer-updating code
+    /// between the calls.
+    fn update_partial_new_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+        EpdError::check_buffer_size(buffer_len(width as usize, height as usize), buffer.len())?;
+
+        let window = sequence::partial_window(x, y, width, height);
+        self.run(
+            spi,
+            delay,
+            &sequence::update_partial_new_frame(&window, buffer),
+        )
+    }
+
+    fn clear_partial_frame(
+        &mut self,
+        spi: &mut SPI,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_window(x, y, width, height, P::WIDTH, P::HEIGHT)?;
+
+        let color_value = self.color.get_byte_value();
+        let window = sequence::partial_window(x, y, width, height);
+        let bytes = buffer_len(width as usize, height as usize) as u32;
+        let steps = sequence::clear_partial_frame::<P>(&window, color_value, bytes);
+        self.run(spi, delay, &steps)
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> FullRefresh<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> GrayscaleDisplay<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// The grayscale waveforms only exist as register LUTs. With [LutSource::Otp] the panel is
+    /// switched to the registers for the frame, [lut_source](Uc8151::lut_source) stays unchanged
+    /// and [update_and_display_gray_frame](GrayscaleDisplay::update_and_display_gray_frame)
+    /// switches back to the OTP waveforms.
+    fn update_gray_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        EpdError::check_buffer_size(
+            gray_buffer_len(P::WIDTH as usize, P::HEIGHT as usize),
+            buffer.len(),
+        )?;
+        self.set_lut_helper(spi, &P::LUTS.gray, delay)?;
+        if self.lut_source == LutSource::Otp {
+            self.run(spi, delay, &sequence::set_lut_source(LutSource::Registers))?;
+        }
+
+        // The high bits of the pixels are sent as old data and the low bits as new data
+        self.interface.cmd(spi, Command::DataStartTransmission1)?;
+        self.send_gray_plane(spi, buffer, 1)?;
+
+        self.interface.cmd(spi, Command::DataStartTransmission2)?;
+        self.send_gray_plane(spi, buffer, 0)
+    }
+
+    fn update_and_display_gray_frame(
+        &mut self,
+        spi: &mut SPI,
+        buffer: &[u8],
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        self.update_gray_frame(spi, buffer, delay)?;
+        self.display_frame(spi, delay)?;
+        self.set_lut(spi, None, delay)?;
+        if self.lut_source == LutSource::Otp {
+            self.run(spi, delay, &sequence::set_lut_source(LutSource::Otp))?;
+        }
+        Ok(())
+    }
+}
+
+impl<SPI, CS, BUSY, DC, RST, DELAY, P> ExternalTemperature<SPI, CS, BUSY, DC, RST, DELAY>
+    for Uc8151<SPI, CS, BUSY, DC, RST, DELAY, P>
+where
+    SPI: Write<u8>,
+    CS: OutputPin,
+    BUSY: InputPin,
+    DC: OutputPin,
+    RST: OutputPin,
+    DELAY: DelayMs<u8>,
+    P: Panel,
+{
+    /// Forces the temperature of the controller (`CascadeSetting` TSFIX and `ForceTemperature`)
+    ///
+    /// If the [TemperatureBand] changes, the register waveforms of the current [RefreshLut]
+    /// for the new band are uploaded, replacing a [custom LUT](Uc8151::set_custom_lut).
+    fn set_temperature(
+        &mut self,
+        spi: &mut SPI,
+        celsius: i8,
+        delay: &mut DELAY,
+    ) -> Result<(), DriverError<SPI, CS, BUSY, DC, RST>> {
+        let band = self.temperature_band();
+        self.temperature = Some(celsius);
+        self.run(spi, delay, &sequence::set_temperature(&[celsius as u8]))?;
+        if self.temperature_band() != band {
+            self.set_lut(spi, None, delay)?;
+        }
+        Ok(())
+    }
+}
+
+/// Bytes of a bit plane built on the stack before they are sent
+const GRAY_CHUNK_LEN: usize = 256;
+
+/// Extracts one bit plane byte out of two bytes of a 2 bits per pixel buffer.
+///
+/// `bit` selects the high (1) or low (0) bit of each pixel.
+fn gray_plane_byte(pixels: &[u8], bit: u8) -> u8 {
+    let mut plane = 0u8;
+    for (i, &byte) in pixels.iter().enumerate() {
+        for p in 0..4 {
+            let value = (byte >> (6 - 2 * p + bit)) & 0x01;
+            plane |= value << (7 - (i as u8 * 4 + p));
+        }
+    }
+    plane
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::{Plane, SimulatedPanel};
+
+    #[test]
+    fn gray_planes() {
+        use crate::color::QuadColor;
+        // white, light gray, dark gray, black | black, black, white, white
+        let pixels = [0b1110_0100, 0b0000_1111];
+        assert_eq!(gray_plane_byte(&pixels, 1), 0b1100_0011);
+        assert_eq!(gray_plane_byte(&pixels, 0), 0b1010_0011);
+
+        let white = [QuadColor::White.get_byte_value(); 2];
+        assert_eq!(gray_plane_byte(&white, 1), Color::White.get_byte_value());
+        assert_eq!(gray_plane_byte(&white, 0), Color::White.get_byte_value());
+    }
+
+    #[test]
+    fn temperature_bands() {
+        use crate::lut::{Lut, LutLimits};
+
+        let bands = [
+            TemperatureBand::Cold,
+            TemperatureBand::Normal,
+            TemperatureBand::Hot,
+        ];
+        for band in bands {
+            for refresh in [RefreshLut::Full, RefreshLut::Quick] {
+                let normal = DEFAULT_LUTS.lut(refresh, TemperatureBand::Normal);
+                assert_eq!(DEFAULT_LUTS.lut(refresh, band), normal);
+            }
+        }
+        let frames = bands.map(|band| {
+            let full = Lut::uc8151(ESTIMATED_TEMPERATURE_LUTS.lut(RefreshLut::Full, band));
+            let quick = Lut::uc8151(ESTIMATED_TEMPERATURE_LUTS.lut(RefreshLut::Quick, band));
+            quick.validate(&LutLimits::default()).unwrap();
+            full.validate(&LutLimits::default()).unwrap().frames()
+        });
+        assert!(frames[0] > frames[1] && frames[1] > frames[2]);
+        assert_eq!(
+            constants::FULL_LUT_COLD.group(Waveform::WhiteToWhite, 1).0[1],
+            Phase::new(Level::High, 0x23)
+        );
+    }
+
+    #[test]
+    fn temperature_on_panel() {
+        use crate::epd2in13_GDEW0213T5D::*;
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13_T5D<_, _, _, _, _, _>>();
+        let quick = Uc8151Lut::builtin(RefreshLut::Quick);
+        assert_eq!(panel.temperature(), Some(25));
+
+        epd.set_temperature(&mut spi, 20, &mut delay).unwrap();
+        assert_eq!(panel.temperature(), Some(20));
+        assert_eq!(panel.lut(0x21).unwrap(), quick.ww);
+
+        // Only the forced temperature changes, the waveforms stay the ones of the normal band
+        epd.set_temperature(&mut spi, -10, &mut delay).unwrap();
+        assert_eq!(epd.temperature_band(), TemperatureBand::Cold);
+        assert_eq!(panel.temperature(), Some(-10));
+        assert_eq!(panel.lut(0x21).unwrap(), quick.ww);
+
+        // Kept across deep sleep
+        epd.sleep(&mut spi, &mut delay).unwrap();
+        epd.wake_up(&mut spi, &mut delay).unwrap();
+        assert_eq!(panel.temperature(), Some(-10));
+        assert_eq!(panel.violations(), []);
+    }
+
+    #[test]
+    fn estimated_temperature_luts_on_panel() {
+        use crate::epd2in13_GDEW0213T5D::{HEIGHT, WIDTH};
+
+        struct Estimated;
+        impl Panel for Estimated {
+            const WIDTH: u32 = WIDTH;
+            const HEIGHT: u32 = HEIGHT;
+            const LUTS: &'static LutSet = &ESTIMATED_TEMPERATURE_LUTS;
+        }
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Uc8151<_, _, _, _, _, _, Estimated>>();
+        let quick = Uc8151Lut::builtin(RefreshLut::Quick);
+
+        epd.set_temperature(&mut spi, -10, &mut delay).unwrap();
+        let (phases, _) = quick.group(Waveform::WhiteToWhite, 0);
+        let cold = panel.lut(0x21).unwrap();
+        assert_eq!(cold[1], phases[0].frames * 3 / 2);
+
+        epd.sleep(&mut spi, &mut delay).unwrap();
+        epd.wake_up(&mut spi, &mut delay).unwrap();
+        assert_eq!(panel.lut(0x21).unwrap(), cold);
+        assert_eq!(panel.violations(), []);
+    }
+
+    #[test]
+    fn rejects_wrong_buffers_and_windows() {
+        use crate::epd2in13_GDEW0213T5D::{Epd2in13_T5D, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13_T5D<_, _, _, _, _, _>>();
+        let bytes_sent = panel.bytes_sent();
+
+        let frame_len = buffer_len(WIDTH as usize, HEIGHT as usize);
+        let short = [0xFF; 8];
+        let expected = Err(EpdError::BufferSize {
+            expected: frame_len,
+            actual: short.len(),
+        });
+        assert_eq!(epd.update_frame(&mut spi, &short, &mut delay), expected);
+        assert_eq!(epd.update_old_frame(&mut spi, &short, &mut delay), expected);
+        assert_eq!(epd.update_new_frame(&mut spi, &short, &mut delay), expected);
+        assert_eq!(
+            epd.clear_partial_frame(&mut spi, 4, 0, 16, 16, &mut delay),
+            Err(EpdError::WindowAlignment)
+        );
+        assert_eq!(
+            epd.clear_partial_frame(&mut spi, 96, 0, 16, 16, &mut delay),
+            Err(EpdError::WindowAlignment)
+        );
+        assert_eq!(panel.bytes_sent(), bytes_sent);
+
+        let short = [0xFF; 8];
+        assert_eq!(
+            epd.update_gray_frame(&mut spi, &short, &mut delay),
+            Err(EpdError::BufferSize {
+                expected: gray_buffer_len(WIDTH as usize, HEIGHT as usize),
+                actual: short.len(),
+            })
+        );
+        assert_eq!(panel.bytes_sent(), bytes_sent);
+    }
+
+    #[test]
+    fn gray_frame() {
+        use crate::epd2in13_GDEW0213T5D::{Epd2in13_T5D, HEIGHT, WIDTH};
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13_T5D<_, _, _, _, _, _>>();
+        epd.set_lut_source(&mut spi, LutSource::Otp, &mut delay)
+            .unwrap();
+        assert_eq!(panel.lut(0x20), None);
+
+        let mut buffer = [0u8; gray_buffer_len(WIDTH as usize, HEIGHT as usize)];
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            *byte = (i * 7) as u8;
+        }
+        epd.update_gray_frame(&mut spi, &buffer, &mut delay)
+            .unwrap();
+        let plane = |bit| {
+            buffer
+                .chunks(2)
+                .map(|pixels| gray_plane_byte(pixels, bit))
+                .collect::<std::vec::Vec<_>>()
+        };
+        assert_eq!(panel.ram(Plane::Old), plane(1));
+        assert_eq!(panel.ram(Plane::New), plane(0));
+        assert_eq!(panel.lut(0x20).unwrap(), constants::GRAY_LUT.vcom0);
+        assert_eq!(epd.lut_source(), LutSource::Otp);
+
+        epd.update_and_display_gray_frame(&mut spi, &buffer, &mut delay)
+            .unwrap();
+        assert_eq!(panel.lut(0x20), None);
+        assert_eq!(epd.lut_source(), LutSource::Otp);
+        assert!(panel.violations().is_empty());
+    }
+
+    #[test]
+    fn custom_lut_on_panel() {
+        use crate::epd2in13_GDEW0213T5D::*;
+        use crate::error::EpdError;
+        use crate::lut::LutError;
+
+        let panel = SimulatedPanel::uc8151(WIDTH, HEIGHT);
+        let (mut epd, mut spi, mut delay) = panel.connect::<Epd2in13_T5D<_, _, _, _, _, _>>();
+        let quick = Uc8151Lut::builtin(RefreshLut::Quick);
+        assert_eq!(panel.lut(0x20).unwrap(), quick.vcom0);
+
+        let lut = Uc8151Lut::new()
+            .with_timing(0, [0x20, 0x10, 0, 0], 3)
+            .with_levels(
+                Waveform::WhiteToBlack,
+                0,
+                [Level::High, Level::Low, Level::Ground, Level::Ground],
+            );
+        // Pixels turning black are driven by 48 frames more into one direction
+        assert_eq!(
+            epd.set_custom_lut(&mut spi, &lut, &mut delay),
+            Err(EpdError::Lut(LutError::CycleBias { bias: 48 }))
+        );
+        assert_eq!(panel.lut(0x23).unwrap(), quick.wb);
+
+        let lut = lut.with_levels(
+            Waveform::BlackToWhite,
+            0,
+            [Level::Low, Level::High, Level::Ground, Level::Ground],
+        );
+        epd.set_custom_lut(&mut spi, &lut, &mut delay).unwrap();
+        assert_eq!(panel.lut(0x20).unwrap(), lut.vcom0);
+        assert_eq!(panel.lut(0x21).unwrap(), lut.ww);
+        assert_eq!(panel.lut(0x22).unwrap(), lut.bw);
+        assert_eq!(panel.lut(0x23).unwrap(), lut.wb);
+        assert_eq!(panel.lut(0x24).unwrap(), lut.bb);
+
+        epd.set_lut_source(&mut spi, LutSource::Otp, &mut delay)
+            .unwrap();
+        assert_eq!(panel.lut(0x23), None);
+        epd.sleep(&mut spi, &mut delay).unwrap();
+        epd.wake_up(&mut spi, &mut delay).unwrap();
+        assert_eq!(epd.lut_source(), LutSource::Otp);
+        assert_eq!(panel.lut(0x23), None);
+
+        epd.set_lut_source(&mut spi, LutSource::Registers, &mut delay)
+            .unwrap();
+        assert_eq!(panel.lut(0x23).unwrap(), quick.wb);
+        assert_eq!(panel.violations(), []);
+    }
+}
@@ -0,0 +1,242 @@
+//! Command sequences of the UC8151, shared by the blocking and the async driver
+
+use super::command::Command;
+use super::lut::{LutSource, Uc8151Lut};
+use super::Panel;
+use crate::sequence::Step;
+
+/// Data of [Command::ResolutionSetting]
+const fn resolution(width: u32, height: u32) -> [u8; 3] {
+    [width as u8 & 0xf8, (height >> 8) as u8, height as u8]
+}
+
+/// Bytes of one full frame
+const fn frame_bytes<P: Panel>() -> u32 {
+    P::WIDTH / 8 * P::HEIGHT
+}
+
+pub(super) fn init<P: Panel>(source: LutSource) -> [Step<'static>; 20] {
+    [
+        // reset the device
+        Step::Reset(10),
+        Step::Reset(20),
+        Step::Reset(20),
+        // set the power settings
+        Step::cmd(Command::PowerSetting),
+        // &[0x03, 0x00, 0x2b, 0x2b, 0xff],
+        Step::Data(&[0x03, 0x00, 0x2b, 0x2b, 0x03]),
+        // start the booster
+        Step::cmd(Command::BoosterSoftStart),
+        Step::Data(&[0x17, 0x17, 0x17]),
+        // power on
+        Step::cmd(Command::PowerOn),
+        Step::Delay(5),
+        Step::WaitUntilIdle,
+        // set the panel settings
+        Step::cmd(Command::PanelSetting),
+        Step::Data(panel_setting(source)),
+        // Set Frequency, 200 Hz didn't work on my board
+        // 150Hz and 171Hz wasn't tested yet
+        // TODO: Test these other frequencies
+        // 0x3a -> 100Hz, 0x29 -> 150Hz, 0x39 -> 200Hz, 0x31 -> 171Hz, 0x3c -> 50Hz (default)
+        Step::cmd(Command::PllControl),
+        Step::Data(&[0x3a]),
+        Step::cmd(Command::ResolutionSetting),
+        Step::Data(&const { resolution(P::WIDTH, P::HEIGHT) }),
+        Step::cmd(Command::VcmDcSetting),
+        Step::Data(&[0x12]),
+        //VBDF 17|D7 VBDW 97  VBDB 57  VBDF F7  VBDW 77  VBDB 37  VBDR B7
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(&[0x97]),
+    ]
+}
+
+pub(super) fn sleep() -> [Step<'static>; 11] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::VcomAndDataIntervalSetting),
+        Step::Data(&[0x17]),              //border floating
+        Step::cmd(Command::VcmDcSetting), // VCOM to 0V
+        Step::cmd(Command::PanelSetting),
+        Step::cmd(Command::PowerSetting), //VG&VS to 0V fast
+        Step::DataRepeat(0x00, 4),
+        Step::cmd(Command::PowerOff),
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DeepSleep),
+        Step::Data(&[0xA5]),
+    ]
+}
+
+pub(super) fn update_frame<P: Panel>(color: u8, buffer: &[u8]) -> [Step<'_>; 5] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, frame_bytes::<P>()),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::Data(buffer),
+    ]
+}
+
+pub(super) fn begin_frame<P: Panel>(color: u8) -> [Step<'static>; 4] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, frame_bytes::<P>()),
+        Step::cmd(Command::DataStartTransmission2),
+    ]
+}
+
+pub(super) fn update_and_display_frame<P: Panel>(color: u8, buffer: &[u8]) -> [Step<'_>; 6] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, frame_bytes::<P>()),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::Data(buffer),
+        Step::cmd(Command::DisplayRefresh),
+    ]
+}
+
+pub(super) fn update_partial_frame<'a>(window: &'a [u8; 9], buffer: &'a [u8]) -> [Step<'a>; 7] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PartialIn),
+        Step::cmd(Command::PartialWindow),
+        Step::Data(window),
+        //TODO: check if data_start transmission 1 also needs "old"/background data here
+        Step::cmd(Command::DataStartTransmission2),
+        Step::Data(buffer),
+        Step::cmd(Command::PartialOut),
+    ]
+}
+
+pub(super) fn display_frame() -> [Step<'static>; 2] {
+    [Step::WaitUntilIdle, Step::cmd(Command::DisplayRefresh)]
+}
+
+pub(super) fn clear_frame<P: Panel>(color: u8) -> [Step<'static>; 7] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::ResolutionSetting),
+        Step::Data(&const { resolution(P::WIDTH, P::HEIGHT) }),
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, frame_bytes::<P>()),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::DataRepeat(color, frame_bytes::<P>()),
+    ]
+}
+
+pub(super) fn set_lut(lut: &Uc8151Lut) -> [Step<'_>; 11] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::LutForVcom),
+        Step::Data(&lut.vcom0),
+        Step::cmd(Command::LutWhiteToWhite),
+        Step::Data(&lut.ww),
+        Step::cmd(Command::LutBlackToWhite),
+        Step::Data(&lut.bw),
+        Step::cmd(Command::LutWhiteToBlack),
+        Step::Data(&lut.wb),
+        Step::cmd(Command::LutBlackToBlack),
+        Step::Data(&lut.bb),
+    ]
+}
+
+/// Data of [Command::PanelSetting]: B/W mode with the waveforms from `source`
+fn panel_setting(source: LutSource) -> &'static [u8] {
+    match source {
+        LutSource::Otp => &[0x1F],
+        LutSource::Registers => &[0x3F],
+    }
+}
+
+pub(super) fn set_lut_source(source: LutSource) -> [Step<'static>; 3] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PanelSetting),
+        Step::Data(panel_setting(source)),
+    ]
+}
+
+/// Makes the controller use `celsius` (one byte) instead of its sensor
+pub(super) fn set_temperature(celsius: &[u8]) -> [Step<'_>; 5] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::CascadeSetting),
+        Step::Data(&[0x02]),
+        Step::cmd(Command::ForceTemperature),
+        Step::Data(celsius),
+    ]
+}
+
+/// Sends `buffer` as old (DTM1) or new (DTM2) frame
+pub(super) fn update_old_or_new_frame(old: bool, buffer: &[u8]) -> [Step<'_>; 3] {
+    let command = if old {
+        Command::DataStartTransmission1
+    } else {
+        Command::DataStartTransmission2
+    };
+    [Step::WaitUntilIdle, Step::cmd(command), Step::Data(buffer)]
+}
+
+pub(super) fn update_partial_old_frame<'a>(window: &'a [u8; 9], buffer: &'a [u8]) -> [Step<'a>; 6] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PartialIn),
+        Step::cmd(Command::PartialWindow),
+        Step::Data(window),
+        Step::cmd(Command::DataStartTransmission1),
+        Step::Data(buffer),
+    ]
+}
+
+pub(super) fn update_partial_new_frame<'a>(window: &'a [u8; 9], buffer: &'a [u8]) -> [Step<'a>; 7] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::PartialIn),
+        Step::cmd(Command::PartialWindow),
+        Step::Data(window),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::Data(buffer),
+        Step::cmd(Command::PartialOut),
+    ]
+}
+
+pub(super) fn clear_partial_frame<P: Panel>(
+    window: &[u8; 9],
+    color: u8,
+    bytes: u32,
+) -> [Step<'_>; 11] {
+    [
+        Step::WaitUntilIdle,
+        Step::cmd(Command::ResolutionSetting),
+        Step::Data(&const { resolution(P::WIDTH, P::HEIGHT) }),
+        Step::cmd(Command::PartialIn),
+        Step::cmd(Command::PartialWindow),
+        Step::Data(window),
+        Step::cmd(Command::DataStartTransmission1),
+        Step::DataRepeat(color, bytes),
+        Step::cmd(Command::DataStartTransmission2),
+        Step::DataRepeat(color, bytes),
+        Step::cmd(Command::PartialOut),
+    ]
+}
+
+/// Data of [Command::PartialWindow] for the given window
+pub(super) fn partial_window(x: u32, y: u32, width: u32, height: u32) -> [u8; 9] {
+    // x should be the multiple of 8, the last 3 bit will always be ignored
+    let x_start = x & 0xf8;
+    let x_end = x_start + width - 1;
+    let y_end = y + height - 1;
+    [
+        (x >> 8) as u8,
+        x_start as u8,
+        (x_end >> 8) as u8,
+        (x_end | 0x07) as u8,
+        (y >> 8) as u8,
+        y as u8,
+        (y_end >> 8) as u8,
+        y_end as u8,
+        0x01, // Gates scan both inside and outside of the partial window. (default)
+    ]
+}